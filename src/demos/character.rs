@@ -0,0 +1,136 @@
+use glam::{Quat, Vec3};
+use winit::keyboard::KeyCode;
+
+use crate::engine::context::{Camera3D, Context, ContextBuilder};
+use crate::engine::EventHandler;
+use crate::engine::physics::{BodyHandle, CharacterController, Collider, RigidBody, Shape};
+
+/// Walking speed && the upward speed a jump leaves the ground with;
+const WALK_SPEED: f32 = 4.0;
+const JUMP_SPEED: f32 = 5.0;
+
+/// The platform shuttles between the two landings, this far either side of the middle;
+const PLATFORM_REACH: f32 = 3.0;
+const PLATFORM_SPEED: f32 = 1.5;
+
+/// Stairs the character walks up, each step a bit lower than its step height;
+const STEPS: usize = 5;
+const STEP_RISE: f32 = 0.2;
+const STEP_RUN: f32 = 0.5;
+
+/// Camera trails the character from above && behind;
+const CAMERA_OFFSET: Vec3 = Vec3::new(0.0, 4.0, 8.0);
+
+/// Capsule walked around a yard of stairs, ramps && crates, a platform ferries it between two landings;
+/// WASD or the arrow keys walk, space jumps;
+/// Run with `character` as the first argument;
+pub struct Character {
+    controller: CharacterController,
+    platform: BodyHandle,
+    /// Vertical speed, kept by the demo since the controller only moves where it's told;
+    rise: f32,
+}
+
+impl Character {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: character", "Delfi")
+    }
+
+    /// Unit walking direction from the held keys, forward is away from the camera;
+    fn heading(context: &Context) -> Vec3 {
+        let input = context.input();
+        let held = |keys: [KeyCode; 2]| keys.iter().any(|key| input.is_key_pressed(*key));
+
+        let mut heading = Vec3::ZERO;
+        for (keys, direction) in [
+            ([KeyCode::KeyW, KeyCode::ArrowUp], Vec3::NEG_Z),
+            ([KeyCode::KeyS, KeyCode::ArrowDown], Vec3::Z),
+            ([KeyCode::KeyA, KeyCode::ArrowLeft], Vec3::NEG_X),
+            ([KeyCode::KeyD, KeyCode::ArrowRight], Vec3::X),
+        ] {
+            if held(keys) {
+                heading += direction;
+            }
+        }
+
+        heading.normalize_or_zero()
+    }
+}
+
+impl EventHandler for Character {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+
+        // Stairs up to the first landing, the platform leaves from its side;
+        for step in 0..STEPS {
+            let height = STEP_RISE * (step + 1) as f32;
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new(3.25 + STEP_RUN * step as f32, height * 0.5, -PLATFORM_REACH))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(STEP_RUN * 0.5, height * 0.5, 1.0)))));
+        }
+        for z in [-PLATFORM_REACH, PLATFORM_REACH] {
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new(6.5, 0.5, z))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(1.0, 0.5, 1.0)))));
+        }
+        let platform = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::new(8.5, 0.9, 0.0))
+            .with_linear_velocity(Vec3::NEG_Z * PLATFORM_SPEED)
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(1.0, 0.1, 1.0)))));
+
+        // A ramp gentle enough to walk up && one too steep, both rising away from the stairs;
+        for (angle, z) in [(0.44_f32, 2.0), (0.96, -1.0)] {
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new(-2.0 - 2.5 * angle.cos(), 2.5 * angle.sin(), z))
+                .with_orientation(Quat::from_rotation_z(-angle))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(2.5, 0.05, 1.0)))));
+        }
+
+        // Crates to shove around && climb onto;
+        for position in [Vec3::new(0.5, 0.4, 3.0), Vec3::new(1.5, 0.4, 3.5), Vec3::new(1.0, 1.2, 3.2)] {
+            world.add_body(RigidBody::new()
+                .with_position(position)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.4))))
+                .with_density(150.0));
+        }
+
+        let body = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::Y * 0.9)
+            .with_collider(Collider::new(Shape::capsule(0.5, 0.35))));
+        let controller = CharacterController::new(body).with_step_height(0.3);
+
+        *context.camera3d_mut() = Camera3D::new(Vec3::Y * 0.9 + CAMERA_OFFSET, Vec3::Y * 0.9);
+
+        Self { controller, platform, rise: 0.0 }
+    }
+
+    fn on_fixed_update(&mut self, context: &mut Context) {
+        let dt = context.time().step_delta().as_secs_f32();
+        let heading = Self::heading(context);
+        let jump = context.input().is_key_pressed(KeyCode::Space);
+
+        // Turned back at either landing, before the character reads how its ground moves;
+        if let Some(platform) = context.physics_mut().body_mut(self.platform) {
+            let z = platform.position().z;
+            if z.abs() > PLATFORM_REACH {
+                platform.set_linear_velocity(Vec3::Z * -z.signum() * PLATFORM_SPEED);
+            }
+        }
+
+        let gravity = context.physics().gravity().y;
+        self.rise = match self.controller.is_grounded() {
+            true if jump => JUMP_SPEED,
+            true => 0.0,
+            false => self.rise + gravity * dt
+        };
+
+        let world = context.physics_mut();
+        let moved = self.controller.move_by(world, (heading * WALK_SPEED + Vec3::Y * self.rise) * dt, dt);
+        let Some(position) = world.body(self.controller.body()).map(|body| body.position() + moved) else { return };
+
+        let camera = context.camera3d_mut();
+        camera.set_position(position + CAMERA_OFFSET);
+        camera.set_target(position);
+    }
+}
@@ -0,0 +1,153 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{Quat, Vec3};
+
+use crate::engine::context::{Camera3D, Context, ContextBuilder};
+use crate::engine::EventHandler;
+use crate::engine::physics::{BodyHandle, Cloth, ClothHandle, Collider, RigidBody, Shape};
+
+/// Steady breeze the flag's gusts swing around;
+const BREEZE: Vec3 = Vec3::new(6.0, 0.0, 1.0);
+const GUST: Vec3 = Vec3::new(3.0, 0.0, 2.5);
+
+/// Ball sweeping through the curtain, back && forth;
+const BALL_SPEED: f32 = 1.2;
+const BALL_TRAVEL: f32 = 1.5;
+
+/// Floor every cloth scene stands on;
+fn floor() -> RigidBody {
+    RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0)))
+}
+
+/// Flag pinned along a pole, waving in gusting wind;
+/// Run with `flag` as the first argument;
+pub struct Flag {
+    cloth: ClothHandle,
+    elapsed: f32,
+}
+
+impl Flag {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: flag", "Delfi")
+    }
+}
+
+impl EventHandler for Flag {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(floor());
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::Y * 1.5)
+            .with_collider(Collider::new(Shape::cylinder(1.5, 0.04))));
+
+        let mut cloth = Cloth::new(Vec3::new(0.06, 2.9, 0.0), Vec3::X * 1.6, Vec3::NEG_Y * 1.0, 24, 15)
+            .with_mass(0.3)
+            .with_wind(BREEZE);
+        for row in 0..cloth.rows() {
+            cloth.pin(cloth.index(0, row));
+        }
+
+        let cloth = world.add_cloth(cloth);
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(1.0, 2.4, 4.0), Vec3::new(0.8, 2.2, 0.0));
+
+        Self { cloth, elapsed: 0.0 }
+    }
+
+    fn on_fixed_update(&mut self, context: &mut Context) {
+        self.elapsed += context.time().step_delta().as_secs_f32();
+
+        // Two detuned waves, never quite repeating;
+        let gust = (self.elapsed * 1.3).sin() * 0.6 + (self.elapsed * 3.1).sin() * 0.4;
+        if let Some(cloth) = context.physics_mut().cloth_mut(self.cloth) {
+            cloth.set_wind(BREEZE + GUST * gust);
+        }
+    }
+}
+
+/// Curtain hanging from a rail, a kinematic ball keeps pushing through it;
+/// Run with `curtain` as the first argument;
+pub struct Curtain {
+    ball: BodyHandle,
+}
+
+impl Curtain {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: curtain", "Delfi")
+    }
+}
+
+impl EventHandler for Curtain {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(floor());
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::Y * 2.6)
+            .with_orientation(Quat::from_rotation_z(FRAC_PI_2))
+            .with_collider(Collider::new(Shape::capsule(1.2, 0.03))));
+
+        let ball = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::new(0.0, 1.2, -BALL_TRAVEL))
+            .with_linear_velocity(Vec3::Z * BALL_SPEED)
+            .with_collider(Collider::new(Shape::sphere(0.35))));
+
+        // Rings every other particle along the top, the cloth sags between them;
+        let mut cloth = Cloth::new(Vec3::new(-1.0, 2.5, 0.0), Vec3::X * 2.0, Vec3::NEG_Y * 2.3, 31, 35)
+            .with_mass(1.5)
+            .with_thickness(0.03);
+        for column in (0..cloth.columns()).step_by(2) {
+            cloth.pin(cloth.index(column, 0));
+        }
+
+        world.add_cloth(cloth);
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(2.5, 1.8, 3.5), Vec3::new(0.0, 1.3, 0.0));
+
+        Self { ball }
+    }
+
+    fn on_fixed_update(&mut self, context: &mut Context) {
+        let Some(ball) = context.physics_mut().body_mut(self.ball) else { return };
+
+        // Turn around at either end of the track;
+        let (z, speed) = (ball.position().z, ball.linear_velocity().z);
+        if (z > BALL_TRAVEL && speed > 0.0) || (z < -BALL_TRAVEL && speed < 0.0) {
+            ball.set_linear_velocity(Vec3::Z * -speed);
+        }
+    }
+}
+
+/// Square cloth dropped onto a table, draping over its edges;
+/// Run with `tablecloth` as the first argument;
+pub struct Tablecloth {}
+
+impl Tablecloth {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: tablecloth", "Delfi")
+    }
+}
+
+impl EventHandler for Tablecloth {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(floor());
+
+        // Top 1.6 by 1 meters at 0.8 high, a leg under each corner;
+        let (half_top, height) = (Vec3::new(0.8, 0.03, 0.5), 0.8);
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::Y * (height - half_top.y))
+            .with_collider(Collider::new(Shape::cuboid(half_top))));
+        for (x, z) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new((half_top.x - 0.08) * x, (height - half_top.y * 2.0) * 0.5, (half_top.z - 0.08) * z))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(0.04, (height - half_top.y * 2.0) * 0.5, 0.04)))));
+        }
+
+        let cloth = Cloth::new(Vec3::new(-1.1, 1.3, -0.8), Vec3::X * 2.2, Vec3::Z * 1.6, 33, 25)
+            .with_mass(0.8)
+            .with_friction(0.5);
+        world.add_cloth(cloth);
+
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(1.8, 2.0, 2.6), Vec3::new(0.0, 0.6, 0.0));
+
+        Self {}
+    }
+}
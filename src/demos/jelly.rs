@@ -0,0 +1,48 @@
+use glam::{Quat, Vec3};
+
+use crate::engine::context::{Camera3D, Context, ContextBuilder};
+use crate::engine::EventHandler;
+use crate::engine::physics::{Collider, RigidBody, Shape, SoftBody};
+
+/// Soft balls of rising stiffness bouncing down a ramp, a crate dropped onto a jelly cube;
+/// Run with `jelly` as the first argument;
+pub struct Jelly {}
+
+impl Jelly {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: jelly", "Delfi")
+    }
+}
+
+impl EventHandler for Jelly {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::new(-1.5, 0.8, 0.0))
+            .with_orientation(Quat::from_rotation_z(-0.35))
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(1.4, 0.05, 1.2)))));
+
+        // Floppy to firm, each keeps its volume as it squashes;
+        for (i, compliance) in [1e-2, 1e-3, 1e-4].into_iter().enumerate() {
+            world.add_soft_body(SoftBody::ball(Vec3::new(-2.4, 2.4 + i as f32 * 0.9, -0.7 + i as f32 * 0.7), 0.3, 2)
+                .with_mass(1.0)
+                .with_edge_compliance(compliance));
+        }
+
+        // Shape matching pulls the cube back square once the crate settles on it;
+        world.add_soft_body(SoftBody::cuboid(Vec3::new(1.5, 0.45, 0.0), Vec3::splat(0.45), 5)
+            .with_mass(2.0)
+            .with_edge_compliance(1e-2)
+            .with_shape_matching(1e-3));
+        world.add_body(RigidBody::new()
+            .with_position(Vec3::new(1.5, 2.5, 0.0))
+            .with_orientation(Quat::from_rotation_y(0.4))
+            .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.25))))
+            .with_density(12.0));
+
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(0.5, 2.5, 6.0), Vec3::new(0.0, 0.8, 0.0));
+
+        Self {}
+    }
+}
@@ -0,0 +1,11 @@
+pub use character::Character;
+pub use cloth::{Curtain, Flag, Tablecloth};
+pub use jelly::Jelly;
+pub use pouring::Pouring;
+pub use rope::{Bridge, Crane, Pendulums};
+
+mod character;
+mod cloth;
+mod jelly;
+mod pouring;
+mod rope;
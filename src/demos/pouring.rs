@@ -0,0 +1,93 @@
+use glam::Vec3;
+
+use crate::engine::context::{Camera3D, Context, ContextBuilder};
+use crate::engine::EventHandler;
+use crate::engine::physics::{Collider, FluidHandle, RigidBody, Shape, SphFluid};
+
+/// Distance between fluid particles;
+const SPACING: f32 = 0.1;
+
+/// Particles poured before the nozzle closes;
+const MAX_PARTICLES: usize = 1200;
+
+/// Nozzle position && the velocity liquid leaves it with;
+const NOZZLE: Vec3 = Vec3::new(-1.6, 2.6, 0.0);
+const JET: Vec3 = Vec3::new(2.0, 0.0, 0.0);
+
+/// Height below which lost particles are removed;
+const FLOOR: f32 = -2.0;
+
+/// Layers of the jet's square cross section;
+const JET_WIDTH: usize = 3;
+
+/// Liquid poured from a nozzle onto a ledge && into a tank, seen from the side;
+/// Everything sits between two glass panes, the camera looks through the front one;
+/// Run with `pouring` as the first argument;
+pub struct Pouring {
+    fluid: FluidHandle,
+    /// Jet length since the last emitted layer;
+    travelled: f32,
+}
+
+impl Pouring {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: pouring", "Delfi")
+    }
+
+    /// Static box given by its center && half extents;
+    fn wall(center: Vec3, half_extents: Vec3) -> RigidBody {
+        RigidBody::fixed()
+            .with_position(center)
+            .with_collider(Collider::new(Shape::cuboid(half_extents)))
+    }
+}
+
+impl EventHandler for Pouring {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+
+        // Tank two meters wide, open at the top;
+        let (width, depth, height, thickness) = (1.0, 0.25, 0.8, 0.05);
+        world.add_body(Self::wall(Vec3::Y * -thickness, Vec3::new(width + thickness * 2.0, thickness, depth)));
+        for side in [-1.0, 1.0] {
+            world.add_body(Self::wall(Vec3::new((width + thickness) * side, height, 0.0), Vec3::new(thickness, height, depth)));
+
+            // Glass panes, half a meter apart;
+            world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Z * side, -depth))));
+        }
+
+        // Ledge the jet splashes on before spilling into the tank, closed behind the nozzle;
+        world.add_body(Self::wall(Vec3::new(-1.2, 2.0, 0.0), Vec3::new(0.6, thickness, depth)));
+        world.add_body(Self::wall(Vec3::new(-1.85, 2.5, 0.0), Vec3::new(thickness, 0.5, depth)));
+
+        let fluid = world.add_fluid(SphFluid::new(SPACING));
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(0.0, 1.6, 4.5), Vec3::new(0.0, 1.1, 0.0));
+
+        Self { fluid, travelled: 0.0 }
+    }
+
+    fn on_fixed_update(&mut self, context: &mut Context) {
+        let dt = context.time().step_delta().as_secs_f32();
+        let Some(fluid) = context.physics_mut().fluid_mut(self.fluid) else { return };
+
+        // Splashes over the rim fall forever, stop simulating them;
+        fluid.particles_mut().retain(|particle| particle.position.y > FLOOR);
+        if fluid.particles().len() >= MAX_PARTICLES {
+            return;
+        }
+
+        // A new layer each time the jet moved by one particle spacing;
+        self.travelled += JET.length() * dt;
+        while self.travelled >= SPACING {
+            self.travelled -= SPACING;
+
+            let offset = (JET_WIDTH - 1) as f32 * 0.5;
+            for y in 0..JET_WIDTH {
+                for z in 0..JET_WIDTH {
+                    let cross = Vec3::new(0.0, y as f32 - offset, z as f32 - offset) * SPACING;
+                    fluid.add_particle(NOZZLE + cross + JET.normalize() * self.travelled, JET);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::f32::consts::TAU;
+
+use glam::{Quat, Vec3};
+
+use crate::engine::context::{Camera3D, Context, ContextBuilder};
+use crate::engine::EventHandler;
+use crate::engine::physics::{BodyHandle, Collider, RigidBody, Rope, Shape};
+
+/// Links of each bridge rope, a plank spans every other one;
+const BRIDGE_SEGMENTS: usize = 39;
+/// Ropes run just outside the planks on either side;
+const BRIDGE_SIDE: f32 = 0.6;
+
+/// Balls dropped onto the bridge, one every few seconds;
+const BALLS: usize = 4;
+const BALL_EVERY: f32 = 2.5;
+
+/// Seconds the longest pendulum takes for 15 swings, the next ones fit one more each;
+const PENDULUM_CYCLE: f32 = 30.0;
+
+/// Floor every rope scene stands on;
+fn floor() -> RigidBody {
+    RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0)))
+}
+
+/// Plank bridge slung between two posts, balls keep dropping onto it;
+/// Run with `bridge` as the first argument;
+pub struct Bridge {
+    elapsed: f32,
+    dropped: usize,
+}
+
+impl Bridge {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: bridge", "Delfi")
+    }
+}
+
+impl EventHandler for Bridge {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(floor());
+        for x in [-4.2, 4.2] {
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new(x, 1.5, 0.0))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(0.15, 1.5, 0.8)))));
+        }
+
+        // Heavy ropes with a little slack, so the deck sags && the planks don't outweigh them;
+        let mut ropes = [-BRIDGE_SIDE, BRIDGE_SIDE].map(|z| {
+            let mut rope = Rope::new(Vec3::new(-4.0, 3.0, z), Vec3::new(4.0, 3.0, z), BRIDGE_SEGMENTS)
+                .with_length(8.4)
+                .with_mass(4.0)
+                .with_damping(0.5)
+                .with_substeps(30);
+            rope.pin(0);
+            rope.pin(rope.last());
+            rope
+        });
+
+        // Tied at all four corners, so a plank can't tip like a trapdoor;
+        let half = ropes[0].rest_length() / BRIDGE_SEGMENTS as f32 * 0.5;
+        for index in (1..BRIDGE_SEGMENTS - 1).step_by(2) {
+            let [a, b] = [index, index + 1].map(|k| (ropes[0].positions()[k] + ropes[1].positions()[k]) * 0.5);
+            let plank = world.add_body(RigidBody::new()
+                .with_position((a + b) * 0.5)
+                .with_orientation(Quat::from_rotation_arc(Vec3::X, (b - a).normalize()))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(half, 0.03, 0.5))))
+                .with_density(300.0));
+
+            for (rope, z) in ropes.iter_mut().zip([-BRIDGE_SIDE, BRIDGE_SIDE]) {
+                rope.attach(index, plank, Vec3::new(-half, 0.0, z));
+                rope.attach(index + 1, plank, Vec3::new(half, 0.0, z));
+            }
+        }
+
+        for rope in ropes {
+            world.add_rope(rope);
+        }
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(3.0, 4.0, 7.0), Vec3::new(0.0, 1.8, 0.0));
+
+        Self { elapsed: 0.0, dropped: 0 }
+    }
+
+    fn on_fixed_update(&mut self, context: &mut Context) {
+        self.elapsed += context.time().step_delta().as_secs_f32();
+        if self.dropped == BALLS || self.elapsed < BALL_EVERY * (self.dropped + 1) as f32 {
+            return;
+        }
+
+        // Along the deck from one end to the other, each somewhat heavier than a plank;
+        let x = -1.5 + self.dropped as f32;
+        context.physics_mut().add_body(RigidBody::new()
+            .with_position(Vec3::new(x, 3.5, 0.0))
+            .with_collider(Collider::new(Shape::sphere(0.25)))
+            .with_density(100.0));
+        self.dropped += 1;
+    }
+}
+
+/// Row of ball && chain pendulums released together, their lengths make them drift out of step;
+/// Run with `pendulums` as the first argument;
+pub struct Pendulums {}
+
+impl Pendulums {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: pendulums", "Delfi")
+    }
+}
+
+impl EventHandler for Pendulums {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(floor());
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::new(0.0, 2.56, 0.0))
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(0.05, 0.05, 1.8)))));
+
+        let (radius, release) = (0.1, 0.5_f32);
+        let direction = Vec3::new(release.sin(), -release.cos(), 0.0);
+        for i in 0..8 {
+            let period = PENDULUM_CYCLE / (15 + i) as f32;
+            let length = 9.81 * (period / TAU).powi(2);
+            let pivot = Vec3::new(0.0, 2.5, -1.4 + i as f32 * 0.4);
+
+            let ball = world.add_body(RigidBody::new()
+                .with_position(pivot + direction * length)
+                .with_collider(Collider::new(Shape::sphere(radius)))
+                .with_mass(1.0));
+
+            let mut chain = Rope::new(pivot, pivot + direction * (length - radius), 12)
+                .with_mass(0.2)
+                .with_damping(0.0)
+                .with_substeps(40);
+            chain.pin(0);
+            chain.attach(chain.last(), ball, -direction * radius);
+            world.add_rope(chain);
+        }
+
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(4.0, 2.0, 3.0), Vec3::new(0.0, 1.6, 0.0));
+
+        Self {}
+    }
+}
+
+/// Hook riding along a jib, lowering a crate to the floor && hauling it back up;
+/// A slack rope hangs off the jib's end, coiling on the floor without passing through itself;
+/// Run with `crane` as the first argument;
+pub struct Crane {
+    hook: BodyHandle,
+    elapsed: f32,
+}
+
+impl Crane {
+    pub fn context() -> ContextBuilder {
+        ContextBuilder::new("SPE: crane", "Delfi")
+    }
+}
+
+impl EventHandler for Crane {
+    fn setup(context: &mut Context) -> Self {
+        let world = context.physics_mut();
+        world.add_body(floor());
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::new(-2.9, 2.2, 0.0))
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(0.1, 2.2, 0.1)))));
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::new(0.0, 4.3, 0.0))
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(3.0, 0.1, 0.1)))));
+
+        let hook = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::Y * 4.0)
+            .with_collider(Collider::new(Shape::sphere(0.08))));
+        let load = world.add_body(RigidBody::new()
+            .with_position(Vec3::Y * 1.2)
+            .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.3))))
+            .with_mass(5.0));
+
+        // Ten times heavier than its cable, more substeps keep the cable from stretching under it;
+        let mut cable = Rope::new(Vec3::Y * 3.92, Vec3::Y * 1.5, 16)
+            .with_mass(0.5)
+            .with_substeps(40);
+        cable.attach(0, hook, Vec3::NEG_Y * 0.08);
+        cable.attach(cable.last(), load, Vec3::Y * 0.3);
+        world.add_rope(cable);
+
+        // Laid out level from the jib's end, it swings down && piles up;
+        let mut coil = Rope::new(Vec3::new(2.8, 4.1, 0.2), Vec3::new(2.8, 4.1, 6.2), 90)
+            .with_mass(0.9)
+            .with_radius(0.04)
+            .with_self_collision(true);
+        coil.pin(0);
+        world.add_rope(coil);
+
+        *context.camera3d_mut() = Camera3D::new(Vec3::new(2.0, 3.0, 8.0), Vec3::new(0.0, 2.0, 0.0));
+
+        Self { hook, elapsed: 0.0 }
+    }
+
+    fn on_fixed_update(&mut self, context: &mut Context) {
+        self.elapsed += context.time().step_delta().as_secs_f32();
+        let Some(hook) = context.physics_mut().body_mut(self.hook) else { return };
+
+        // Two meters either way along the jib, slower down to two meters high && back;
+        let t = self.elapsed;
+        hook.set_linear_velocity(Vec3::new(0.8 * (0.4 * t).cos(), -0.3 * (0.3 * t).sin(), 0.0));
+    }
+}
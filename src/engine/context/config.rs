@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use winit::dpi::PhysicalSize;
 
@@ -9,6 +11,8 @@ pub struct Config {
     pub(super) min_size: Option<PhysicalSize<i32>>,
     pub(super) visible: bool,
     pub(super) transparent: bool,
+    pub(super) fixed_delta: Duration,
+    pub(super) max_substeps: u32,
 }
 
 impl Config {
@@ -30,6 +34,8 @@ impl Default for Config {
             min_size: Some((640, 360).into()),
             visible: true,
             transparent: false,
+            fixed_delta: Duration::from_secs_f64(1.0 / 120.0),
+            max_substeps: 8,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use std::f32::consts::PI;
+
+use glam::{Mat4, Vec2, Vec3};
+
+/// Orthographic camera of the 2D world;
+/// Looks at a point of the XY plane && shows a fixed world height, Y points up;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera2D {
+    position: Vec2,
+    height: f32,
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self { position: Vec2::ZERO, height: 20.0 }
+    }
+}
+
+impl Camera2D {
+    pub fn new(position: Vec2, height: f32) -> Self {
+        Self { position, height }
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+
+    /// Visible world height in meters;
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn set_height(&mut self, height: f32) {
+        self.height = height.max(f32::EPSILON);
+    }
+
+    /// World to clip space scale for a viewport of the given width / height;
+    /// Y is flipped, vulkan clip space points down;
+    pub fn scale(&self, aspect: f32) -> Vec2 {
+        Vec2::new(2.0 / (self.height * aspect), -2.0 / self.height)
+    }
+
+    /// World point under a viewport position given in pixels;
+    pub fn screen_to_world(&self, pixel: Vec2, resolution: Vec2) -> Vec2 {
+        let ndc = pixel / resolution * 2.0 - Vec2::ONE;
+        self.position + ndc / self.scale(resolution.x / resolution.y)
+    }
+}
+
+/// Perspective camera of the 3D world, looking from a position at a target with Y up;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera3D {
+    position: Vec3,
+    target: Vec3,
+    /// Vertical field of view in radians;
+    fov: f32,
+    near: f32,
+    far: f32,
+}
+
+impl Default for Camera3D {
+    fn default() -> Self {
+        Self::new(Vec3::new(0.0, 2.0, 6.0), Vec3::Y)
+    }
+}
+
+impl Camera3D {
+    pub fn new(position: Vec3, target: Vec3) -> Self {
+        Self { position, target, fov: 60f32.to_radians(), near: 0.05, far: 200.0 }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+
+    pub fn target(&self) -> Vec3 {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Vec3) {
+        self.target = target;
+    }
+
+    pub fn fov(&self) -> f32 {
+        self.fov
+    }
+
+    pub fn set_fov(&mut self, fov: f32) {
+        self.fov = fov.clamp(f32::EPSILON, PI - f32::EPSILON);
+    }
+
+    /// Near && far clip distances;
+    pub fn set_clip(&mut self, near: f32, far: f32) {
+        self.near = near.max(f32::EPSILON);
+        self.far = far.max(self.near * 2.0);
+    }
+
+    /// World to clip space for a viewport of the given width / height;
+    /// Y is flipped, vulkan clip space points down && depth goes from 0 to 1;
+    pub fn view_projection(&self, aspect: f32) -> Mat4 {
+        let view = Mat4::look_at_rh(self.position, self.target, Vec3::Y);
+        let projection = Mat4::perspective_rh(self.fov, aspect.max(f32::EPSILON), self.near, self.far);
+
+        Mat4::from_scale(Vec3::new(1.0, -1.0, 1.0)) * projection * view
+    }
+}
@@ -0,0 +1,30 @@
+use crate::engine::physics::Cloth;
+
+use super::Vertex3D;
+
+/// Checkerboard of the sheet's quads, shows how it stretches && folds;
+const LIGHT_COLOR: [f32; 4] = [0.85, 0.25, 0.3, 1.0];
+const DARK_COLOR: [f32; 4] = [0.65, 0.15, 0.25, 1.0];
+
+/// Triangle list of the cloth's current shape with smooth normals;
+pub fn cloth_vertices(cloth: &Cloth) -> Vec<Vertex3D> {
+    let positions = cloth.positions();
+    let normals = cloth.normals();
+    let quads = cloth.columns() - 1;
+
+    cloth.triangles().iter()
+        .enumerate()
+        .flat_map(|(triangle, corners)| {
+            let quad = triangle / 2;
+            let color = match (quad % quads + quad / quads) % 2 == 0 {
+                true => LIGHT_COLOR,
+                false => DARK_COLOR
+            };
+
+            corners.map(|i| {
+                let i = i as usize;
+                Vertex3D::from(positions[i]).with_normal(normals[i]).with_color(color)
+            })
+        })
+        .collect()
+}
@@ -24,12 +24,16 @@ vulkano_shaders::shader!(
 );
 
 impl Mesh for Cube {
-    fn vertex_buffer(memory_alloc: Arc<StandardMemoryAllocator>) -> Arc<Buffer> {
-        let vertices = [
+    fn vertices() -> Vec<Vertex3D> {
+        vec![
             Vertex3D::new(0.0, 0.0, 0.0),
             Vertex3D::new(0.0, -0.5, 0.0),
             Vertex3D::new(0.5, 0.0, 0.0)
-        ];
+        ]
+    }
+
+    fn vertex_buffer(memory_alloc: Arc<StandardMemoryAllocator>) -> Arc<Buffer> {
+        let vertices = Self::vertices();
 
         let sub = Buffer::from_iter(
             memory_alloc.clone(),
@@ -0,0 +1,39 @@
+use glam::Vec3;
+
+use crate::engine::physics::SphFluid;
+
+use super::Vertex3D;
+
+const CALM_COLOR: [f32; 4] = [0.15, 0.35, 0.85, 1.0];
+const FAST_COLOR: [f32; 4] = [0.75, 0.9, 1.0, 1.0];
+
+/// Particle speed drawn with the full foam color;
+const FAST_SPEED: f32 = 4.0;
+
+/// Corners of the unit octahedron, one triangle per octant;
+const OCTANTS: [[Vec3; 3]; 8] = {
+    let (x, y, z) = (Vec3::X, Vec3::Y, Vec3::Z);
+    let (nx, ny, nz) = (Vec3::NEG_X, Vec3::NEG_Y, Vec3::NEG_Z);
+    [
+        [x, y, z], [z, y, nx], [nx, y, nz], [nz, y, x],
+        [x, z, ny], [z, nx, ny], [nx, nz, ny], [nz, x, ny],
+    ]
+};
+
+/// One octahedron per particle, brighter when fast;
+pub fn fluid_vertices(fluid: &SphFluid) -> Vec<Vertex3D> {
+    let particles = fluid.particles();
+    let radius = particles.radius();
+
+    particles.particles().iter()
+        .flat_map(|particle| {
+            let t = (particle.velocity.length() / FAST_SPEED).min(1.0);
+            let color = std::array::from_fn(|i| CALM_COLOR[i] + (FAST_COLOR[i] - CALM_COLOR[i]) * t);
+
+            OCTANTS.iter().flat_map(move |corners| {
+                let normal = corners.iter().sum::<Vec3>().normalize();
+                corners.map(|corner| Vertex3D::from(particle.position + corner * radius).with_normal(normal).with_color(color))
+            })
+        })
+        .collect()
+}
@@ -1,9 +1,18 @@
 use std::sync::Arc;
 
+use glam::Vec3;
 use vulkano::buffer::{Buffer, BufferContents};
 use vulkano::memory::allocator::StandardMemoryAllocator;
 use vulkano::pipeline::graphics::vertex_input::Vertex;
 
+use crate::engine::physics::{ConvexHull, MassProperties, TriMesh};
+
+pub mod cloth;
+pub mod fluid;
+pub mod rope;
+pub mod shape2d;
+pub mod shape3d;
+pub mod soft;
 mod cube;
 
 #[derive(BufferContents, Vertex)]
@@ -11,14 +20,43 @@ mod cube;
 pub struct Vertex3D {
     #[format(R32G32B32_SFLOAT)]
     pub position: [f32; 3],
+    /// Zero normals are drawn unlit;
+    #[format(R32G32B32_SFLOAT)]
+    pub normal: [f32; 3],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],
 }
 
 impl Vertex3D {
     fn new(x: f32, y: f32, z: f32) -> Self {
         Self {
-            position: [x, y, z]
+            position: [x, y, z],
+            normal: [0.0; 3],
+            color: [1.0; 4],
         }
     }
+
+    fn with_normal(mut self, normal: Vec3) -> Self {
+        self.normal = normal.to_array();
+        self
+    }
+
+    fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl From<Vec3> for Vertex3D {
+    fn from(position: Vec3) -> Self {
+        Self::new(position.x, position.y, position.z)
+    }
+}
+
+impl From<&Vertex3D> for Vec3 {
+    fn from(vertex: &Vertex3D) -> Self {
+        Vec3::from_array(vertex.position)
+    }
 }
 
 #[derive(BufferContents, Vertex)]
@@ -26,16 +64,49 @@ impl Vertex3D {
 pub struct Vertex2D {
     #[format(R32G32_SFLOAT)]
     pub position: [f32; 2],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],
 }
 
 impl Vertex2D {
     fn new(x: f32, y: f32) -> Self {
         Self {
-            position: [x, y]
+            position: [x, y],
+            color: [1.0; 4],
         }
     }
+
+    fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
 }
 
 pub trait Mesh: Sized + Send + Sync {
+    /// Triangle list, three vertices per triangle;
+    fn vertices() -> Vec<Vertex3D>;
+
     fn vertex_buffer(memory_alloc: Arc<StandardMemoryAllocator>) -> Arc<Buffer>;
+
+    /// Convex collision hull around mesh vertices;
+    fn convex_hull() -> ConvexHull {
+        ConvexHull::new(Self::vertices().iter().map(Vec3::from))
+    }
+
+    fn triangles() -> Vec<[Vec3; 3]> {
+        Self::vertices()
+            .chunks_exact(3)
+            .map(|t| [Vec3::from(&t[0]), Vec3::from(&t[1]), Vec3::from(&t[2])])
+            .collect()
+    }
+
+    /// Static triangle mesh collider with the same triangles;
+    fn tri_mesh() -> TriMesh {
+        TriMesh::from_triangles(Self::triangles())
+    }
+
+    /// Mass properties of the closed mesh filled with the density;
+    fn mass_properties(density: f32) -> MassProperties {
+        MassProperties::from_triangles(Self::triangles(), density)
+    }
 }
@@ -0,0 +1,44 @@
+use std::f32::consts::TAU;
+
+use glam::Vec3;
+
+use crate::engine::physics::Rope;
+
+use super::Vertex3D;
+
+/// Alternating segments show the links of a chain && how the rope twists;
+const LIGHT_COLOR: [f32; 4] = [0.8, 0.7, 0.45, 1.0];
+const DARK_COLOR: [f32; 4] = [0.55, 0.45, 0.3, 1.0];
+
+/// Sides of each segment's tube;
+const SIDES: usize = 6;
+
+/// Open tube around every segment with radial normals;
+pub fn rope_vertices(rope: &Rope) -> Vec<Vertex3D> {
+    let radius = rope.radius();
+
+    rope.positions().windows(2)
+        .enumerate()
+        .flat_map(|(segment, pair)| {
+            let (start, end) = (pair[0], pair[1]);
+            let (u, v) = (end - start).try_normalize().unwrap_or(Vec3::Y).any_orthonormal_pair();
+            let color = match segment % 2 == 0 {
+                true => LIGHT_COLOR,
+                false => DARK_COLOR
+            };
+
+            (0..SIDES).flat_map(move |side| {
+                let [a, b] = [side, side + 1].map(|k| {
+                    let (sin, cos) = (k as f32 / SIDES as f32 * TAU).sin_cos();
+                    u * cos + v * sin
+                });
+                let vertex = |center: Vec3, normal: Vec3| Vertex3D::from(center + normal * radius).with_normal(normal).with_color(color);
+
+                [
+                    vertex(start, a), vertex(end, a), vertex(end, b),
+                    vertex(start, a), vertex(end, b), vertex(start, b),
+                ]
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec2;
+
+use crate::engine::physics2d::{BodyType, RigidBody, Shape, World};
+
+use super::Vertex2D;
+
+/// Segments of a full circle outline;
+const CIRCLE_SEGMENTS: usize = 32;
+
+const STATIC_COLOR: [f32; 4] = [0.45, 0.45, 0.5, 1.0];
+const KINEMATIC_COLOR: [f32; 4] = [0.35, 0.6, 0.9, 1.0];
+const DYNAMIC_COLOR: [f32; 4] = [0.95, 0.6, 0.3, 1.0];
+
+/// Darkening of the first triangle, shows the body's rotation;
+const MARKER_SHADE: f32 = 0.6;
+
+/// Local outline of a shape, counter clockwise;
+fn outline(shape: &Shape) -> Vec<Vec2> {
+    match shape {
+        Shape::Circle { radius } => {
+            let mut points = arc(Vec2::ZERO, *radius, 0.0, TAU, CIRCLE_SEGMENTS);
+            points.pop();
+            points
+        }
+        Shape::Polygon(polygon) => polygon.points().to_vec(),
+        Shape::Capsule { half_height, radius } => {
+            let segments = CIRCLE_SEGMENTS / 2;
+            let mut points = arc(Vec2::Y * -*half_height, *radius, PI, TAU, segments);
+            points.extend(arc(Vec2::Y * *half_height, *radius, 0.0, PI, segments));
+            points
+        }
+    }
+}
+
+/// Points of an arc, both ends included;
+fn arc(center: Vec2, radius: f32, from: f32, to: f32, segments: usize) -> Vec<Vec2> {
+    (0..=segments)
+        .map(|i| from + (to - from) * i as f32 / segments as f32)
+        .map(|angle| center + Vec2::from_angle(angle) * radius)
+        .collect()
+}
+
+/// Triangle fan of a body's collider in world space;
+pub fn body_vertices(body: &RigidBody) -> Vec<Vertex2D> {
+    let Some(collider) = body.collider() else { return Vec::new() };
+
+    let color = match body.body_type() {
+        BodyType::Static => STATIC_COLOR,
+        BodyType::Kinematic => KINEMATIC_COLOR,
+        BodyType::Dynamic => DYNAMIC_COLOR,
+    };
+    let [r, g, b, a] = color;
+    let marker = [r * MARKER_SHADE, g * MARKER_SHADE, b * MARKER_SHADE, a];
+
+    let iso = body.isometry();
+    let points: Vec<Vec2> = outline(collider.shape()).into_iter()
+        .map(|p| iso.transform_point(p))
+        .collect();
+
+    // Inside the convex outline even when the shape is off the body origin;
+    let center = points.iter().sum::<Vec2>() / points.len() as f32;
+    let vertex = |p: Vec2, color| Vertex2D::new(p.x, p.y).with_color(color);
+
+    let mut vertices = Vec::with_capacity(points.len() * 3);
+    for i in 0..points.len() {
+        let color = match i == 0 {
+            true => marker,
+            false => color
+        };
+
+        vertices.push(vertex(center, color));
+        vertices.push(vertex(points[i], color));
+        vertices.push(vertex(points[(i + 1) % points.len()], color));
+    }
+
+    vertices
+}
+
+/// Triangle list of every body with a collider;
+pub fn world_vertices(world: &World) -> Vec<Vertex2D> {
+    world.bodies()
+        .flat_map(|(_, body)| body_vertices(body))
+        .collect()
+}
@@ -0,0 +1,107 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec3;
+
+use crate::engine::physics::{BodyType, Isometry, RigidBody, Shape, World};
+
+use super::{cloth, fluid, rope, soft, Vertex3D};
+
+/// Longitude && latitude bands of a tessellated shape;
+const SLICES: usize = 24;
+const STACKS: usize = 12;
+
+/// Bisection steps locating the surface along each direction;
+const SURFACE_STEPS: usize = 20;
+
+const STATIC_COLOR: [f32; 4] = [0.45, 0.45, 0.5, 1.0];
+const KINEMATIC_COLOR: [f32; 4] = [0.35, 0.6, 0.9, 1.0];
+const DYNAMIC_COLOR: [f32; 4] = [0.95, 0.6, 0.3, 1.0];
+
+/// Sleeping bodies are drawn darker && greyer;
+const SLEEPING_SHADE: f32 = 0.6;
+const SLEEPING_SATURATION: f32 = 0.4;
+
+/// Local surface point && normal seen from the shape's center in a direction;
+/// Convex shapes are star shaped around their center, so the signed distance changes sign once;
+fn surface(shape: &Shape, center: Vec3, reach: f32, direction: Vec3) -> (Vec3, Vec3) {
+    let (mut inside, mut outside) = (0.0, reach);
+    for _ in 0..SURFACE_STEPS {
+        let middle = (inside + outside) * 0.5;
+        match shape.local_distance(center + direction * middle).0 < 0.0 {
+            true => inside = middle,
+            false => outside = middle
+        }
+    }
+
+    let point = center + direction * outside;
+    (point, shape.local_distance(point).1)
+}
+
+/// Latitude && longitude grid of a convex shape projected onto its surface, in world space;
+fn convex_vertices(shape: &Shape, iso: &Isometry, color: [f32; 4]) -> Vec<Vertex3D> {
+    let bounds = shape.aabb(&Isometry::default());
+    let (center, reach) = (bounds.center(), bounds.half_extents().length() * 1.01);
+
+    let grid: Vec<(Vec3, Vec3)> = (0..=STACKS)
+        .flat_map(|stack| (0..=SLICES).map(move |slice| (stack, slice)))
+        .map(|(stack, slice)| {
+            let (polar, azimuth) = (PI * stack as f32 / STACKS as f32, TAU * slice as f32 / SLICES as f32);
+            let direction = Vec3::new(polar.sin() * azimuth.cos(), polar.cos(), polar.sin() * azimuth.sin());
+
+            let (point, normal) = surface(shape, center, reach, direction);
+            (iso.transform_point(point), iso.transform_vector(normal))
+        })
+        .collect();
+
+    let vertex = |index: usize| {
+        let (point, normal) = grid[index];
+        Vertex3D::from(point).with_normal(normal).with_color(color)
+    };
+
+    let mut vertices = Vec::with_capacity(STACKS * SLICES * 6);
+    for stack in 0..STACKS {
+        for slice in 0..SLICES {
+            let a = stack * (SLICES + 1) + slice;
+            let b = a + SLICES + 1;
+            vertices.extend([a, b, a + 1, a + 1, b, b + 1].map(vertex));
+        }
+    }
+
+    vertices
+}
+
+/// Triangle list of a body's convex collider, planes && meshes aren't drawn;
+pub fn body_vertices(body: &RigidBody) -> Vec<Vertex3D> {
+    let Some(collider) = body.collider().filter(|c| c.shape().is_convex() && !c.is_sensor()) else { return Vec::new() };
+
+    let color = match body.body_type() {
+        BodyType::Static => STATIC_COLOR,
+        BodyType::Kinematic => KINEMATIC_COLOR,
+        BodyType::Dynamic => DYNAMIC_COLOR,
+    };
+
+    let color = match body.is_sleeping() {
+        true => sleeping(color),
+        false => color
+    };
+
+    convex_vertices(collider.shape(), &body.isometry(), color)
+}
+
+/// Color pulled toward its grey && darkened;
+fn sleeping([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let grey = 0.3 * r + 0.59 * g + 0.11 * b;
+    let tint = |c: f32| (grey + (c - grey) * SLEEPING_SATURATION) * SLEEPING_SHADE;
+    [tint(r), tint(g), tint(b), a]
+}
+
+/// Triangle list of every body, cloth, soft body, rope && fluid;
+pub fn world_vertices(world: &World) -> Vec<Vertex3D> {
+    let bodies = world.bodies().flat_map(|(_, body)| body_vertices(body));
+    let cloths = world.cloths().flat_map(|(_, cloth)| cloth::cloth_vertices(cloth));
+    let softs = world.soft_bodies().flat_map(|(_, body)| soft::soft_vertices(body));
+    let ropes = world.ropes().flat_map(|(_, rope)| rope::rope_vertices(rope));
+    let fluids = world.fluids().flat_map(|(_, fluid)| fluid::fluid_vertices(fluid));
+
+    bodies.chain(cloths).chain(softs).chain(ropes).chain(fluids).collect()
+}
@@ -0,0 +1,18 @@
+use crate::engine::physics::SoftBody;
+
+use super::Vertex3D;
+
+const JELLY_COLOR: [f32; 4] = [0.35, 0.8, 0.4, 1.0];
+
+/// Triangle list of the soft body's current surface with smooth normals;
+pub fn soft_vertices(body: &SoftBody) -> Vec<Vertex3D> {
+    let positions = body.positions();
+    let normals = body.normals();
+
+    body.triangles().iter()
+        .flat_map(|corners| corners.map(|i| {
+            let i = i as usize;
+            Vertex3D::from(positions[i]).with_normal(normals[i]).with_color(JELLY_COLOR)
+        }))
+        .collect()
+}
@@ -2,17 +2,19 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use vulkano::{Validated, VulkanError};
-use vulkano::buffer::{Buffer, BufferCreateInfo, BufferUsage};
+use vulkano::buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer};
 use vulkano::buffer::allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo};
 use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, PrimaryCommandBufferAbstract, RenderPassBeginInfo, SubpassBeginInfo, SubpassEndInfo};
 use vulkano::command_buffer::allocator::StandardCommandBufferAllocator;
 use vulkano::descriptor_set::{PersistentDescriptorSet, WriteDescriptorSet};
 use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
-use vulkano::device::{DeviceExtensions, Queue};
+use vulkano::device::{Device, DeviceExtensions, Queue};
 use vulkano::image::Image;
+use vulkano::image::view::ImageView;
 use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator};
 use vulkano::pipeline::{GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout, PipelineShaderStageCreateInfo};
 use vulkano::pipeline::graphics::color_blend::{ColorBlendAttachmentState, ColorBlendState};
+use vulkano::pipeline::graphics::depth_stencil::{DepthState, DepthStencilState};
 use vulkano::pipeline::graphics::GraphicsPipelineCreateInfo;
 use vulkano::pipeline::graphics::input_assembly::InputAssemblyState;
 use vulkano::pipeline::graphics::multisample::MultisampleState;
@@ -21,19 +23,23 @@ use vulkano::pipeline::graphics::vertex_input::{Vertex, VertexDefinition};
 use vulkano::pipeline::graphics::viewport::{Viewport, ViewportState};
 use vulkano::pipeline::layout::PipelineDescriptorSetLayoutCreateInfo;
 use vulkano::render_pass::{Framebuffer, Subpass};
+use vulkano::shader::ShaderModule;
 use vulkano::swapchain::{acquire_next_image, PresentMode, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo};
 use vulkano::sync::GpuFuture;
 use winit::event_loop::EventLoop;
 use winit::window::Window;
 
-use meshes::Vertex2D;
+pub use camera::{Camera2D, Camera3D};
+use meshes::{shape2d, shape3d, Vertex2D};
 
 use crate::engine::context::graphics::meshes::{Mesh, Vertex3D};
+use crate::engine::{physics, physics2d};
 
 use super::Config;
 
 mod renderer;
 mod meshes;
+mod camera;
 
 mod vs2d {
     vulkano_shaders::shader! {
@@ -125,6 +131,47 @@ mod fs2d {
     }
 }
 
+mod vs_shapes2d {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 460
+
+            layout(location = 0) in vec2 position;
+            layout(location = 1) in vec4 color;
+
+            layout(location = 0) out vec4 frag_color;
+
+            layout(push_constant) uniform Camera {
+                vec2 center;
+                vec2 scale;
+            } camera;
+
+            void main() {
+                gl_Position = vec4((position - camera.center) * camera.scale, 0.0, 1.0);
+                frag_color = color;
+            }
+        ",
+    }
+}
+
+mod fs_shapes2d {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 460
+
+            layout(location = 0) in vec4 frag_color;
+
+            layout(location = 0) out vec4 out_color;
+
+            void main() {
+                out_color = frag_color;
+            }
+        ",
+    }
+}
+
 mod vs3d {
     vulkano_shaders::shader! {
         ty: "vertex",
@@ -132,9 +179,26 @@ mod vs3d {
             #version 460
 
             layout(location = 0) in vec3 position;
+            layout(location = 1) in vec3 normal;
+            layout(location = 2) in vec4 color;
+
+            layout(location = 0) out vec4 frag_color;
+
+            layout(push_constant) uniform Camera {
+                mat4 view_projection;
+            } camera;
+
+            const vec3 LIGHT = normalize(vec3(0.4, 1.0, 0.6));
+            const float AMBIENT = 0.35;
 
             void main() {
-                gl_Position = vec4(position, 1.0);
+                gl_Position = camera.view_projection * vec4(position, 1.0);
+
+                // Two sided, cloth shows both faces;
+                float light = length(normal) > 0.0
+                    ? AMBIENT + (1.0 - AMBIENT) * abs(dot(normalize(normal), LIGHT))
+                    : 1.0;
+                frag_color = vec4(color.rgb * light, color.a);
             }
         ",
     }
@@ -146,8 +210,12 @@ mod fs3d {
         src: r"
             #version 460
 
-            void main() {
+            layout(location = 0) in vec4 frag_color;
 
+            layout(location = 0) out vec4 out_color;
+
+            void main() {
+                out_color = frag_color;
             }
         ",
     }
@@ -165,9 +233,14 @@ pub struct GraphicsContext {
     swapchain: Arc<Swapchain>,
     images: Vec<Arc<Image>>,
     image_index: usize,
+    depth: Arc<ImageView>,
 
     pipeline3d: Arc<GraphicsPipeline>,
     pipeline2d: Arc<GraphicsPipeline>,
+    shapes2d: Arc<GraphicsPipeline>,
+
+    camera2d: Camera2D,
+    camera3d: Camera3D,
 
     memory_alloc: Arc<StandardMemoryAllocator>,
     buffer_alloc: Arc<StandardCommandBufferAllocator>,
@@ -178,7 +251,62 @@ pub struct GraphicsContext {
     resized: bool,
 }
 
-fn create_pipelines(queue: Arc<Queue>, frame_buffer: Arc<Framebuffer>, window: Arc<Window>) -> (Arc<GraphicsPipeline>, Arc<GraphicsPipeline>) {
+fn create_pipeline<V: Vertex>(
+    device: Arc<Device>,
+    subpass: Subpass,
+    viewport: Viewport,
+    vs: Arc<ShaderModule>,
+    fs: Arc<ShaderModule>,
+    depth_test: bool,
+) -> Arc<GraphicsPipeline> {
+    let vertex_shader = vs.entry_point("main").unwrap();
+    let fragment_shader = fs.entry_point("main").unwrap();
+
+    let vertex_input_state = V::per_vertex()
+        .definition(&vertex_shader.info().input_interface)
+        .unwrap();
+
+    let stages = [
+        PipelineShaderStageCreateInfo::new(vertex_shader),
+        PipelineShaderStageCreateInfo::new(fragment_shader),
+    ];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .unwrap(),
+    ).unwrap();
+
+    GraphicsPipeline::new(
+        device,
+        None,
+        GraphicsPipelineCreateInfo {
+            stages: stages.into_iter().collect(),
+            vertex_input_state: Some(vertex_input_state),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState {
+                viewports: [viewport].into_iter().collect(),
+                ..Default::default()
+            }),
+            rasterization_state: Some(RasterizationState::default()),
+            multisample_state: Some(MultisampleState::default()),
+            depth_stencil_state: Some(DepthStencilState {
+                depth: depth_test.then(DepthState::simple),
+                ..Default::default()
+            }),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                subpass.num_color_attachments(),
+                ColorBlendAttachmentState::default(),
+            )),
+            subpass: Some(subpass.into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
+        },
+    ).unwrap()
+}
+
+/// 3D pipeline, 2D background pipeline && 2D shapes pipeline;
+fn create_pipelines(queue: Arc<Queue>, frame_buffer: Arc<Framebuffer>, window: Arc<Window>) -> (Arc<GraphicsPipeline>, Arc<GraphicsPipeline>, Arc<GraphicsPipeline>) {
     let device = queue.device();
 
     let render_pass = frame_buffer.render_pass();
@@ -195,99 +323,34 @@ fn create_pipelines(queue: Arc<Queue>, frame_buffer: Arc<Framebuffer>, window: A
         }
     };
 
-    let pipeline3d = {
-        let vs = vs3d::load(device.clone()).unwrap();
-        let fs = fs3d::load(device.clone()).unwrap();
-
-        let vertex_shader = vs.entry_point("main").unwrap();
-        let fragment_shader = fs.entry_point("main").unwrap();
-
-        let vertex_input_state = Vertex3D::per_vertex()
-            .definition(&vertex_shader.info().input_interface)
-            .unwrap();
-
-        let stages = [
-            PipelineShaderStageCreateInfo::new(vertex_shader),
-            PipelineShaderStageCreateInfo::new(fragment_shader),
-        ];
-
-        let layout = PipelineLayout::new(
-            device.clone(),
-            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
-                .into_pipeline_layout_create_info(device.clone())
-                .unwrap(),
-        ).unwrap();
-
-        GraphicsPipeline::new(
-            device.clone(),
-            None,
-            GraphicsPipelineCreateInfo {
-                stages: stages.clone().into_iter().collect(),
-                vertex_input_state: Some(vertex_input_state),
-                input_assembly_state: Some(InputAssemblyState::default()),
-                viewport_state: Some(ViewportState {
-                    viewports: [viewport.clone()].into_iter().collect(),
-                    ..Default::default()
-                }),
-                rasterization_state: Some(RasterizationState::default()),
-                multisample_state: Some(MultisampleState::default()),
-                color_blend_state: Some(ColorBlendState::with_attachment_states(
-                    subpass.num_color_attachments(),
-                    ColorBlendAttachmentState::default(),
-                )),
-                subpass: Some(subpass.clone().into()),
-                ..GraphicsPipelineCreateInfo::layout(layout.clone())
-            },
-        ).unwrap()
-    };
-
-    let pipeline2d = {
-        let vs = vs2d::load(device.clone()).unwrap();
-        let fs = fs2d::load(device.clone()).unwrap();
-
-        let vertex_shader = vs.entry_point("main").unwrap();
-        let fragment_shader = fs.entry_point("main").unwrap();
-
-        let vertex_input_state = Vertex2D::per_vertex()
-            .definition(&vertex_shader.info().input_interface)
-            .unwrap();
-
-        let stages = [
-            PipelineShaderStageCreateInfo::new(vertex_shader),
-            PipelineShaderStageCreateInfo::new(fragment_shader),
-        ];
-
-        let layout = PipelineLayout::new(
-            device.clone(),
-            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
-                .into_pipeline_layout_create_info(device.clone())
-                .unwrap(),
-        ).unwrap();
-
-        GraphicsPipeline::new(
-            device.clone(),
-            None,
-            GraphicsPipelineCreateInfo {
-                stages: stages.into_iter().collect(),
-                vertex_input_state: Some(vertex_input_state),
-                input_assembly_state: Some(InputAssemblyState::default()),
-                viewport_state: Some(ViewportState {
-                    viewports: [viewport].into_iter().collect(),
-                    ..Default::default()
-                }),
-                rasterization_state: Some(RasterizationState::default()),
-                multisample_state: Some(MultisampleState::default()),
-                color_blend_state: Some(ColorBlendState::with_attachment_states(
-                    subpass.num_color_attachments(),
-                    ColorBlendAttachmentState::default(),
-                )),
-                subpass: Some(subpass.into()),
-                ..GraphicsPipelineCreateInfo::layout(layout)
-            },
-        ).unwrap()
-    };
-
-    (pipeline3d, pipeline2d)
+    let pipeline3d = create_pipeline::<Vertex3D>(
+        device.clone(),
+        subpass.clone(),
+        viewport.clone(),
+        vs3d::load(device.clone()).unwrap(),
+        fs3d::load(device.clone()).unwrap(),
+        true,
+    );
+
+    let pipeline2d = create_pipeline::<Vertex2D>(
+        device.clone(),
+        subpass.clone(),
+        viewport.clone(),
+        vs2d::load(device.clone()).unwrap(),
+        fs2d::load(device.clone()).unwrap(),
+        false,
+    );
+
+    let shapes2d = create_pipeline::<Vertex2D>(
+        device.clone(),
+        subpass,
+        viewport,
+        vs_shapes2d::load(device.clone()).unwrap(),
+        fs_shapes2d::load(device.clone()).unwrap(),
+        false,
+    );
+
+    (pipeline3d, pipeline2d, shapes2d)
 }
 
 impl GraphicsContext {
@@ -325,11 +388,13 @@ impl GraphicsContext {
             Default::default(),
         ));
 
+        let depth = renderer::create_depth_buffer(memory_alloc.clone(), &swapchain);
+
         let image = images[0].clone();
         let frame_buffer =
-            renderer::create_frame_buffer(queue.clone(), swapchain.clone(), image);
+            renderer::create_frame_buffer(queue.clone(), swapchain.clone(), image, depth.clone());
 
-        let (pipeline3d, pipeline2d) =
+        let (pipeline3d, pipeline2d, shapes2d) =
             create_pipelines(queue.clone(), frame_buffer.clone(), window.clone());
 
         Self {
@@ -341,9 +406,15 @@ impl GraphicsContext {
             swapchain,
             images,
             image_index: 0,
+            depth,
 
             pipeline3d,
             pipeline2d,
+            shapes2d,
+
+            camera2d: Camera2D::default(),
+            camera3d: Camera3D::default(),
+
             memory_alloc,
             buffer_alloc,
             descriptor_alloc,
@@ -380,16 +451,19 @@ impl GraphicsContext {
                 }
             ).unwrap();
 
+            self.depth = renderer::create_depth_buffer(self.memory_alloc.clone(), &new_swapchain);
+
             let image= new_images[self.image_index].clone();
             let frame_buffer =
-                renderer::create_frame_buffer(self.queue.clone(), new_swapchain.clone(), image);
+                renderer::create_frame_buffer(self.queue.clone(), new_swapchain.clone(), image, self.depth.clone());
 
             if self.resized {
-                let (new_3d, new_2d) =
+                let (new_3d, new_2d, new_shapes) =
                     create_pipelines(self.queue.clone(), frame_buffer, self.window.clone());
 
                 self.pipeline3d = new_3d;
                 self.pipeline2d = new_2d;
+                self.shapes2d = new_shapes;
 
                 self.resized = false;
             }
@@ -439,7 +513,40 @@ impl GraphicsContext {
         self.vsync
     }
 
-    pub fn redraw(&mut self, acquired: Box<dyn GpuFuture + Send + Sync>, init_time: Duration) {
+    pub fn camera2d(&self) -> &Camera2D {
+        &self.camera2d
+    }
+
+    pub fn camera2d_mut(&mut self) -> &mut Camera2D {
+        &mut self.camera2d
+    }
+
+    pub fn camera3d(&self) -> &Camera3D {
+        &self.camera3d
+    }
+
+    pub fn camera3d_mut(&mut self) -> &mut Camera3D {
+        &mut self.camera3d
+    }
+
+    fn vertex_buffer<T: BufferContents>(&self, vertices: Vec<T>) -> Subbuffer<[T]> {
+        Buffer::from_iter(
+            self.memory_alloc.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            vertices,
+        ).unwrap()
+    }
+
+    /// Draw the background, then the 3D world through the perspective camera && every 2D body over it through the orthographic camera;
+    pub fn redraw(&mut self, acquired: Box<dyn GpuFuture + Send + Sync>, init_time: Duration, world: &physics::World, world2d: &physics2d::World) {
         let mut command_buffer = AutoCommandBufferBuilder::primary(
             &self.buffer_alloc,
             self.queue.queue_family_index(),
@@ -447,7 +554,7 @@ impl GraphicsContext {
         ).unwrap();
 
         let image = self.images[self.image_index].clone();
-        let frame_buffer = renderer::create_frame_buffer(self.queue.clone(), self.swapchain.clone(), image);
+        let frame_buffer = renderer::create_frame_buffer(self.queue.clone(), self.swapchain.clone(), image, self.depth.clone());
 
         let uniform_buffer = SubbufferAllocator::new(
             self.memory_alloc.clone(),
@@ -481,31 +588,14 @@ impl GraphicsContext {
             [],
         ).unwrap();
 
-        let vertex1 = Vertex2D { position: [-1.0, -1.0] };
-        let vertex2 = Vertex2D { position: [1.0, -1.0] };
-        let vertex3 = Vertex2D { position: [1.0, 1.0] };
-        let vertex4 = Vertex2D { position: [1.0, 1.0] };
-        let vertex5 = Vertex2D { position: [-1.0, 1.0] };
-        let vertex6 = Vertex2D { position: [-1.0, -1.0] };
-
-        let vertex_buffer = Buffer::from_iter(
-            self.memory_alloc.clone(),
-            BufferCreateInfo {
-                usage: BufferUsage::VERTEX_BUFFER,
-                ..Default::default()
-            },
-            AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
-                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                ..Default::default()
-            },
-            vec![vertex1, vertex2, vertex3, vertex4, vertex5, vertex6],
-        ).unwrap();
+        let background = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [1.0, 1.0], [-1.0, 1.0], [-1.0, -1.0]]
+            .map(|position| Vertex2D { position, color: [1.0; 4] });
+        let vertex_buffer = self.vertex_buffer(background.into());
 
         command_buffer
             .begin_render_pass(
                 RenderPassBeginInfo {
-                    clear_values: vec![Some([22.0 / 255.0, 22.0 / 255.0, 29.0 / 255.0, 1.0].into())],
+                    clear_values: vec![Some([22.0 / 255.0, 22.0 / 255.0, 29.0 / 255.0, 1.0].into()), Some(1f32.into())],
                     ..RenderPassBeginInfo::framebuffer(frame_buffer)
                 },
                 SubpassBeginInfo::default(),
@@ -524,7 +614,56 @@ impl GraphicsContext {
             .draw(
                 6, 1, 0, 0,
             )
-            .unwrap()
+            .unwrap();
+
+        let aspect = resolution[0] / resolution[1].max(1.0);
+
+        let solids = shape3d::world_vertices(world);
+        if !solids.is_empty() {
+            let camera = vs3d::Camera {
+                view_projection: self.camera3d.view_projection(aspect).to_cols_array_2d(),
+            };
+
+            let count = solids.len() as u32;
+            let solid_buffer = self.vertex_buffer(solids);
+
+            command_buffer
+                .bind_pipeline_graphics(self.pipeline3d.clone())
+                .unwrap()
+                .push_constants(self.pipeline3d.layout().clone(), 0, camera)
+                .unwrap()
+                .bind_vertex_buffers(0, solid_buffer)
+                .unwrap()
+                .draw(
+                    count, 1, 0, 0,
+                )
+                .unwrap();
+        }
+
+        let shapes = shape2d::world_vertices(world2d);
+        if !shapes.is_empty() {
+            let camera = vs_shapes2d::Camera {
+                center: self.camera2d.position().into(),
+                scale: self.camera2d.scale(aspect).into(),
+            };
+
+            let count = shapes.len() as u32;
+            let shape_buffer = self.vertex_buffer(shapes);
+
+            command_buffer
+                .bind_pipeline_graphics(self.shapes2d.clone())
+                .unwrap()
+                .push_constants(self.shapes2d.layout().clone(), 0, camera)
+                .unwrap()
+                .bind_vertex_buffers(0, shape_buffer)
+                .unwrap()
+                .draw(
+                    count, 1, 0, 0,
+                )
+                .unwrap();
+        }
+
+        command_buffer
             .end_render_pass(
                 SubpassEndInfo::default()
             ).unwrap();
@@ -3,9 +3,11 @@ use std::sync::Arc;
 use vulkano::{single_pass_renderpass, VulkanLibrary};
 use vulkano::device::{Device, DeviceCreateInfo, DeviceExtensions, Queue, QueueCreateInfo, QueueFlags};
 use vulkano::device::physical::{PhysicalDevice, PhysicalDeviceType};
-use vulkano::image::{Image, ImageUsage};
+use vulkano::format::Format;
+use vulkano::image::{Image, ImageCreateInfo, ImageType, ImageUsage};
 use vulkano::image::view::ImageView;
 use vulkano::instance::{Instance, InstanceCreateInfo};
+use vulkano::memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator};
 use vulkano::render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass};
 use vulkano::swapchain::{Surface, Swapchain, SwapchainCreateInfo};
 use winit::event_loop::EventLoop;
@@ -13,7 +15,10 @@ use winit::window::{Window, WindowBuilder};
 
 use super::Config;
 
-pub(super) fn create_frame_buffer(queue: Arc<Queue>, swapchain: Arc<Swapchain>, image: Arc<Image>) -> Arc<Framebuffer> {
+/// Supported as a depth attachment by every vulkan device;
+const DEPTH_FORMAT: Format = Format::D16_UNORM;
+
+pub(super) fn create_frame_buffer(queue: Arc<Queue>, swapchain: Arc<Swapchain>, image: Arc<Image>, depth: Arc<ImageView>) -> Arc<Framebuffer> {
     let render_pass = single_pass_renderpass!(
         queue.device().clone(),
         attachments: {
@@ -23,10 +28,16 @@ pub(super) fn create_frame_buffer(queue: Arc<Queue>, swapchain: Arc<Swapchain>,
                 load_op: Clear,
                 store_op: Store,
             },
+            depth: {
+                format: DEPTH_FORMAT,
+                samples: 1,
+                load_op: Clear,
+                store_op: DontCare,
+            },
         },
         pass: {
             color: [color],
-            depth_stencil: {},
+            depth_stencil: {depth},
         },
     ).expect("Render pass init error");
 
@@ -34,12 +45,30 @@ pub(super) fn create_frame_buffer(queue: Arc<Queue>, swapchain: Arc<Swapchain>,
     Framebuffer::new(
         render_pass.clone(),
         FramebufferCreateInfo {
-            attachments: vec![view],
+            attachments: vec![view, depth],
             ..Default::default()
         },
     ).unwrap()
 }
 
+/// Depth attachment matching the swapchain images, recreated with them;
+pub(super) fn create_depth_buffer(memory_alloc: Arc<StandardMemoryAllocator>, swapchain: &Swapchain) -> Arc<ImageView> {
+    let [width, height] = swapchain.image_extent();
+    let image = Image::new(
+        memory_alloc,
+        ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format: DEPTH_FORMAT,
+            extent: [width, height, 1],
+            usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT | ImageUsage::TRANSIENT_ATTACHMENT,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    ).unwrap();
+
+    ImageView::new_default(image).unwrap()
+}
+
 pub(super) fn init_window(event_loop: &EventLoop<()>, config: &Config) -> Arc<Window> {
     let builder = WindowBuilder::new()
         .with_title(config.title.clone())
@@ -1,12 +1,19 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use winit::event_loop::EventLoop;
 
+pub use graphics::{Camera2D, Camera3D};
+
 use config::Config;
 use graphics::GraphicsContext;
 use input::InputContext;
 use time::TimeContext;
 
+use super::physics::{BodyHandle, PairFilter, RigidBody, World, WorldBuilder};
+use super::physics2d;
+
 mod input;
 mod time;
 mod graphics;
@@ -18,6 +25,8 @@ pub struct Context {
     pub(super) input: InputContext,
     pub(super) time: TimeContext,
     pub(super) graphics: GraphicsContext,
+    pub(super) physics: World,
+    pub(super) physics2d: physics2d::World,
 }
 
 impl Context {
@@ -25,16 +34,83 @@ impl Context {
         let builder = ContextBuilder::default();
         builder.build()
     }
+
+    pub fn time(&self) -> &TimeContext {
+        &self.time
+    }
+
+    /// Keyboard state, held keys stay pressed across fixed updates;
+    pub fn input(&self) -> &InputContext {
+        &self.input
+    }
+
+    pub fn physics(&self) -> &World {
+        &self.physics
+    }
+
+    pub fn physics_mut(&mut self) -> &mut World {
+        &mut self.physics
+    }
+
+    /// Planar world, stepped alongside the 3D one && drawn by the 2D pipeline;
+    pub fn physics2d(&self) -> &physics2d::World {
+        &self.physics2d
+    }
+
+    pub fn physics2d_mut(&mut self) -> &mut physics2d::World {
+        &mut self.physics2d
+    }
+
+    pub fn camera2d(&self) -> &Camera2D {
+        self.graphics.camera2d()
+    }
+
+    pub fn camera2d_mut(&mut self) -> &mut Camera2D {
+        self.graphics.camera2d_mut()
+    }
+
+    /// Perspective camera the 3D world is drawn through;
+    pub fn camera3d(&self) -> &Camera3D {
+        self.graphics.camera3d()
+    }
+
+    pub fn camera3d_mut(&mut self) -> &mut Camera3D {
+        self.graphics.camera3d_mut()
+    }
 }
 
 #[derive(Default)]
 pub struct ContextBuilder {
     config: Config,
+    physics: WorldBuilder,
+    physics2d: physics2d::WorldBuilder,
+    collision_filter: Option<PairFilter>,
 }
 
 impl ContextBuilder {
     pub fn new(title: &str, author: &str) -> Self {
-        Self { config: Config::new(title, author) }
+        Self {
+            config: Config::new(title, author),
+            physics: WorldBuilder::new(),
+            physics2d: physics2d::WorldBuilder::new(),
+            collision_filter: None,
+        }
+    }
+
+    pub fn with_physics(mut self, physics: WorldBuilder) -> Self {
+        self.physics = physics;
+        self
+    }
+
+    pub fn with_physics2d(mut self, physics2d: physics2d::WorldBuilder) -> Self {
+        self.physics2d = physics2d;
+        self
+    }
+
+    /// Veto body pairs before the narrow phase, replaces the physics builder's pair filter;
+    pub fn with_collision_filter(mut self, filter: impl Fn(BodyHandle, &RigidBody, BodyHandle, &RigidBody) -> bool + 'static) -> Self {
+        self.collision_filter = Some(Box::new(filter));
+        self
     }
 
     pub fn with_transparent(mut self, transparent: bool) -> Self {
@@ -61,16 +137,33 @@ impl ContextBuilder {
         self
     }
 
+    /// Fixed simulation step, zero runs one update per frame stepped by the frame delta;
+    pub fn with_fixed_delta(mut self, fixed_delta: Duration) -> Self {
+        self.config.fixed_delta = fixed_delta;
+        self
+    }
+
+    /// Max fixed steps per frame;
+    pub fn with_max_substeps(mut self, max_substeps: u32) -> Self {
+        self.config.max_substeps = max_substeps.max(1);
+        self
+    }
+
     pub fn build(self) -> (Context, EventLoop<()>) {
         let event_loop = EventLoop::new().unwrap();
         let title = self.config.title.clone();
         let author = self.config.author.clone();
 
-        let time = TimeContext::new();
+        let time = TimeContext::new(self.config.fixed_delta, self.config.max_substeps);
         let input = InputContext::new();
         let graphics = GraphicsContext::new(&self.config, &event_loop);
+        let physics = match self.collision_filter {
+            Some(filter) => self.physics.with_pair_filter(filter),
+            None => self.physics
+        }.build();
+        let physics2d = self.physics2d.build();
 
-        (Context { title, author, time, input, graphics }, event_loop)
+        (Context { title, author, time, input, graphics, physics, physics2d }, event_loop)
     }
 
     pub fn save(&self) {
@@ -4,28 +4,101 @@ pub struct TimeContext {
     init_time: Instant,
     current: Instant,
     frames: Vec<Duration>,
+
+    fixed_delta: Duration,
+    max_substeps: u32,
+    accumulator: Duration,
 }
 
 const MAX_FRAMES: usize = 200;
 
 impl TimeContext {
-    pub(super) fn new() -> Self {
+    pub(super) fn new(fixed_delta: Duration, max_substeps: u32) -> Self {
         let init_time = Instant::now();
         let current = init_time.clone();
         let frames = Vec::from([Duration::from_secs_f64(0.01)]);
 
-        Self { init_time, current, frames }
+        Self {
+            init_time,
+            current,
+            frames,
+            fixed_delta,
+            max_substeps,
+            accumulator: Duration::ZERO,
+        }
     }
 
     pub(in crate::engine) fn tick(&mut self) {
         let now = Instant::now();
-        let elapsed = self.current.elapsed();
+        let elapsed = now - self.current;
         self.current = now;
 
+        self.advance(elapsed);
+    }
+
+    fn advance(&mut self, elapsed: Duration) {
         self.frames.push(elapsed);
         if self.frames.len() >= MAX_FRAMES {
             self.frames.remove(0);
         }
+
+        self.accumulator += elapsed;
+    }
+
+    /// Number of fixed steps to run this frame, each `step_delta` long;
+    /// Consumes accumulated time, drops whole steps past max_substeps so less than one step is left;
+    /// Without a fixed delta every frame with time passed is a single step;
+    pub(in crate::engine) fn fixed_steps(&mut self) -> u32 {
+        if self.fixed_delta.is_zero() {
+            let steps = match self.accumulator.is_zero() {
+                true => 0,
+                false => 1
+            };
+            self.accumulator = Duration::ZERO;
+            return steps;
+        }
+
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_delta {
+            self.accumulator -= self.fixed_delta;
+            steps += 1;
+
+            if steps >= self.max_substeps {
+                // Spiral of death guard: forget time we can't catch up on, keep the fraction of a step;
+                let left = self.accumulator.as_nanos() % self.fixed_delta.as_nanos();
+                self.accumulator = Duration::from_nanos(left as u64);
+                break;
+            }
+        }
+
+        steps
+    }
+
+    /// Fixed simulation step, zero steps by the frame delta instead;
+    pub fn fixed_delta(&self) -> Duration {
+        self.fixed_delta
+    }
+
+    /// Time covered by each step of this frame, the fixed delta or the frame delta without one;
+    pub fn step_delta(&self) -> Duration {
+        match self.fixed_delta.is_zero() {
+            true => self.delta(),
+            false => self.fixed_delta
+        }
+    }
+
+    pub fn max_substeps(&self) -> u32 {
+        self.max_substeps
+    }
+
+    /// Leftover fraction of a fixed step in [0; 1), zero without a fixed delta;
+    /// Used to interpolate between previous && current simulation state;
+    pub fn alpha(&self) -> f32 {
+        if self.fixed_delta.is_zero() {
+            return 0.0;
+        }
+
+        (self.accumulator.as_secs_f64() / self.fixed_delta.as_secs_f64()) as f32
     }
 
     pub fn init_time(&self) -> Duration {
@@ -47,4 +120,56 @@ impl TimeContext {
         let average = 1.0 / self.average_delta().as_secs_f64();
         average.round() as u32
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn accumulates_frames_into_fixed_steps() {
+        let mut time = TimeContext::new(STEP, 8);
+        time.advance(Duration::from_millis(25));
+        assert_eq!(time.fixed_steps(), 2);
+        assert!((time.alpha() - 0.5).abs() < 1e-6);
+
+        // The leftover carries into the next frame;
+        time.advance(Duration::from_millis(7));
+        assert_eq!(time.fixed_steps(), 1);
+        assert!((time.alpha() - 0.2).abs() < 1e-6);
+
+        time.advance(Duration::from_millis(3));
+        assert_eq!(time.fixed_steps(), 0);
+        assert!((time.alpha() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn long_frames_are_clamped_to_max_substeps() {
+        let mut time = TimeContext::new(STEP, 4);
+        time.advance(Duration::from_millis(100));
+        assert_eq!(time.fixed_steps(), 4);
+        assert_eq!(time.alpha(), 0.0);
+
+        time.advance(Duration::from_micros(97_500));
+        assert_eq!(time.fixed_steps(), 4);
+        assert!(time.alpha() < 1.0 && (time.alpha() - 0.75).abs() < 1e-6, "{}", time.alpha());
+
+        time.advance(Duration::from_millis(5));
+        assert_eq!(time.fixed_steps(), 1);
+        assert!((time.alpha() - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn zero_fixed_delta_steps_once_per_frame() {
+        let mut time = TimeContext::new(Duration::ZERO, 8);
+        time.advance(Duration::from_millis(40));
+        assert_eq!(time.fixed_steps(), 1);
+        assert_eq!(time.step_delta(), Duration::from_millis(40));
+        assert_eq!(time.alpha(), 0.0);
+
+        time.advance(Duration::ZERO);
+        assert_eq!(time.fixed_steps(), 0);
+    }
+}
@@ -5,8 +5,11 @@ use winit::keyboard::KeyCode;
 use winit::window::Fullscreen;
 
 use context::Context;
+use physics::{CollisionEvent, TriggerEvent};
 
 pub mod context;
+pub mod physics;
+pub mod physics2d;
 
 /// Worker helps with context x application logic;
 pub struct Worker<Handler>
@@ -121,6 +124,24 @@ impl<Handler> Worker<Handler>
                     }
                     WindowEvent::RedrawRequested => {
                         ctx.time.tick();
+
+                        let step_delta = ctx.time.step_delta().as_secs_f32();
+                        for _ in 0..ctx.time.fixed_steps() {
+                            handler.on_fixed_update(ctx);
+                            ctx.physics.step(step_delta);
+                            ctx.physics2d.step(step_delta);
+
+                            let events: Vec<CollisionEvent> = ctx.physics.drain_collision_events().collect();
+                            for event in &events {
+                                handler.on_collision(ctx, event);
+                            }
+
+                            let triggers: Vec<TriggerEvent> = ctx.physics.drain_trigger_events().collect();
+                            for trigger in &triggers {
+                                handler.on_trigger(ctx, trigger);
+                            }
+                        }
+
                         handler.on_update();
 
                         let acquired = match ctx.graphics.acquire() {
@@ -136,7 +157,7 @@ impl<Handler> Worker<Handler>
                         handler.on_draw();
 
                         let init_time = ctx.time.init_time();
-                        ctx.graphics.redraw(acquired, init_time);
+                        ctx.graphics.redraw(acquired, init_time, &ctx.physics, &ctx.physics2d);
                     }
                     _ => ()
                 }
@@ -154,6 +175,15 @@ pub trait EventHandler: Sized + Send + Sync {
 
     fn on_update(&mut self) { /* Empty */ }
 
+    /// Called zero or more times per frame, each covering `time().step_delta()`;
+    fn on_fixed_update(&mut self, _context: &mut Context) { /* Empty */ }
+
+    /// Called after each physics step for every collision started, persisted or ended;
+    fn on_collision(&mut self, _context: &mut Context, _event: &CollisionEvent) { /* Empty */ }
+
+    /// Called after each physics step for every body entering or leaving a sensor;
+    fn on_trigger(&mut self, _context: &mut Context, _event: &TriggerEvent) { /* Empty */ }
+
     fn on_draw(&self) { /* Empty */ }
 
     fn on_quit(&self) { /* Empty */ }
@@ -0,0 +1,414 @@
+use glam::{Mat3, Quat, Vec3};
+
+use super::collider::Collider;
+use super::collision::Motion;
+use super::mass::MassProperties;
+use super::shape::Isometry;
+use super::slot::SlotHandle;
+
+/// Body slot inside the physics world && its generation;
+/// Slots are reused once a body is removed, the generation makes old handles find nothing;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyHandle(pub(super) usize, pub(super) u32);
+
+impl BodyHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+
+}
+
+impl SlotHandle for BodyHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyType {
+    /// Moved by forces and contacts;
+    Dynamic,
+    /// Never moves;
+    Static,
+    /// Moved only by its velocity, ignores forces;
+    Kinematic,
+}
+
+/// Rigid body state;
+/// Position, orientation,
+/// Velocities, mass, inertia;
+#[derive(Debug, Clone)]
+pub struct RigidBody {
+    pub(super) body_type: BodyType,
+
+    pub(super) position: Vec3,
+    pub(super) orientation: Quat,
+    pub(super) linear_velocity: Vec3,
+    pub(super) angular_velocity: Vec3,
+
+    pub(super) mass: f32,
+    pub(super) inv_mass: f32,
+    /// Center of mass in body space, the body turns about it;
+    pub(super) center: Vec3,
+    /// About the center of mass;
+    pub(super) inertia: Mat3,
+    pub(super) inv_inertia: Mat3,
+
+    pub(super) force: Vec3,
+    pub(super) torque: Vec3,
+
+    pub(super) collider: Option<Collider>,
+    /// Sweep motion to stop at the first impact instead of tunneling;
+    pub(super) ccd: bool,
+
+    pub(super) sleeping: bool,
+    /// Seconds spent below the sleep thresholds;
+    pub(super) sleep_time: f32,
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self {
+            body_type: BodyType::Dynamic,
+
+            position: Vec3::ZERO,
+            orientation: Quat::IDENTITY,
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+
+            mass: 1.0,
+            inv_mass: 1.0,
+            center: Vec3::ZERO,
+            inertia: Mat3::IDENTITY,
+            inv_inertia: Mat3::IDENTITY,
+
+            force: Vec3::ZERO,
+            torque: Vec3::ZERO,
+
+            collider: None,
+            ccd: false,
+
+            sleeping: false,
+            sleep_time: 0.0,
+        }
+    }
+}
+
+impl RigidBody {
+    /// Dynamic body with unit mass && inertia;
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Body that never moves;
+    pub fn fixed() -> Self {
+        Self::default().with_type(BodyType::Static)
+    }
+
+    /// Body moved only by its velocity;
+    pub fn kinematic() -> Self {
+        Self::default().with_type(BodyType::Kinematic)
+    }
+
+    pub fn with_type(mut self, body_type: BodyType) -> Self {
+        self.body_type = body_type;
+        self.update_mass();
+        self
+    }
+
+    pub fn with_position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Quat) -> Self {
+        self.orientation = orientation.normalize();
+        self
+    }
+
+    pub fn with_linear_velocity(mut self, velocity: Vec3) -> Self {
+        self.linear_velocity = velocity;
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, velocity: Vec3) -> Self {
+        self.angular_velocity = velocity;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self.update_mass();
+        self
+    }
+
+    /// Local (body space) inertia tensor;
+    pub fn with_inertia(mut self, inertia: Mat3) -> Self {
+        self.inertia = inertia;
+        self.update_mass();
+        self
+    }
+
+    /// Mass, inertia && the body space center of mass the body turns about;
+    pub fn with_mass_properties(mut self, properties: MassProperties) -> Self {
+        self.mass = properties.mass;
+        self.center = properties.center;
+        self.inertia = properties.inertia;
+        self.update_mass();
+        self
+    }
+
+    /// Mass && inertia of the collider shape filled with the density, set the collider first;
+    pub fn with_density(self, density: f32) -> Self {
+        match self.collider.as_ref().map(|c| c.shape.mass_properties(density)) {
+            Some(properties) => self.with_mass_properties(properties),
+            None => self
+        }
+    }
+
+    pub fn with_collider(mut self, collider: Collider) -> Self {
+        self.collider = Some(collider);
+        self
+    }
+
+    /// Continuous collision detection, for small && fast bodies;
+    pub fn with_ccd(mut self, ccd: bool) -> Self {
+        self.ccd = ccd;
+        self
+    }
+
+    /// Recompute inverse mass properties;
+    fn update_mass(&mut self) {
+        match self.body_type {
+            BodyType::Dynamic if self.mass > 0.0 => {
+                self.inv_mass = 1.0 / self.mass;
+                self.inv_inertia = match self.inertia.determinant().abs() > f32::EPSILON {
+                    true => self.inertia.inverse(),
+                    false => Mat3::ZERO
+                };
+            }
+            _ => {
+                self.inv_mass = 0.0;
+                self.inv_inertia = Mat3::ZERO;
+            }
+        }
+    }
+
+    pub fn body_type(&self) -> BodyType {
+        self.body_type
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic
+    }
+
+    /// Dynamic && awake;
+    pub(super) fn is_active(&self) -> bool {
+        self.is_dynamic() && !self.sleeping
+    }
+
+    /// Sleeping bodies are skipped by the simulation until something wakes their island;
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    pub fn wake(&mut self) {
+        self.sleeping = false;
+        self.sleep_time = 0.0;
+    }
+
+    pub(super) fn sleep(&mut self) {
+        self.sleeping = true;
+        self.linear_velocity = Vec3::ZERO;
+        self.angular_velocity = Vec3::ZERO;
+        self.force = Vec3::ZERO;
+        self.torque = Vec3::ZERO;
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+        self.wake();
+    }
+
+    /// Current position && orientation;
+    pub fn isometry(&self) -> Isometry {
+        Isometry::new(self.position, self.orientation)
+    }
+
+    /// World space center of mass;
+    pub fn center_of_mass(&self) -> Vec3 {
+        self.position + self.orientation * self.center
+    }
+
+    /// Body space center of mass;
+    pub fn local_center_of_mass(&self) -> Vec3 {
+        self.center
+    }
+
+    pub fn collider(&self) -> Option<&Collider> {
+        self.collider.as_ref()
+    }
+
+    pub fn set_collider(&mut self, collider: Option<Collider>) {
+        self.collider = collider;
+    }
+
+    pub fn ccd(&self) -> bool {
+        self.ccd
+    }
+
+    pub fn set_ccd(&mut self, ccd: bool) {
+        self.ccd = ccd;
+    }
+
+    pub fn orientation(&self) -> Quat {
+        self.orientation
+    }
+
+    pub fn set_orientation(&mut self, orientation: Quat) {
+        self.orientation = orientation.normalize();
+        self.wake();
+    }
+
+    pub fn set_linear_velocity(&mut self, velocity: Vec3) {
+        self.linear_velocity = velocity;
+        self.wake();
+    }
+
+    pub fn angular_velocity(&self) -> Vec3 {
+        self.angular_velocity
+    }
+
+    pub fn set_angular_velocity(&mut self, velocity: Vec3) {
+        self.angular_velocity = velocity;
+        self.wake();
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub fn inv_mass(&self) -> f32 {
+        self.inv_mass
+    }
+
+    pub fn inertia(&self) -> Mat3 {
+        self.inertia
+    }
+
+    /// Inverse inertia tensor rotated into world space;
+    pub fn world_inv_inertia(&self) -> Mat3 {
+        let rotation = Mat3::from_quat(self.orientation);
+        rotation * self.inv_inertia * rotation.transpose()
+    }
+
+    /// Velocity of the center of mass;
+    pub fn linear_velocity(&self) -> Vec3 {
+        self.linear_velocity
+    }
+
+    /// Velocity of a world space point attached to the body;
+    pub fn velocity_at(&self, point: Vec3) -> Vec3 {
+        self.linear_velocity + self.angular_velocity.cross(point - self.center_of_mass())
+    }
+
+    /// Apply force at the center of mass until the next step;
+    pub fn apply_force(&mut self, force: Vec3) {
+        self.force += force;
+        self.wake();
+    }
+
+    /// Apply force at a world space point until the next step;
+    pub fn apply_force_at(&mut self, force: Vec3, point: Vec3) {
+        self.force += force;
+        self.torque += (point - self.center_of_mass()).cross(force);
+        self.wake();
+    }
+
+    pub fn apply_torque(&mut self, torque: Vec3) {
+        self.torque += torque;
+        self.wake();
+    }
+
+    /// Accumulate force without waking, continuous generators leave resting bodies asleep;
+    pub(super) fn add_force(&mut self, force: Vec3) {
+        self.force += force;
+    }
+
+    pub(super) fn add_force_at(&mut self, force: Vec3, point: Vec3) {
+        self.force += force;
+        self.torque += (point - self.center_of_mass()).cross(force);
+    }
+
+    pub(super) fn add_torque(&mut self, torque: Vec3) {
+        self.torque += torque;
+    }
+
+    /// Instant velocity change at the center of mass;
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        self.linear_velocity += impulse * self.inv_mass;
+        self.wake();
+    }
+
+    /// Instant velocity change at a world space point;
+    pub fn apply_impulse_at(&mut self, impulse: Vec3, point: Vec3) {
+        self.linear_velocity += impulse * self.inv_mass;
+        self.angular_velocity += self.world_inv_inertia() * (point - self.center_of_mass()).cross(impulse);
+        self.wake();
+    }
+
+    /// Instant angular velocity change;
+    pub fn apply_angular_impulse(&mut self, impulse: Vec3) {
+        self.angular_velocity += self.world_inv_inertia() * impulse;
+        self.wake();
+    }
+
+    /// Semi-implicit euler velocity update;
+    pub(super) fn integrate_velocity(&mut self, gravity: Vec3, dt: f32) {
+        if self.is_active() {
+            let acceleration = gravity + self.force * self.inv_mass;
+            self.linear_velocity += acceleration * dt;
+            self.angular_velocity += self.world_inv_inertia() * self.torque * dt;
+        }
+
+        self.force = Vec3::ZERO;
+        self.torque = Vec3::ZERO;
+    }
+
+    /// Constant velocities of the body position over the next step;
+    pub(super) fn motion(&self) -> Motion {
+        match self.body_type == BodyType::Static || self.sleeping {
+            true => Motion::default(),
+            false => Motion::new(self.velocity_at(self.position), self.angular_velocity)
+        }
+    }
+
+    pub(super) fn integrate_position(&mut self, dt: f32) {
+        if self.body_type == BodyType::Static || self.sleeping {
+            return;
+        }
+
+        self.displace(self.linear_velocity, self.angular_velocity, dt);
+    }
+
+    /// Move the center of mass && turn about it by the given velocities without keeping them;
+    pub(super) fn displace(&mut self, linear: Vec3, angular: Vec3, dt: f32) {
+        let center = self.center_of_mass() + linear * dt;
+
+        let spin = Quat::from_xyzw(angular.x, angular.y, angular.z, 0.0) * self.orientation;
+        self.orientation = (self.orientation + spin * (0.5 * dt)).normalize();
+        self.position = center - self.orientation * self.center;
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::{BTreeSet, HashMap};
+
+use glam::Vec3;
+
+use super::{ordered, BodyPair, BroadPhase, PairEvents};
+use super::super::body::BodyHandle;
+use super::super::shape::Aabb;
+
+const NULL: usize = usize::MAX;
+const DEFAULT_MARGIN: f32 = 0.1;
+
+#[derive(Debug, Clone)]
+struct Node {
+    aabb: Aabb,
+    parent: usize,
+    child1: usize,
+    child2: usize,
+    height: i32,
+    handle: Option<BodyHandle>,
+}
+
+impl Node {
+    fn is_leaf(&self) -> bool {
+        self.child1 == NULL
+    }
+}
+
+/// Dynamic bounding volume hierarchy;
+/// Leaves hold fattened bounds, so small motions don't touch the tree;
+/// Inserts pick the cheapest sibling by surface area && rotations keep the tree balanced;
+pub struct Bvh {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: usize,
+    margin: f32,
+
+    leaves: HashMap<BodyHandle, usize>,
+    moved: BTreeSet<BodyHandle>,
+    neighbors: HashMap<BodyHandle, BTreeSet<BodyHandle>>,
+    pairs: BTreeSet<BodyPair>,
+    events: PairEvents,
+}
+
+impl Default for Bvh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bvh {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: NULL,
+            margin: DEFAULT_MARGIN,
+
+            leaves: HashMap::new(),
+            moved: BTreeSet::new(),
+            neighbors: HashMap::new(),
+            pairs: BTreeSet::new(),
+            events: PairEvents::default(),
+        }
+    }
+
+    /// Extra space around every leaf;
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin.max(0.0);
+        self
+    }
+
+    /// Tree height, zero for a single leaf;
+    pub fn height(&self) -> i32 {
+        match self.root {
+            NULL => 0,
+            root => self.nodes[root].height
+        }
+    }
+
+    /// Fattened bounds stored in the tree;
+    pub fn fat_aabb(&self, handle: BodyHandle) -> Option<Aabb> {
+        self.leaves.get(&handle).map(|leaf| self.nodes[*leaf].aabb)
+    }
+
+    /// Visit nodes whose bounds pass the test, calls back with every accepted leaf;
+    pub fn traverse(&self, mut test: impl FnMut(&Aabb) -> bool, mut callback: impl FnMut(BodyHandle)) {
+        if self.root == NULL {
+            return;
+        }
+
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !test(&node.aabb) {
+                continue;
+            }
+
+            match node.handle {
+                Some(handle) => callback(handle),
+                None => {
+                    stack.push(node.child1);
+                    stack.push(node.child2);
+                }
+            }
+        }
+    }
+
+    fn allocate(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, index: usize) {
+        self.nodes[index].handle = None;
+        self.nodes[index].child1 = NULL;
+        self.nodes[index].child2 = NULL;
+        self.free.push(index);
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        if self.root == NULL {
+            self.root = leaf;
+            self.nodes[leaf].parent = NULL;
+            return;
+        }
+
+        // Walk down choosing the cheaper subtree by surface area;
+        let leaf_aabb = self.nodes[leaf].aabb;
+        let mut index = self.root;
+        while !self.nodes[index].is_leaf() {
+            let node = &self.nodes[index];
+            let (child1, child2) = (node.child1, node.child2);
+
+            let area = node.aabb.surface_area();
+            let combined = node.aabb.merged(&leaf_aabb).surface_area();
+            let cost = 2.0 * combined;
+            let inheritance = 2.0 * (combined - area);
+
+            let child_cost = |child: usize| {
+                let child = &self.nodes[child];
+                let merged = leaf_aabb.merged(&child.aabb).surface_area();
+                match child.is_leaf() {
+                    true => merged + inheritance,
+                    false => merged - child.aabb.surface_area() + inheritance
+                }
+            };
+
+            let cost1 = child_cost(child1);
+            let cost2 = child_cost(child2);
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+
+            index = match cost1 < cost2 {
+                true => child1,
+                false => child2
+            };
+        }
+
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate(Node {
+            aabb: leaf_aabb.merged(&self.nodes[sibling].aabb),
+            parent: old_parent,
+            child1: sibling,
+            child2: leaf,
+            height: self.nodes[sibling].height + 1,
+            handle: None,
+        });
+
+        self.nodes[sibling].parent = new_parent;
+        self.nodes[leaf].parent = new_parent;
+        self.replace_child(old_parent, sibling, new_parent);
+
+        self.refit(self.nodes[leaf].parent);
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if leaf == self.root {
+            self.root = NULL;
+            return;
+        }
+
+        let parent = self.nodes[leaf].parent;
+        let grand_parent = self.nodes[parent].parent;
+        let sibling = match self.nodes[parent].child1 == leaf {
+            true => self.nodes[parent].child2,
+            false => self.nodes[parent].child1
+        };
+
+        self.replace_child(grand_parent, parent, sibling);
+        self.nodes[sibling].parent = grand_parent;
+        self.release(parent);
+
+        self.refit(grand_parent);
+    }
+
+    /// Point the parent (or root) at a new child;
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if parent == NULL {
+            self.root = new;
+        } else if self.nodes[parent].child1 == old {
+            self.nodes[parent].child1 = new;
+        } else {
+            self.nodes[parent].child2 = new;
+        }
+    }
+
+    /// Rebalance && recompute bounds up to the root;
+    fn refit(&mut self, mut index: usize) {
+        while index != NULL {
+            index = self.balance(index);
+
+            let (child1, child2) = (self.nodes[index].child1, self.nodes[index].child2);
+            self.nodes[index].height = 1 + self.nodes[child1].height.max(self.nodes[child2].height);
+            self.nodes[index].aabb = self.nodes[child1].aabb.merged(&self.nodes[child2].aabb);
+
+            index = self.nodes[index].parent;
+        }
+    }
+
+    /// Rotate the taller grand child up when the subtree is unbalanced, returns the subtree root;
+    fn balance(&mut self, a: usize) -> usize {
+        if self.nodes[a].is_leaf() || self.nodes[a].height < 2 {
+            return a;
+        }
+
+        let b = self.nodes[a].child1;
+        let c = self.nodes[a].child2;
+        let balance = self.nodes[c].height - self.nodes[b].height;
+
+        match balance {
+            _ if balance > 1 => self.rotate(a, c, false),
+            _ if balance < -1 => self.rotate(a, b, true),
+            _ => a
+        }
+    }
+
+    /// Lift child `up` of `a` into its place;
+    fn rotate(&mut self, a: usize, up: usize, up_is_first: bool) -> usize {
+        let other = match up_is_first {
+            true => self.nodes[a].child2,
+            false => self.nodes[a].child1
+        };
+        let (f, g) = (self.nodes[up].child1, self.nodes[up].child2);
+
+        let parent = self.nodes[a].parent;
+        self.nodes[up].child1 = a;
+        self.nodes[up].parent = parent;
+        self.nodes[a].parent = up;
+        self.replace_child(parent, a, up);
+
+        // Taller grand child stays with `up`, the shorter one moves to `a`;
+        let (keep, give) = match self.nodes[f].height > self.nodes[g].height {
+            true => (f, g),
+            false => (g, f)
+        };
+
+        self.nodes[up].child2 = keep;
+        match up_is_first {
+            true => self.nodes[a].child1 = give,
+            false => self.nodes[a].child2 = give
+        }
+        self.nodes[give].parent = a;
+
+        self.nodes[a].aabb = self.nodes[other].aabb.merged(&self.nodes[give].aabb);
+        self.nodes[a].height = 1 + self.nodes[other].height.max(self.nodes[give].height);
+        self.nodes[up].aabb = self.nodes[a].aabb.merged(&self.nodes[keep].aabb);
+        self.nodes[up].height = 1 + self.nodes[a].height.max(self.nodes[keep].height);
+
+        up
+    }
+
+    fn link(&mut self, a: BodyHandle, b: BodyHandle) {
+        self.neighbors.entry(a).or_default().insert(b);
+        self.neighbors.entry(b).or_default().insert(a);
+
+        let pair = ordered(a, b);
+        if self.pairs.insert(pair) {
+            self.events.added.push(pair);
+        }
+    }
+
+    fn unlink(&mut self, a: BodyHandle, b: BodyHandle) {
+        if let Some(set) = self.neighbors.get_mut(&a) {
+            set.remove(&b);
+        }
+        if let Some(set) = self.neighbors.get_mut(&b) {
+            set.remove(&a);
+        }
+
+        let pair = ordered(a, b);
+        if self.pairs.remove(&pair) {
+            self.events.removed.push(pair);
+        }
+    }
+}
+
+impl BroadPhase for Bvh {
+    fn contains(&self, handle: BodyHandle) -> bool {
+        self.leaves.contains_key(&handle)
+    }
+
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if self.contains(handle) {
+            return self.set_aabb(handle, aabb);
+        }
+
+        let leaf = self.allocate(Node {
+            aabb: aabb.expanded(self.margin),
+            parent: NULL,
+            child1: NULL,
+            child2: NULL,
+            height: 0,
+            handle: Some(handle),
+        });
+
+        self.insert_leaf(leaf);
+        self.leaves.insert(handle, leaf);
+        self.moved.insert(handle);
+    }
+
+    fn remove(&mut self, handle: BodyHandle) {
+        let Some(leaf) = self.leaves.remove(&handle) else { return };
+
+        self.remove_leaf(leaf);
+        self.release(leaf);
+        self.moved.remove(&handle);
+
+        let neighbors = self.neighbors.remove(&handle).unwrap_or_default();
+        for other in neighbors {
+            self.unlink(handle, other);
+        }
+    }
+
+    fn set_aabb(&mut self, handle: BodyHandle, aabb: Aabb) {
+        let Some(&leaf) = self.leaves.get(&handle) else { return };
+        if self.nodes[leaf].aabb.contains(&aabb) {
+            return;
+        }
+
+        self.remove_leaf(leaf);
+        self.nodes[leaf].aabb = aabb.expanded(self.margin);
+        self.insert_leaf(leaf);
+        self.moved.insert(handle);
+    }
+
+    fn update(&mut self) -> PairEvents {
+        let moved = std::mem::take(&mut self.moved);
+        for handle in moved {
+            let Some(&leaf) = self.leaves.get(&handle) else { continue };
+            let fat = self.nodes[leaf].aabb;
+
+            let mut now = BTreeSet::new();
+            self.traverse(|aabb| aabb.overlaps(&fat), |other| {
+                if other != handle {
+                    now.insert(other);
+                }
+            });
+
+            let before = self.neighbors.get(&handle).cloned().unwrap_or_default();
+            for gone in before.difference(&now) {
+                self.unlink(handle, *gone);
+            }
+            for new in now.difference(&before) {
+                self.link(handle, *new);
+            }
+        }
+
+        let mut events = std::mem::take(&mut self.events);
+
+        // Drop pairs that toggled back during the same update;
+        events.added.sort();
+        events.added.dedup();
+        events.added.retain(|pair| self.pairs.contains(pair));
+
+        events.removed.sort();
+        events.removed.dedup();
+        events.removed.retain(|pair| !self.pairs.contains(pair));
+
+        events
+    }
+
+    fn pairs(&self) -> Box<dyn Iterator<Item=&BodyPair> + '_> {
+        Box::new(self.pairs.iter())
+    }
+
+    fn query_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(BodyHandle)) {
+        self.traverse(|node| node.overlaps(aabb), callback);
+    }
+
+    fn query_ray(&self, origin: Vec3, translation: Vec3, callback: &mut dyn FnMut(BodyHandle)) {
+        self.traverse(|node| node.ray_fraction(origin, translation).is_some(), callback);
+    }
+}
@@ -0,0 +1,130 @@
+use glam::Vec3;
+
+use super::body::BodyHandle;
+use super::shape::Aabb;
+
+pub use bvh::Bvh;
+pub use sap::SweepAndPrune;
+
+mod bvh;
+mod sap;
+
+/// Potentially touching body pair, lower handle first;
+pub type BodyPair = (BodyHandle, BodyHandle);
+
+pub(super) fn ordered(a: BodyHandle, b: BodyHandle) -> BodyPair {
+    match a < b {
+        true => (a, b),
+        false => (b, a)
+    }
+}
+
+/// Overlap changes since the previous broad phase update;
+#[derive(Debug, Default, Clone)]
+pub struct PairEvents {
+    pub added: Vec<BodyPair>,
+    pub removed: Vec<BodyPair>,
+}
+
+/// Broad phase strategy: keeps body bounds && finds overlapping pairs;
+pub trait BroadPhase {
+    fn contains(&self, handle: BodyHandle) -> bool;
+
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb);
+
+    fn remove(&mut self, handle: BodyHandle);
+
+    /// Store new bounds of a body already inserted;
+    fn set_aabb(&mut self, handle: BodyHandle, aabb: Aabb);
+
+    /// Find overlap changes since the previous update;
+    fn update(&mut self) -> PairEvents;
+
+    /// Currently overlapping pairs;
+    fn pairs(&self) -> Box<dyn Iterator<Item=&BodyPair> + '_>;
+
+    /// Every body whose bounds overlap the box;
+    fn query_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(BodyHandle));
+
+    /// Every body whose bounds the segment [origin; origin + translation] crosses;
+    fn query_ray(&self, origin: Vec3, translation: Vec3, callback: &mut dyn FnMut(BodyHandle));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// Boxes inserted, moved && removed at random, the pair set must follow the brute force one;
+    fn matches_brute_force(mut broad_phase: impl BroadPhase, exact: bool) {
+        let mut seed = 7u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+
+        let mut boxes: Vec<Option<Aabb>> = vec![None; 150];
+        let mut tracked = BTreeSet::new();
+
+        for round in 0..60 {
+            for (i, slot) in boxes.iter_mut().enumerate() {
+                let handle = BodyHandle(i, 0);
+                let roll = random();
+                match slot {
+                    None if roll < 0.3 => {
+                        let min = Vec3::new(random(), random(), random()) * 20.0;
+                        let aabb = Aabb::new(min, min + Vec3::new(random(), random(), random()) * 2.0 + 0.1);
+                        broad_phase.insert(handle, aabb);
+                        *slot = Some(aabb);
+                    }
+                    Some(_) if roll < 0.05 => {
+                        broad_phase.remove(handle);
+                        *slot = None;
+                    }
+                    Some(aabb) if roll < 0.6 => {
+                        let offset = Vec3::new(random(), random(), random()) - 0.5;
+                        *aabb = Aabb::new(aabb.min + offset, aabb.max + offset);
+                        broad_phase.set_aabb(handle, *aabb);
+                    }
+                    _ => ()
+                }
+            }
+
+            let events = broad_phase.update();
+            for pair in &events.removed {
+                assert!(tracked.remove(pair), "round {round}: removed {pair:?} was never added");
+            }
+            for pair in &events.added {
+                assert!(tracked.insert(*pair), "round {round}: added {pair:?} twice");
+            }
+
+            // Pairs of removed bodies leave without events;
+            tracked.retain(|(a, b): &BodyPair| boxes[a.0].is_some() && boxes[b.0].is_some());
+            let pairs: BTreeSet<BodyPair> = broad_phase.pairs().copied().collect();
+            assert_eq!(pairs, tracked, "round {round}: events disagree with pairs");
+
+            let overlapping: BTreeSet<BodyPair> = (0..boxes.len())
+                .flat_map(|a| (a + 1..boxes.len()).map(move |b| (a, b)))
+                .filter(|&(a, b)| matches!((&boxes[a], &boxes[b]), (Some(x), Some(y)) if x.overlaps(y)))
+                .map(|(a, b)| (BodyHandle(a, 0), BodyHandle(b, 0)))
+                .collect();
+
+            match exact {
+                true => assert_eq!(pairs, overlapping, "round {round}"),
+                false => assert!(pairs.is_superset(&overlapping), "round {round}: missed an overlap")
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_and_prune_matches_brute_force() {
+        matches_brute_force(SweepAndPrune::new(), true);
+    }
+
+    #[test]
+    fn bvh_matches_brute_force() {
+        matches_brute_force(Bvh::new().with_margin(0.0), true);
+        matches_brute_force(Bvh::new(), false);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::{BTreeSet, HashMap};
+
+use glam::Vec3;
+
+use super::{ordered, BodyPair, BroadPhase, PairEvents};
+use super::super::body::BodyHandle;
+use super::super::shape::Aabb;
+
+#[derive(Debug, Clone, Copy)]
+struct Endpoint {
+    value: f32,
+    handle: BodyHandle,
+    is_min: bool,
+}
+
+/// Incremental sweep && prune over three axes;
+/// Endpoint lists stay sorted between frames, so coherent motion costs few swaps;
+pub struct SweepAndPrune {
+    axes: [Vec<Endpoint>; 3],
+    aabbs: HashMap<BodyHandle, Aabb>,
+    pairs: BTreeSet<BodyPair>,
+    events: PairEvents,
+}
+
+impl Default for SweepAndPrune {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SweepAndPrune {
+    pub fn new() -> Self {
+        Self {
+            axes: [Vec::new(), Vec::new(), Vec::new()],
+            aabbs: HashMap::new(),
+            pairs: BTreeSet::new(),
+            events: PairEvents::default(),
+        }
+    }
+
+    pub fn aabb(&self, handle: BodyHandle) -> Option<&Aabb> {
+        self.aabbs.get(&handle)
+    }
+
+    /// Insertion sort, every swap of a min && a max endpoint is an overlap change;
+    fn sort_axis(&mut self, axis: usize) {
+        for i in 1..self.axes[axis].len() {
+            let mut j = i;
+            while j > 0 && self.axes[axis][j].value < self.axes[axis][j - 1].value {
+                let moving = self.axes[axis][j];
+                let passed = self.axes[axis][j - 1];
+
+                if moving.handle != passed.handle {
+                    match (moving.is_min, passed.is_min) {
+                        (true, false) => self.begin_overlap(moving.handle, passed.handle),
+                        (false, true) => self.end_overlap(moving.handle, passed.handle),
+                        _ => ()
+                    }
+                }
+
+                self.axes[axis].swap(j, j - 1);
+                j -= 1;
+            }
+        }
+    }
+
+    fn begin_overlap(&mut self, a: BodyHandle, b: BodyHandle) {
+        let overlapping = match (self.aabbs.get(&a), self.aabbs.get(&b)) {
+            (Some(aabb_a), Some(aabb_b)) => aabb_a.overlaps(aabb_b),
+            _ => false
+        };
+
+        let pair = ordered(a, b);
+        if overlapping && self.pairs.insert(pair) {
+            self.events.added.push(pair);
+        }
+    }
+
+    fn end_overlap(&mut self, a: BodyHandle, b: BodyHandle) {
+        let pair = ordered(a, b);
+        if self.pairs.remove(&pair) {
+            self.events.removed.push(pair);
+        }
+    }
+}
+
+impl BroadPhase for SweepAndPrune {
+    fn contains(&self, handle: BodyHandle) -> bool {
+        self.aabbs.contains_key(&handle)
+    }
+
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if self.aabbs.insert(handle, aabb).is_some() {
+            return;
+        }
+
+        // New endpoints start at the end && are sorted into place by the next update;
+        for (axis, endpoints) in self.axes.iter_mut().enumerate() {
+            endpoints.push(Endpoint { value: aabb.min[axis], handle, is_min: true });
+            endpoints.push(Endpoint { value: aabb.max[axis], handle, is_min: false });
+        }
+    }
+
+    fn remove(&mut self, handle: BodyHandle) {
+        if self.aabbs.remove(&handle).is_none() {
+            return;
+        }
+
+        for endpoints in &mut self.axes {
+            endpoints.retain(|e| e.handle != handle);
+        }
+
+        let gone: Vec<BodyPair> = self.pairs.iter()
+            .copied()
+            .filter(|(a, b)| *a == handle || *b == handle)
+            .collect();
+
+        for pair in gone {
+            self.pairs.remove(&pair);
+            self.events.removed.push(pair);
+        }
+    }
+
+    /// Store new bounds, endpoints are refreshed by the next update;
+    fn set_aabb(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if let Some(stored) = self.aabbs.get_mut(&handle) {
+            *stored = aabb;
+        }
+    }
+
+    fn pairs(&self) -> Box<dyn Iterator<Item=&BodyPair> + '_> {
+        Box::new(self.pairs.iter())
+    }
+
+    /// Linear scan, sweep && prune has no spatial index for queries;
+    fn query_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(BodyHandle)) {
+        for (handle, bounds) in &self.aabbs {
+            if bounds.overlaps(aabb) {
+                callback(*handle);
+            }
+        }
+    }
+
+    fn query_ray(&self, origin: Vec3, translation: Vec3, callback: &mut dyn FnMut(BodyHandle)) {
+        for (handle, bounds) in &self.aabbs {
+            if bounds.ray_fraction(origin, translation).is_some() {
+                callback(*handle);
+            }
+        }
+    }
+
+    /// Restore sorted order && return pair changes since the last update;
+    fn update(&mut self) -> PairEvents {
+        for axis in 0..3 {
+            for endpoint in self.axes[axis].iter_mut() {
+                let aabb = &self.aabbs[&endpoint.handle];
+                endpoint.value = match endpoint.is_min {
+                    true => aabb.min[axis],
+                    false => aabb.max[axis]
+                };
+            }
+
+            self.sort_axis(axis);
+        }
+
+        let mut events = std::mem::take(&mut self.events);
+
+        // Drop pairs that toggled back during the same update;
+        events.added.sort();
+        events.added.dedup();
+        events.added.retain(|pair| self.pairs.contains(pair));
+
+        events.removed.sort();
+        events.removed.dedup();
+        events.removed.retain(|pair| !self.pairs.contains(pair));
+
+        events
+    }
+}
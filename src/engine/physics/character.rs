@@ -0,0 +1,383 @@
+use std::f32::consts::FRAC_PI_4;
+
+use glam::{Quat, Vec3};
+
+use super::body::{BodyHandle, RigidBody};
+use super::collision::collide;
+use super::query::{QueryFilter, QueryHit};
+use super::shape::{Isometry, Shape};
+use super::World;
+
+/// Sweeps per move, each slides what's left along the surface hit before;
+const MAX_SLIDES: usize = 4;
+/// Sweeps use the capsule grown by this much, keeping a gap to every surface so the next one doesn't start touching it;
+const SKIN: f32 = 0.01;
+/// Backs off every surface hit by this much, just past where sweeps count as touching it;
+const LIFT: f32 = 0.0025;
+
+/// Surface the character stands on;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ground {
+    pub body: BodyHandle,
+    pub point: Vec3,
+    /// Pointing up, out of the ground;
+    pub normal: Vec3,
+}
+
+/// Moves a kinematic capsule by sweeping it through the world && sliding along whatever it hits, Y is up;
+/// Sets the body's velocity, so the next step carries it to where the sweeps ended && shoves dynamic bodies aside;
+/// Only fixed && kinematic bodies block it, dynamic ones are ground to stand on but never walls;
+#[derive(Debug, Clone)]
+pub struct CharacterController {
+    body: BodyHandle,
+    /// Tallest ledge walked onto without jumping;
+    step_height: f32,
+    /// Steepest walkable slope in radians, steeper ones are walls;
+    max_slope: f32,
+    /// Farthest it's pulled down onto the ground, so it doesn't fly off slopes && stairs;
+    snap_distance: f32,
+    ground: Option<Ground>,
+}
+
+impl CharacterController {
+    /// Controller of a kinematic body with a capsule collider;
+    pub fn new(body: BodyHandle) -> Self {
+        Self {
+            body,
+            step_height: 0.3,
+            max_slope: FRAC_PI_4,
+            snap_distance: 0.2,
+            ground: None,
+        }
+    }
+
+    pub fn with_step_height(mut self, height: f32) -> Self {
+        self.step_height = height.max(0.0);
+        self
+    }
+
+    pub fn with_max_slope(mut self, angle: f32) -> Self {
+        self.max_slope = angle;
+        self
+    }
+
+    pub fn with_snap_distance(mut self, distance: f32) -> Self {
+        self.snap_distance = distance.max(0.0);
+        self
+    }
+
+    pub fn body(&self) -> BodyHandle {
+        self.body
+    }
+
+    /// Standing on walkable ground after the last move;
+    pub fn is_grounded(&self) -> bool {
+        self.ground.is_some()
+    }
+
+    pub fn ground(&self) -> Option<Ground> {
+        self.ground
+    }
+
+    /// Walk by `translation` over the next step of `dt`, riding along with the ground it stands on;
+    /// Returns the translation the body will actually make, zero without a body or capsule collider or time to move in;
+    pub fn move_by(&mut self, world: &mut World, translation: Vec3, dt: f32) -> Vec3 {
+        if dt <= 0.0 {
+            return Vec3::ZERO;
+        }
+
+        let Some(body) = world.body(self.body) else { return Vec3::ZERO };
+        let Some(&Shape::Capsule { half_height, radius }) = body.collider().map(|collider| collider.shape()) else { return Vec3::ZERO };
+        let (start, rotation) = (body.position(), body.orientation());
+
+        // Ride along with the ground, it moves over the same step so it doesn't block the ride;
+        let carry = self.ground
+            .and_then(|ground| world.body(ground.body).map(|body| body.velocity_at(ground.point) * dt))
+            .unwrap_or(Vec3::ZERO);
+
+        let shape = Shape::capsule(half_height, radius + SKIN);
+        let sweep = Sweep { world, shape: &shape, rotation, body: self.body, max_slope: self.max_slope };
+        let mut position = sweep.depenetrate(start);
+        position = sweep.slide(position, carry, false, self.ground.map(|ground| ground.body)).0;
+
+        let vertical = Vec3::Y * translation.y;
+        position = self.walk(&sweep, position, translation - vertical);
+        position = sweep.slide(position, vertical, vertical.y < 0.0, None).0;
+
+        // Jumping leaves the ground, standing snaps down onto it, falling only lands once touching;
+        self.ground = match translation.y > 0.0 {
+            true => None,
+            false => {
+                let reach = match self.ground.is_some() {
+                    true => self.snap_distance,
+                    false => SKIN
+                };
+                sweep.footing(position, reach).filter(|(hit, _)| self.is_walkable(hit.normal)).map(|(hit, drop)| {
+                    position -= Vec3::Y * drop;
+                    Ground { body: hit.body, point: hit.point, normal: hit.normal }
+                })
+            }
+        };
+
+        let moved = position - start;
+        if let Some(body) = world.body_mut(self.body) {
+            body.set_linear_velocity(moved / dt);
+        }
+
+        moved
+    }
+
+    /// Slide sideways, stepping up onto a ledge when a wall stops it while standing;
+    fn walk(&self, sweep: &Sweep, position: Vec3, horizontal: Vec3) -> Vec3 {
+        let (walked, hits) = sweep.slide(position, horizontal, false, None);
+        let blocked = hits.iter().any(|hit| !self.is_walkable(hit.normal));
+        if !blocked || self.ground.is_none() || self.step_height <= 0.0 {
+            return walked;
+        }
+
+        // Up, across && back down, only kept if it lands on walkable ground further along;
+        let raised = sweep.slide(position, Vec3::Y * self.step_height, false, None).0;
+        let across = sweep.slide(raised, horizontal, false, None).0;
+        let Some((hit, drop)) = sweep.footing(across, across.y - position.y) else { return walked };
+
+        // Rounded bottoms ride up edges, so the ledge is measured from the feet rather than how far it rose;
+        let stepped = across - Vec3::Y * drop;
+        let progress = |end: Vec3| flat(end - position).length();
+        let low = hit.point.y - sweep.feet(position) <= self.step_height + SKIN;
+        match low && self.is_walkable(hit.normal) && progress(stepped) > progress(walked) + SKIN {
+            true => stepped,
+            false => walked
+        }
+    }
+
+    fn is_walkable(&self, normal: Vec3) -> bool {
+        normal.y >= self.max_slope.cos()
+    }
+}
+
+/// Part of a vector along the ground;
+fn flat(v: Vec3) -> Vec3 {
+    Vec3::new(v.x, 0.0, v.z)
+}
+
+/// The character's grown capsule swept through the world, skipping its own body;
+struct Sweep<'a> {
+    world: &'a World,
+    shape: &'a Shape,
+    rotation: Quat,
+    body: BodyHandle,
+    max_slope: f32,
+}
+
+impl Sweep<'_> {
+    /// First hit moving by `translation` from `position`, dynamic bodies only block `footing` sweeps;
+    fn cast(&self, position: Vec3, translation: Vec3, footing: bool, skip: Option<BodyHandle>) -> Option<QueryHit> {
+        let predicate = |handle: BodyHandle, body: &RigidBody| (footing || !body.is_dynamic()) && Some(handle) != skip;
+        let filter = QueryFilter::new()
+            .with_exclude(self.body)
+            .with_sensors(false)
+            .with_predicate(&predicate);
+
+        self.world.cast_shape(self.shape, &Isometry::new(position, self.rotation), translation, &filter)
+    }
+
+    /// Sweep && slide along what's hit, backing off it by `LIFT`, returns the end && every hit;
+    /// Walls && slopes too steep to walk push straight back on sideways moves, so they can't be climbed;
+    fn slide(&self, mut position: Vec3, translation: Vec3, footing: bool, skip: Option<BodyHandle>) -> (Vec3, Vec<QueryHit>) {
+        let mut hits = Vec::new();
+        let mut remaining = translation;
+        for _ in 0..MAX_SLIDES {
+            let length = remaining.length();
+            if length <= f32::EPSILON {
+                break;
+            }
+
+            let Some(hit) = self.cast(position, remaining, footing, skip) else {
+                position += remaining;
+                break;
+            };
+
+            position += remaining * hit.fraction + hit.normal * LIFT;
+            remaining *= 1.0 - hit.fraction;
+
+            let normal = match remaining.y == 0.0 && hit.normal.y < self.max_slope.cos() {
+                true => flat(hit.normal).try_normalize().unwrap_or(hit.normal),
+                false => hit.normal
+            };
+            let into = remaining.dot(normal);
+            if into < 0.0 {
+                remaining -= normal * into;
+            }
+            hits.push(hit);
+        }
+
+        (position, hits)
+    }
+
+    /// Ground within `reach` below && how far down to move to rest `LIFT` above it;
+    /// The rounded bottom meets ledges on their edge, a ray just past it finds the top they'd stand on;
+    fn footing(&self, position: Vec3, reach: f32) -> Option<(QueryHit, f32)> {
+        let mut hit = self.cast(position, Vec3::NEG_Y * reach, true, None)?;
+        let drop = (hit.fraction * reach - LIFT).max(0.0);
+
+        let past = hit.point + flat(hit.point - position).normalize_or_zero() * SKIN;
+        let origin = Vec3::new(past.x, position.y, past.z);
+        let only = |handle: BodyHandle, _: &RigidBody| handle == hit.body;
+        let filter = QueryFilter::new().with_predicate(&only);
+        if let Some(surface) = self.world.cast_ray(origin, Vec3::NEG_Y * (position.y - past.y + SKIN), &filter) {
+            hit.normal = surface.normal;
+        }
+
+        Some((hit, drop))
+    }
+
+    /// Height of the lowest point of the shape;
+    fn feet(&self, position: Vec3) -> f32 {
+        position.y + (self.rotation * self.shape.support(self.rotation.inverse() * Vec3::NEG_Y)).y
+    }
+
+    /// Push out of what it overlaps, left behind by kinematic bodies moving into it;
+    fn depenetrate(&self, mut position: Vec3) -> Vec3 {
+        for _ in 0..MAX_SLIDES {
+            let iso = Isometry::new(position, self.rotation);
+            let filter = QueryFilter::new().with_exclude(self.body).with_sensors(false);
+            let deepest = self.world.overlap(self.shape, &iso, &filter).into_iter()
+                .filter_map(|handle| self.world.body(handle))
+                .filter(|body| !body.is_dynamic())
+                .filter_map(|body| collide(self.shape, &iso, body.collider()?.shape(), &body.isometry()))
+                .max_by(|a, b| a.max_depth().total_cmp(&b.max_depth()));
+
+            let Some(manifold) = deepest else { break };
+            position -= manifold.normal * (manifold.max_depth() + LIFT);
+        }
+
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Collider, RigidBody};
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Floor at zero && a capsule 1.6 tall standing on it at `x`;
+    fn scene(x: f32) -> (World, CharacterController) {
+        let mut world = World::new();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let body = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::new(x, 0.8 + SKIN, 0.0))
+            .with_collider(Collider::new(Shape::capsule(0.5, 0.3))));
+
+        (world, CharacterController::new(body))
+    }
+
+    /// Walk at `velocity` for `steps`, falling like a game would while in the air;
+    fn walk(world: &mut World, character: &mut CharacterController, velocity: Vec3, steps: usize) {
+        let mut fall = 0.0;
+        for _ in 0..steps {
+            fall = match character.is_grounded() {
+                true => 0.0,
+                false => fall - 9.81 * DT
+            };
+            character.move_by(world, (velocity + Vec3::Y * fall) * DT, DT);
+            world.step(DT);
+        }
+    }
+
+    fn position(world: &World, character: &CharacterController) -> Vec3 {
+        world.body(character.body()).unwrap().position()
+    }
+
+    #[test]
+    fn slides_along_a_wall() {
+        let (mut world, mut character) = scene(0.0);
+        world.add_body(RigidBody::fixed()
+            .with_position(Vec3::new(2.0, 1.0, 0.0))
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(0.1, 1.0, 5.0)))));
+        world.step(DT);
+
+        // Walking diagonally into it keeps the part along the wall;
+        walk(&mut world, &mut character, Vec3::new(2.0, 0.0, 1.0), 120);
+        let end = position(&world, &character);
+        assert!(end.x < 1.6 + 1e-3 && end.x > 1.55, "{end}");
+        assert!((end.z - 2.0).abs() < 0.05, "{end}");
+        assert!((end.y - 0.8).abs() < 0.02 && character.is_grounded(), "{end}");
+    }
+
+    #[test]
+    fn steps_up_a_ledge_but_not_a_wall() {
+        let climb = |height: f32| {
+            let (mut world, mut character) = scene(0.0);
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new(2.0, height * 0.5, 0.0))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(1.0, height * 0.5, 2.0)))));
+            world.step(DT);
+
+            walk(&mut world, &mut character, Vec3::X * 2.0, 90);
+            (position(&world, &character), character.is_grounded())
+        };
+
+        // A 20 cm ledge is walked onto, a 50 cm one stops it at its face;
+        let (on_ledge, grounded) = climb(0.2);
+        assert!((on_ledge.y - 1.0).abs() < 0.02 && on_ledge.x > 1.5 && grounded, "{on_ledge}");
+        let (at_wall, grounded) = climb(0.5);
+        assert!((at_wall.y - 0.8).abs() < 0.02 && at_wall.x < 0.7 + 1e-3 && grounded, "{at_wall}");
+    }
+
+    #[test]
+    fn walks_up_gentle_slopes_only() {
+        let climb = |angle: f32| {
+            let (mut world, mut character) = scene(-1.0);
+            world.add_body(RigidBody::fixed()
+                .with_position(Vec3::new(angle.cos(), angle.sin(), 0.0) * 3.0)
+                .with_orientation(Quat::from_rotation_z(angle))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(3.0, 0.01, 2.0)))));
+            world.step(DT);
+
+            walk(&mut world, &mut character, Vec3::X * 2.0, 120);
+            position(&world, &character)
+        };
+
+        // Up a 30 degree ramp, a 60 degree one is a wall;
+        assert!(climb(0.52).y > 1.5, "{}", climb(0.52));
+        assert!(climb(1.05).y < 0.9, "{}", climb(1.05));
+    }
+
+    #[test]
+    fn rides_a_moving_platform() {
+        let (mut world, mut character) = scene(0.5);
+        let platform = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::new(0.0, 0.5, 0.0))
+            .with_linear_velocity(Vec3::new(1.0, 0.5, 0.0))
+            .with_angular_velocity(Vec3::Y * 0.5)
+            .with_collider(Collider::new(Shape::cuboid(Vec3::new(1.0, 0.1, 1.0)))));
+        world.body_mut(character.body()).unwrap().set_position(Vec3::new(0.5, 1.4 + SKIN, 0.0));
+        world.step(DT);
+
+        // Standing still on it, carried over, up && around with it;
+        let body = character.body();
+        let on_platform = |world: &World| world.body(platform).unwrap().isometry().inverse_transform_point(world.body(body).unwrap().position());
+        walk(&mut world, &mut character, Vec3::ZERO, 10);
+        let before = on_platform(&world);
+        walk(&mut world, &mut character, Vec3::ZERO, 60);
+        let after = on_platform(&world);
+
+        assert!(before.distance(after) < 0.01 && (after.y - 0.9).abs() < 0.02, "{before} {after}");
+        assert_eq!(character.ground().map(|ground| ground.body), Some(platform));
+    }
+
+    #[test]
+    fn no_time_means_no_move() {
+        let (mut world, mut character) = scene(0.0);
+        walk(&mut world, &mut character, Vec3::X, 10);
+        let (before, velocity) = (position(&world, &character), world.body(character.body()).unwrap().linear_velocity());
+
+        assert_eq!(character.move_by(&mut world, Vec3::X, 0.0), Vec3::ZERO);
+        world.step(0.0);
+        let body = world.body(character.body()).unwrap();
+        assert_eq!((body.position(), body.linear_velocity()), (before, velocity));
+        assert!(character.is_grounded());
+    }
+}
@@ -0,0 +1,531 @@
+use glam::Vec3;
+
+use super::Boundary;
+use super::body::BodyHandle;
+use super::shape::Aabb;
+use super::slot::SlotHandle;
+
+/// Cloth slot inside the physics world && its generation;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClothHandle(pub(super) usize, pub(super) u32);
+
+impl ClothHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+}
+
+impl SlotHandle for ClothHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+/// Role of a distance constraint, each kind has its own compliance;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// Direct neighbors along a row or column;
+    Stretch,
+    /// Diagonal splitting each quad into its triangles;
+    Shear,
+    /// Opposite corners of triangles sharing an edge, resist folding;
+    Bend,
+}
+
+/// Distance constraint between two particles;
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    a: usize,
+    b: usize,
+    rest: f32,
+    kind: LinkKind,
+}
+
+/// Particle held in place, by a world point or by a point of a body;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pin {
+    pub index: usize,
+    pub body: Option<BodyHandle>,
+    /// World point without a body, point in body space with one;
+    pub point: Vec3,
+}
+
+/// Grid of particles held together by distance constraints, solved with XPBD small steps;
+/// Particle `column + row * columns` starts at `corner + width * column / (columns - 1) + height * row / (rows - 1)`;
+/// The world steps cloths after rigid bodies, they collide with every solid collider but don't push back;
+#[derive(Debug, Clone)]
+pub struct Cloth {
+    columns: usize,
+    rows: usize,
+    positions: Vec<Vec3>,
+    previous: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    particle_mass: f32,
+    links: Vec<Link>,
+    triangles: Vec<[u32; 3]>,
+    pins: Vec<Pin>,
+
+    /// Inverse stiffness of each link kind, zero is rigid;
+    stretch_compliance: f32,
+    shear_compliance: f32,
+    bend_compliance: f32,
+    /// Collision distance from colliders;
+    thickness: f32,
+    friction: f32,
+    /// Share of the velocity lost per second;
+    damping: f32,
+    /// Half the air density times the drag coefficient;
+    drag: f32,
+    wind: Vec3,
+    substeps: u32,
+}
+
+impl Cloth {
+    /// Flat sheet spanning `width` && `height` from `corner` with the given particle counts, at least two each;
+    pub fn new(corner: Vec3, width: Vec3, height: Vec3, columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.max(2), rows.max(2));
+        let step = |count: usize| 1.0 / (count - 1) as f32;
+
+        let positions: Vec<Vec3> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| corner + width * (column as f32 * step(columns)) + height * (row as f32 * step(rows)))
+            .collect();
+
+        let mut links = Vec::new();
+        let mut link = |a: usize, b: usize, kind: LinkKind| {
+            links.push(Link { a, b, rest: positions[a].distance(positions[b]), kind });
+        };
+
+        let index = |column: usize, row: usize| column + row * columns;
+        for row in 0..rows {
+            for column in 0..columns {
+                let i = index(column, row);
+                if column + 1 < columns {
+                    link(i, index(column + 1, row), LinkKind::Stretch);
+                }
+                if row + 1 < rows {
+                    link(i, index(column, row + 1), LinkKind::Stretch);
+                }
+                // The diagonal splitting the quad into its triangles, the other one bends across it;
+                if column + 1 < columns && row + 1 < rows {
+                    link(i, index(column + 1, row + 1), LinkKind::Shear);
+                    link(index(column + 1, row), index(column, row + 1), LinkKind::Bend);
+                }
+                // Opposite corners of the triangles sharing a quad's bottom && right edges;
+                if column + 1 < columns && row + 2 < rows {
+                    link(i, index(column + 1, row + 2), LinkKind::Bend);
+                }
+                if column + 2 < columns && row + 1 < rows {
+                    link(i, index(column + 2, row + 1), LinkKind::Bend);
+                }
+            }
+        }
+
+        let triangles = (0..rows - 1)
+            .flat_map(|row| (0..columns - 1).map(move |column| (column, row)))
+            .flat_map(|(column, row)| {
+                let [a, b, c, d] = [index(column, row), index(column + 1, row), index(column, row + 1), index(column + 1, row + 1)]
+                    .map(|i| i as u32);
+                [[a, b, d], [a, d, c]]
+            })
+            .collect();
+
+        let count = positions.len();
+        Self {
+            columns,
+            rows,
+            previous: positions.clone(),
+            positions,
+            velocities: vec![Vec3::ZERO; count],
+            particle_mass: 1.0 / count as f32,
+            links,
+            triangles,
+            pins: Vec::new(),
+            stretch_compliance: 0.0,
+            shear_compliance: 1e-4,
+            bend_compliance: 1e-2,
+            thickness: 0.02,
+            friction: 0.3,
+            damping: 0.1,
+            drag: 0.6,
+            wind: Vec3::ZERO,
+            substeps: 10,
+        }
+    }
+
+    /// Total mass in kilograms, spread evenly over the particles;
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.particle_mass = mass.max(f32::EPSILON) / self.positions.len() as f32;
+        self
+    }
+
+    pub fn with_stretch_compliance(mut self, compliance: f32) -> Self {
+        self.stretch_compliance = compliance.max(0.0);
+        self
+    }
+
+    pub fn with_shear_compliance(mut self, compliance: f32) -> Self {
+        self.shear_compliance = compliance.max(0.0);
+        self
+    }
+
+    /// Higher is floppier, near zero gives stiff paper;
+    pub fn with_bend_compliance(mut self, compliance: f32) -> Self {
+        self.bend_compliance = compliance.max(0.0);
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness.max(0.0);
+        self
+    }
+
+    /// Share of the sliding motion lost against colliders;
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.max(0.0);
+        self
+    }
+
+    /// Aerodynamic drag of each triangle, zero ignores the air && the wind;
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag.max(0.0);
+        self
+    }
+
+    pub fn with_wind(mut self, wind: Vec3) -> Self {
+        self.wind = wind;
+        self
+    }
+
+    /// More substeps stiffen the constraints at a linear cost;
+    pub fn with_substeps(mut self, substeps: u32) -> Self {
+        self.substeps = substeps.max(1);
+        self
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Particle index of a grid cell;
+    pub fn index(&self, column: usize, row: usize) -> usize {
+        column + row * self.columns
+    }
+
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.velocities
+    }
+
+    /// Particle indices of the sheet's triangles, two per grid quad;
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    /// Area weighted vertex normals of the current shape;
+    pub fn normals(&self) -> Vec<Vec3> {
+        let mut normals = vec![Vec3::ZERO; self.positions.len()];
+        for &[a, b, c] in &self.triangles {
+            let [a, b, c] = [a, b, c].map(|i| i as usize);
+            let normal = (self.positions[b] - self.positions[a]).cross(self.positions[c] - self.positions[a]);
+            for i in [a, b, c] {
+                normals[i] += normal;
+            }
+        }
+
+        normals.into_iter().map(|n| n.normalize_or_zero()).collect()
+    }
+
+    pub fn wind(&self) -> Vec3 {
+        self.wind
+    }
+
+    pub fn set_wind(&mut self, wind: Vec3) {
+        self.wind = wind;
+    }
+
+    /// Move a particle, like grabbing it with the mouse;
+    pub fn set_position(&mut self, index: usize, position: Vec3) {
+        self.positions[index] = position;
+        self.previous[index] = position;
+        self.velocities[index] = Vec3::ZERO;
+    }
+
+    pub fn pins(&self) -> &[Pin] {
+        &self.pins
+    }
+
+    /// Hold a particle where it is;
+    pub fn pin(&mut self, index: usize) {
+        self.pin_at(index, self.positions[index]);
+    }
+
+    /// Hold a particle at a world point, it is moved there on the next step;
+    pub fn pin_at(&mut self, index: usize, point: Vec3) {
+        self.unpin(index);
+        self.pins.push(Pin { index, body: None, point });
+    }
+
+    /// Hold a particle at a point in body space, following the body as it moves;
+    /// The pin lets go once the body is removed;
+    pub fn attach(&mut self, index: usize, body: BodyHandle, local: Vec3) {
+        self.unpin(index);
+        self.pins.push(Pin { index, body: Some(body), point: local });
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        self.pins.retain(|pin| pin.index != index);
+    }
+
+    /// Bounds of every particle;
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.positions.iter().copied()).expanded(self.thickness)
+    }
+
+    /// Farthest a particle may move in `dt`, used to gather colliders ahead of stepping;
+    pub(super) fn reach(&self, gravity: Vec3, dt: f32) -> f32 {
+        let speed = self.velocities.iter().map(|v| v.length()).fold(0.0, f32::max);
+        (speed + gravity.length() * dt) * dt + self.thickness
+    }
+
+    /// Advance by `dt`, `targets` holds the world point of each pin, None for the ones letting go;
+    pub(super) fn step(&mut self, gravity: Vec3, dt: f32, targets: &[Option<Vec3>], boundaries: &[Boundary]) {
+        let inv_mass = 1.0 / self.particle_mass;
+        let mut inv_masses = vec![inv_mass; self.positions.len()];
+
+        // Pins glide to their targets over the substeps so moving bodies drag the cloth smoothly;
+        let pinned: Vec<(usize, Vec3, Vec3)> = self.pins.iter()
+            .zip(targets)
+            .filter_map(|(pin, target)| target.map(|target| (pin.index, self.positions[pin.index], target)))
+            .collect();
+        for &(index, _, _) in &pinned {
+            inv_masses[index] = 0.0;
+        }
+
+        let h = dt / self.substeps as f32;
+        for substep in 1..=self.substeps {
+            let accelerations = self.air_accelerations();
+            for i in 0..self.positions.len() {
+                self.previous[i] = self.positions[i];
+                if inv_masses[i] > 0.0 {
+                    self.velocities[i] += (gravity + accelerations[i]) * h;
+                    self.positions[i] += self.velocities[i] * h;
+                }
+            }
+
+            let t = substep as f32 / self.substeps as f32;
+            for &(index, start, target) in &pinned {
+                self.positions[index] = start.lerp(target, t);
+            }
+
+            self.solve_links(&inv_masses, h);
+            self.collide(&inv_masses, boundaries, dt * (1.0 - t), h);
+
+            let keep = (1.0 - self.damping * h).max(0.0);
+            for i in 0..self.positions.len() {
+                self.velocities[i] = (self.positions[i] - self.previous[i]) / h * keep;
+            }
+        }
+    }
+
+    /// Drag of each triangle against the wind, split over its corners;
+    fn air_accelerations(&self) -> Vec<Vec3> {
+        let mut accelerations = vec![Vec3::ZERO; self.positions.len()];
+        if self.drag == 0.0 {
+            return accelerations;
+        }
+
+        for &[a, b, c] in &self.triangles {
+            let [a, b, c] = [a, b, c].map(|i| i as usize);
+            let cross = (self.positions[b] - self.positions[a]).cross(self.positions[c] - self.positions[a]);
+            let area = cross.length() * 0.5;
+            let Some(normal) = cross.try_normalize() else { continue };
+
+            let relative = self.wind - (self.velocities[a] + self.velocities[b] + self.velocities[c]) / 3.0;
+            let speed = relative.dot(normal);
+            let force = normal * (self.drag * area * speed * speed.abs());
+
+            let acceleration = force / (3.0 * self.particle_mass);
+            for i in [a, b, c] {
+                accelerations[i] += acceleration;
+            }
+        }
+
+        accelerations
+    }
+
+    /// One XPBD pass, the multipliers start at zero as each substep solves once;
+    fn solve_links(&mut self, inv_masses: &[f32], h: f32) {
+        for link in &self.links {
+            let compliance = match link.kind {
+                LinkKind::Stretch => self.stretch_compliance,
+                LinkKind::Shear => self.shear_compliance,
+                LinkKind::Bend => self.bend_compliance,
+            };
+
+            let (wa, wb) = (inv_masses[link.a], inv_masses[link.b]);
+            let offset = self.positions[link.a] - self.positions[link.b];
+            let length = offset.length();
+            if wa + wb == 0.0 || length <= f32::EPSILON {
+                continue;
+            }
+
+            let alpha = compliance / (h * h);
+            let lambda = -(length - link.rest) / (wa + wb + alpha);
+            let correction = offset / length * lambda;
+            self.positions[link.a] += correction * wa;
+            self.positions[link.b] -= correction * wb;
+        }
+    }
+
+    /// Push particles out of colliders posed `lag` seconds before the step's end && cancel part of their sliding;
+    fn collide(&mut self, inv_masses: &[f32], boundaries: &[Boundary], lag: f32, h: f32) {
+        let poses: Vec<_> = boundaries.iter().map(|boundary| boundary.support.pose(lag)).collect();
+
+        let particles = self.positions.iter_mut().zip(&self.previous).zip(inv_masses);
+        for ((position, previous), &inv_mass) in particles {
+            if inv_mass == 0.0 {
+                continue;
+            }
+
+            for (boundary, iso) in boundaries.iter().zip(&poses) {
+                let (distance, normal) = boundary.shape.distance(iso, *position);
+                if distance >= self.thickness {
+                    continue;
+                }
+
+                *position += normal * (self.thickness - distance);
+
+                // Sliding relative to the collider's surface, cloth rides along moving bodies;
+                let moved = *position - *previous - boundary.support.velocity_at(*position) * h;
+                let sliding = moved - normal * moved.dot(normal);
+                *position -= sliding * self.friction;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::shape::{Isometry, Shape};
+    use super::super::{RigidBody, World};
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+    const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+
+    /// Vertical sheet in the XY plane hanging from its top corners;
+    fn curtain() -> Cloth {
+        let mut cloth = Cloth::new(Vec3::new(0.0, 1.0, 0.0), Vec3::X, Vec3::NEG_Y, 11, 11);
+        cloth.pin(cloth.index(0, 0));
+        cloth.pin(cloth.index(10, 0));
+        cloth
+    }
+
+    fn run(cloth: &mut Cloth, steps: usize, boundaries: &[Boundary]) {
+        for _ in 0..steps {
+            let targets: Vec<Option<Vec3>> = cloth.pins().iter().map(|pin| Some(pin.point)).collect();
+            cloth.step(GRAVITY, DT, &targets, boundaries);
+        }
+    }
+
+    #[test]
+    fn hanging_cloth_keeps_pins_and_length() {
+        let mut cloth = curtain();
+        run(&mut cloth, 120, &[]);
+
+        assert_eq!(cloth.positions()[cloth.index(0, 0)], Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(cloth.positions()[cloth.index(10, 0)], Vec3::new(1.0, 1.0, 0.0));
+
+        // Side edges carry the whole weight, they stretch the most;
+        let side: f32 = (0..10)
+            .map(|row| cloth.positions()[cloth.index(0, row)].distance(cloth.positions()[cloth.index(0, row + 1)]))
+            .sum();
+        assert!(side < 1.05, "side stretched to {side}");
+        assert!(cloth.velocities().iter().all(|v| v.length() < 0.2));
+    }
+
+    #[test]
+    fn cloth_drapes_over_sphere() {
+        let sphere = Shape::sphere(0.5);
+        let iso = Isometry::from_position(Vec3::ZERO);
+        let boundaries = [Boundary::fixed(&sphere, iso)];
+
+        let mut cloth = Cloth::new(Vec3::new(-1.0, 0.7, -1.0), Vec3::X * 2.0, Vec3::Z * 2.0, 15, 15);
+        run(&mut cloth, 120, &boundaries);
+
+        for position in cloth.positions() {
+            assert!(position.length() > 0.5, "inside the sphere at {position}");
+        }
+
+        // Center rests on top, the corners hang below the equator;
+        assert!(cloth.positions()[cloth.index(7, 7)].y > 0.5);
+        assert!(cloth.positions()[0].y < 0.0);
+    }
+
+    #[test]
+    fn wind_lifts_a_hanging_sheet() {
+        let mut calm = curtain();
+        let mut windy = curtain().with_wind(Vec3::Z * 8.0);
+        run(&mut calm, 120, &[]);
+        run(&mut windy, 120, &[]);
+
+        let bottom = |cloth: &Cloth| cloth.positions()[cloth.index(5, 10)];
+        assert!(bottom(&calm).z.abs() < 1e-3);
+        assert!(bottom(&windy).z > 0.2, "{}", bottom(&windy));
+    }
+
+    #[test]
+    fn cloth_follows_attached_body() {
+        let mut world = World::new();
+        let hook = world.add_body(RigidBody::kinematic()
+            .with_position(Vec3::Y * 2.0)
+            .with_linear_velocity(Vec3::X));
+
+        let mut cloth = Cloth::new(Vec3::Y * 2.0, Vec3::X, Vec3::NEG_Y, 5, 5);
+        let corner = cloth.index(0, 0);
+        cloth.attach(corner, hook, Vec3::ZERO);
+        let handle = world.add_cloth(cloth);
+
+        for _ in 0..60 {
+            world.step(DT);
+        }
+
+        let hook_position = world.body(hook).unwrap().position();
+        let cloth = world.cloth(handle).unwrap();
+        assert!(cloth.positions()[corner].distance(hook_position) < 1e-4);
+        assert!(cloth.positions().iter().all(|p| p.y < 2.0 + 1e-4));
+
+        // Without its body the pin lets go;
+        world.remove_body(hook);
+        world.step(DT);
+        let cloth = world.cloth(handle).unwrap();
+        assert!(cloth.positions()[corner].y < 2.0);
+
+        // A cloth added in the freed slot isn't reachable through the old handle;
+        assert!(world.remove_cloth(handle).is_some());
+        let next = world.add_cloth(Cloth::new(Vec3::ZERO, Vec3::X, Vec3::Z, 2, 2));
+        assert_eq!(next.index(), handle.index());
+        assert!(world.cloth(handle).is_none() && world.cloth(next).is_some());
+    }
+}
@@ -0,0 +1,182 @@
+use super::material::MaterialHandle;
+use super::shape::Shape;
+
+/// 32 bit collision layers;
+/// Two colliders interact when each one's memberships intersect the other's filter;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CollisionGroups {
+    pub memberships: u32,
+    pub filter: u32,
+}
+
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl CollisionGroups {
+    /// Member of every layer, interacts with every layer;
+    pub const ALL: Self = Self { memberships: u32::MAX, filter: u32::MAX };
+    pub const NONE: Self = Self { memberships: 0, filter: 0 };
+
+    pub fn new(memberships: u32, filter: u32) -> Self {
+        Self { memberships, filter }
+    }
+
+    pub fn interacts_with(&self, other: &CollisionGroups) -> bool {
+        self.memberships & other.filter != 0 && other.memberships & self.filter != 0
+    }
+}
+
+/// Collision geometry attached to a body;
+/// Friction && restitution of both colliders are combined per contact,
+/// a material from the world's table replaces the collider's own coefficients;
+/// Sensors only detect overlaps, they never push bodies;
+#[derive(Debug, Clone)]
+pub struct Collider {
+    pub(super) shape: Shape,
+    pub(super) friction: f32,
+    pub(super) restitution: f32,
+    pub(super) material: Option<MaterialHandle>,
+    pub(super) groups: CollisionGroups,
+    pub(super) sensor: bool,
+}
+
+impl Collider {
+    pub fn new(shape: Shape) -> Self {
+        Self { shape, friction: 0.5, restitution: 0.0, material: None, groups: CollisionGroups::ALL, sensor: false }
+    }
+
+    /// Coulomb friction coefficient;
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.max(0.0);
+        self
+    }
+
+    /// Bounciness, zero for no bounce && one for a perfectly elastic one;
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_material(mut self, material: MaterialHandle) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    pub fn with_groups(mut self, groups: CollisionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Trigger volume reporting bodies entering && leaving it;
+    pub fn with_sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn friction(&self) -> f32 {
+        self.friction
+    }
+
+    pub fn restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    pub fn material(&self) -> Option<MaterialHandle> {
+        self.material
+    }
+
+    pub fn set_material(&mut self, material: Option<MaterialHandle>) {
+        self.material = material;
+    }
+
+    pub fn is_sensor(&self) -> bool {
+        self.sensor
+    }
+
+    pub fn groups(&self) -> CollisionGroups {
+        self.groups
+    }
+
+    pub fn set_groups(&mut self, groups: CollisionGroups) {
+        self.groups = groups;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::super::{BodyHandle, QueryFilter, RigidBody, World, WorldBuilder};
+    use super::*;
+
+    const DEBRIS: u32 = 1 << 1;
+    const DT: f32 = 1.0 / 120.0;
+
+    fn ball(world: &mut World, position: Vec3, groups: CollisionGroups) -> BodyHandle {
+        world.add_body(
+            RigidBody::new()
+                .with_position(position)
+                .with_collider(Collider::new(Shape::sphere(0.5)).with_groups(groups)),
+        )
+    }
+
+    #[test]
+    fn groups_interact_both_ways_only() {
+        let debris = CollisionGroups::new(DEBRIS, !DEBRIS);
+        assert!(!debris.interacts_with(&debris));
+        assert!(debris.interacts_with(&CollisionGroups::ALL));
+        assert!(!CollisionGroups::new(1, 1).interacts_with(&CollisionGroups::new(1, 2)));
+        assert!(!CollisionGroups::NONE.interacts_with(&CollisionGroups::ALL));
+    }
+
+    #[test]
+    fn filtered_pairs_make_no_contacts() {
+        let debris = CollisionGroups::new(DEBRIS, !DEBRIS);
+        let vetoed = |a: BodyHandle| a.index() == 3;
+        let mut world = WorldBuilder::new()
+            .with_pair_filter(move |a, _, b, _| !vetoed(a) && !vetoed(b))
+            .build();
+
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let low = ball(&mut world, Vec3::Y * 0.5, debris);
+        let high = ball(&mut world, Vec3::Y * 2.0, debris);
+        let ghost = ball(&mut world, Vec3::new(5.0, 0.5, 0.0), CollisionGroups::ALL);
+        assert_eq!(ghost.index(), 3);
+
+        let mut debris_touched = false;
+        for _ in 0..120 {
+            world.step(DT);
+            debris_touched |= world.contacts().any(|c| (c.body_a, c.body_b) == (low, high));
+        }
+
+        // Debris falls through debris onto the ground, the vetoed ball through everything;
+        assert!(!debris_touched);
+        assert!((world.body(low).unwrap().position().y - 0.5).abs() < 0.05);
+        assert!((world.body(high).unwrap().position().y - 0.5).abs() < 0.05);
+        assert!(world.body(ghost).unwrap().position().y < -1.0);
+        assert_eq!(world.contacts().count(), 2);
+    }
+
+    #[test]
+    fn queries_reuse_the_masks() {
+        let mut world = World::new();
+        let ground = world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let rubble = world.add_body(
+            RigidBody::fixed()
+                .with_position(Vec3::Y)
+                .with_collider(Collider::new(Shape::sphere(0.5)).with_groups(CollisionGroups::new(DEBRIS, u32::MAX))),
+        );
+        world.step(DT);
+
+        let down = |groups| world.cast_ray(Vec3::Y * 5.0, Vec3::NEG_Y * 10.0, &QueryFilter::new().with_groups(groups)).map(|hit| hit.body);
+        assert_eq!(down(CollisionGroups::ALL), Some(rubble));
+        assert_eq!(down(CollisionGroups::new(u32::MAX, !DEBRIS)), Some(ground));
+    }
+}
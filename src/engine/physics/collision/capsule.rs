@@ -0,0 +1,143 @@
+use glam::Vec3;
+
+use super::Manifold;
+use super::super::shape::{Isometry, Shape};
+
+const SEARCH_ITERATIONS: usize = 32;
+
+/// Capsule against a convex shape;
+/// Signed distance of a convex shape is convex along the capsule segment,
+/// so the deepest segment point is found with a golden section search;
+pub(super) fn collide(half_height: f32, radius: f32, iso: &Isometry, other: &Shape, other_iso: &Isometry) -> Option<Manifold> {
+    let start = iso.transform_point(Vec3::NEG_Y * half_height);
+    let end = iso.transform_point(Vec3::Y * half_height);
+    let along = |t: f32| start.lerp(end, t);
+
+    let distance = |t: f32| other.distance(other_iso, along(t)).0;
+
+    let ratio = (5.0f32.sqrt() - 1.0) * 0.5;
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    for _ in 0..SEARCH_ITERATIONS {
+        let t1 = hi - (hi - lo) * ratio;
+        let t2 = lo + (hi - lo) * ratio;
+        match distance(t1) < distance(t2) {
+            true => hi = t2,
+            false => lo = t1
+        }
+    }
+
+    let deepest = along((lo + hi) * 0.5);
+    let (depth_distance, outward) = other.distance(other_iso, deepest);
+    if depth_distance > radius {
+        return None;
+    }
+
+    let mut manifold = Manifold::new(-outward);
+    manifold.push(deepest - outward * (radius + depth_distance) * 0.5, radius - depth_distance);
+
+    // Segment ends give a second point when the capsule lies flat;
+    for end_point in [start, end] {
+        if end_point.distance_squared(deepest) < 1e-4 {
+            continue;
+        }
+
+        let (end_distance, end_outward) = other.distance(other_iso, end_point);
+        if end_distance <= radius && end_outward.dot(outward) > 0.99 {
+            manifold.push(end_point - outward * (radius + end_distance) * 0.5, radius - end_distance);
+        }
+    }
+
+    if manifold.points.len() > 2 {
+        // Both ends touch, the middle point is redundant;
+        manifold.points.remove(0);
+    }
+
+    Some(manifold)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use glam::Quat;
+
+    use super::super::collide as narrow;
+    use super::super::tests::{assert_near, assert_vec};
+    use super::*;
+
+    fn at(x: f32, y: f32, z: f32) -> Isometry {
+        Isometry::from_position(Vec3::new(x, y, z))
+    }
+
+    fn lying(x: f32, y: f32, z: f32) -> Isometry {
+        Isometry::new(Vec3::new(x, y, z), Quat::from_rotation_z(FRAC_PI_2))
+    }
+
+    #[test]
+    fn capsule_capsule() {
+        let capsule = Shape::capsule(1.0, 0.5);
+
+        // Crossed capsules, one above the other;
+        let crossed = Isometry::new(Vec3::new(0.0, 0.9, 0.0), Quat::from_rotation_x(FRAC_PI_2));
+        let m = narrow(&capsule, &lying(0.0, 0.0, 0.0), &capsule, &crossed).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 0.1);
+        assert_vec(m.points[0].point, Vec3::new(0.0, 0.45, 0.0));
+
+        // Parallel capsules side by side touch along a line;
+        let m = narrow(&capsule, &at(0.0, 0.0, 0.0), &capsule, &at(0.8, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::X);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.2);
+        }
+
+        assert!(narrow(&capsule, &at(0.0, 0.0, 0.0), &capsule, &at(1.1, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn capsule_box() {
+        let capsule = Shape::capsule(1.0, 0.5);
+        let cube = Shape::cuboid(Vec3::ONE);
+
+        // Lying flat on the top face;
+        let m = narrow(&capsule, &lying(0.0, 1.4, 0.0), &cube, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+            assert_near(p.point.y, 0.95);
+        }
+
+        // Standing on the top face;
+        let m = narrow(&cube, &at(0.0, 0.0, 0.0), &capsule, &at(0.3, 2.3, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 0.2);
+        assert_vec(m.points[0].point, Vec3::new(0.3, 0.9, 0.0));
+
+        assert!(narrow(&capsule, &lying(0.0, 1.6, 0.0), &cube, &at(0.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn capsule_cylinder() {
+        let capsule = Shape::capsule(1.0, 0.5);
+        let cylinder = Shape::cylinder(1.0, 1.0);
+
+        // Lying across the top cap;
+        let m = narrow(&capsule, &lying(0.0, 1.3, 0.0), &cylinder, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_near(m.max_depth(), 0.2);
+
+        // Upright next to the side;
+        let m = narrow(&cylinder, &at(0.0, 0.0, 0.0), &capsule, &at(0.0, 0.0, 1.4)).unwrap();
+        assert_vec(m.normal, Vec3::Z);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+        }
+
+        assert!(narrow(&capsule, &at(0.0, 0.0, 1.6), &cylinder, &at(0.0, 0.0, 0.0)).is_none());
+    }
+}
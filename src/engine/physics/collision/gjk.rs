@@ -0,0 +1,449 @@
+use glam::Vec3;
+
+use super::any_perpendicular;
+use super::super::shape::{ConvexHull, Isometry, Shape};
+
+const MAX_ITERATIONS: usize = 64;
+const GJK_TOLERANCE: f32 = 1e-5;
+const EPA_TOLERANCE: f32 = 1e-4;
+
+/// Convex geometry described by its support function;
+pub trait SupportMap {
+    /// Farthest point in the direction, not necessary normalized;
+    fn support(&self, dir: Vec3) -> Vec3;
+}
+
+impl SupportMap for Shape {
+    fn support(&self, dir: Vec3) -> Vec3 {
+        Shape::support(self, dir)
+    }
+}
+
+impl SupportMap for ConvexHull {
+    fn support(&self, dir: Vec3) -> Vec3 {
+        ConvexHull::support(self, dir)
+    }
+}
+
+/// Single point, used for point queries;
+impl SupportMap for Vec3 {
+    fn support(&self, _dir: Vec3) -> Vec3 {
+        *self
+    }
+}
+
+/// Local support map moved into world space;
+pub struct Posed<'a, S: SupportMap + ?Sized> {
+    pub shape: &'a S,
+    pub iso: &'a Isometry,
+}
+
+impl<'a, S: SupportMap + ?Sized> Posed<'a, S> {
+    pub fn new(shape: &'a S, iso: &'a Isometry) -> Self {
+        Self { shape, iso }
+    }
+}
+
+impl<S: SupportMap + ?Sized> SupportMap for Posed<'_, S> {
+    fn support(&self, dir: Vec3) -> Vec3 {
+        let local = self.iso.inverse_transform_vector(dir);
+        self.iso.transform_point(self.shape.support(local))
+    }
+}
+
+/// Penetration of two overlapping shapes, normal points from the first to the second;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penetration {
+    pub normal: Vec3,
+    pub depth: f32,
+    pub point_a: Vec3,
+    pub point_b: Vec3,
+}
+
+/// Vertex of the Minkowski difference with its source points;
+#[derive(Debug, Clone, Copy)]
+struct Vertex {
+    w: Vec3,
+    a: Vec3,
+    b: Vec3,
+}
+
+fn minkowski<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B, dir: Vec3) -> Vertex {
+    let pa = a.support(dir);
+    let pb = b.support(-dir);
+    Vertex { w: pa - pb, a: pa, b: pb }
+}
+
+enum Gjk {
+    Separated { distance: f32, point_a: Vec3, point_b: Vec3 },
+    Intersecting(Vec<Vertex>),
+}
+
+/// Distance && closest points of two separated shapes, None when they overlap;
+pub fn distance<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B) -> Option<(f32, Vec3, Vec3)> {
+    match gjk(a, b) {
+        Gjk::Separated { distance, point_a, point_b } => Some((distance, point_a, point_b)),
+        Gjk::Intersecting(_) => None
+    }
+}
+
+pub fn intersects<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B) -> bool {
+    matches!(gjk(a, b), Gjk::Intersecting(_))
+}
+
+/// Penetration depth of two overlapping shapes, None when they are separated;
+pub fn penetration<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B) -> Option<Penetration> {
+    match gjk(a, b) {
+        Gjk::Separated { .. } => None,
+        Gjk::Intersecting(simplex) => epa(a, b, simplex)
+    }
+}
+
+fn gjk<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B) -> Gjk {
+    let first = minkowski(a, b, Vec3::X);
+    let mut simplex = vec![first];
+    let mut weights = vec![1.0];
+    let mut v = first.w;
+
+    for _ in 0..MAX_ITERATIONS {
+        let vv = v.length_squared();
+        if vv <= f32::EPSILON * f32::EPSILON {
+            return Gjk::Intersecting(simplex);
+        }
+
+        let w = minkowski(a, b, -v);
+        let duplicate = simplex.iter().any(|s| s.w.distance_squared(w.w) <= f32::EPSILON);
+        if duplicate || vv - v.dot(w.w) <= GJK_TOLERANCE * vv {
+            break;
+        }
+
+        simplex.push(w);
+        let (reduced, new_weights, closest) = closest_to_origin(&simplex);
+        simplex = reduced;
+        weights = new_weights;
+        v = closest;
+
+        if simplex.len() == 4 {
+            return Gjk::Intersecting(simplex);
+        }
+    }
+
+    let point_a = simplex.iter().zip(&weights).map(|(s, w)| s.a * *w).sum();
+    let point_b = simplex.iter().zip(&weights).map(|(s, w)| s.b * *w).sum();
+
+    Gjk::Separated { distance: v.length(), point_a, point_b }
+}
+
+/// Closest simplex point to the origin, reduced to the supporting sub-simplex with barycentric weights;
+fn closest_to_origin(simplex: &[Vertex]) -> (Vec<Vertex>, Vec<f32>, Vec3) {
+    match simplex.len() {
+        1 => (simplex.to_vec(), vec![1.0], simplex[0].w),
+        2 => closest_segment(simplex[0], simplex[1]),
+        3 => closest_triangle(simplex[0], simplex[1], simplex[2]),
+        _ => closest_tetrahedron(simplex[0], simplex[1], simplex[2], simplex[3]),
+    }
+}
+
+fn closest_segment(a: Vertex, b: Vertex) -> (Vec<Vertex>, Vec<f32>, Vec3) {
+    let ab = b.w - a.w;
+    let t = match ab.length_squared() > f32::EPSILON {
+        true => (-a.w.dot(ab) / ab.length_squared()).clamp(0.0, 1.0),
+        false => 0.0
+    };
+
+    if t <= 0.0 {
+        (vec![a], vec![1.0], a.w)
+    } else if t >= 1.0 {
+        (vec![b], vec![1.0], b.w)
+    } else {
+        (vec![a, b], vec![1.0 - t, t], a.w + ab * t)
+    }
+}
+
+fn closest_triangle(a: Vertex, b: Vertex, c: Vertex) -> (Vec<Vertex>, Vec<f32>, Vec3) {
+    let ab = b.w - a.w;
+    let ac = c.w - a.w;
+
+    let ap = -a.w;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (vec![a], vec![1.0], a.w);
+    }
+
+    let bp = -b.w;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (vec![b], vec![1.0], b.w);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let t = d1 / (d1 - d3);
+        return (vec![a, b], vec![1.0 - t, t], a.w + ab * t);
+    }
+
+    let cp = -c.w;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (vec![c], vec![1.0], c.w);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let t = d2 / (d2 - d6);
+        return (vec![a, c], vec![1.0 - t, t], a.w + ac * t);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (vec![b, c], vec![1.0 - t, t], b.w + (c.w - b.w) * t);
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    (vec![a, b, c], vec![1.0 - v - w, v, w], a.w + ab * v + ac * w)
+}
+
+fn closest_tetrahedron(a: Vertex, b: Vertex, c: Vertex, d: Vertex) -> (Vec<Vertex>, Vec<f32>, Vec3) {
+    let faces = [(a, b, c, d), (a, c, d, b), (a, d, b, c), (b, d, c, a)];
+
+    let mut best: Option<(Vec<Vertex>, Vec<f32>, Vec3)> = None;
+    for (p, q, r, opposite) in faces {
+        let normal = (q.w - p.w).cross(r.w - p.w);
+        let origin_side = (-p.w).dot(normal);
+        let opposite_side = (opposite.w - p.w).dot(normal);
+
+        // Origin && opposite vertex on different sides of the face, or a flat tetrahedron;
+        if origin_side * opposite_side < 0.0 || opposite_side.abs() <= f32::EPSILON {
+            let candidate = closest_triangle(p, q, r);
+            let better = match &best {
+                None => true,
+                Some((_, _, v)) => candidate.2.length_squared() < v.length_squared()
+            };
+
+            if better {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    best.unwrap_or_else(|| (vec![a, b, c, d], vec![0.25; 4], Vec3::ZERO))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    indices: [usize; 3],
+    normal: Vec3,
+    distance: f32,
+}
+
+fn make_face(points: &[Vertex], indices: [usize; 3], interior: Vec3) -> Option<Face> {
+    let [i, j, k] = indices;
+    let (a, b, c) = (points[i].w, points[j].w, points[k].w);
+
+    let normal = (b - a).cross(c - a).try_normalize()?;
+    let (indices, normal) = match normal.dot(a - interior) < 0.0 {
+        true => ([i, k, j], -normal),
+        false => (indices, normal)
+    };
+
+    Some(Face { indices, normal, distance: normal.dot(a) })
+}
+
+/// Expand the simplex into a tetrahedron enclosing part of the difference;
+fn blow_up<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B, simplex: &mut Vec<Vertex>) -> bool {
+    if simplex.len() == 1 {
+        for dir in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z] {
+            let p = minkowski(a, b, dir);
+            if p.w.distance_squared(simplex[0].w) > EPA_TOLERANCE * EPA_TOLERANCE {
+                simplex.push(p);
+                break;
+            }
+        }
+    }
+
+    if simplex.len() == 2 {
+        let line = (simplex[1].w - simplex[0].w).normalize();
+        let mut dir = any_perpendicular(line);
+        for _ in 0..6 {
+            let p = minkowski(a, b, dir);
+            let off_line = (p.w - simplex[0].w).cross(line).length();
+            if off_line > EPA_TOLERANCE {
+                simplex.push(p);
+                break;
+            }
+
+            dir = glam::Quat::from_axis_angle(line, std::f32::consts::FRAC_PI_3) * dir;
+        }
+    }
+
+    if simplex.len() == 3 {
+        let normal = (simplex[1].w - simplex[0].w).cross(simplex[2].w - simplex[0].w);
+        if let Some(normal) = normal.try_normalize() {
+            for dir in [normal, -normal] {
+                let p = minkowski(a, b, dir);
+                if (p.w - simplex[0].w).dot(normal).abs() > EPA_TOLERANCE {
+                    simplex.push(p);
+                    break;
+                }
+            }
+        }
+    }
+
+    simplex.len() == 4
+}
+
+fn epa<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(a: &A, b: &B, mut points: Vec<Vertex>) -> Option<Penetration> {
+    if !blow_up(a, b, &mut points) {
+        return None;
+    }
+
+    let interior = points.iter().map(|p| p.w).sum::<Vec3>() / 4.0;
+    let mut faces: Vec<Face> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]
+        .into_iter()
+        .filter_map(|indices| make_face(&points, indices, interior))
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let closest = *faces.iter().min_by(|f, g| f.distance.total_cmp(&g.distance))?;
+
+        let p = minkowski(a, b, closest.normal);
+        if p.w.dot(closest.normal) - closest.distance < EPA_TOLERANCE {
+            return Some(finish(&points, &closest));
+        }
+
+        let new_index = points.len();
+        points.push(p);
+
+        // Remove faces seen from the new point && keep their horizon;
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            let visible = face.normal.dot(p.w - points[face.indices[0]].w) > 0.0;
+            if visible {
+                for k in 0..3 {
+                    let edge = (face.indices[k], face.indices[(k + 1) % 3]);
+                    match horizon.iter().position(|e| *e == (edge.1, edge.0)) {
+                        Some(shared) => { horizon.swap_remove(shared); }
+                        None => horizon.push(edge)
+                    }
+                }
+            }
+
+            !visible
+        });
+
+        for (i, j) in horizon {
+            if let Some(face) = make_face(&points, [i, j, new_index], interior) {
+                faces.push(face);
+            }
+        }
+
+        if faces.is_empty() {
+            return Some(finish(&points, &closest));
+        }
+    }
+
+    let closest = faces.iter().min_by(|f, g| f.distance.total_cmp(&g.distance))?;
+    Some(finish(&points, closest))
+}
+
+fn finish(points: &[Vertex], face: &Face) -> Penetration {
+    let [i, j, k] = face.indices;
+    let (a, b, c) = (points[i], points[j], points[k]);
+    let projected = face.normal * face.distance;
+
+    // Barycentric coordinates of the origin projection;
+    let v0 = b.w - a.w;
+    let v1 = c.w - a.w;
+    let v2 = projected - a.w;
+    let d00 = v0.dot(v0);
+    let d01 = v0.dot(v1);
+    let d11 = v1.dot(v1);
+    let d20 = v2.dot(v0);
+    let d21 = v2.dot(v1);
+    let denom = d00 * d11 - d01 * d01;
+
+    let (u, v, w) = match denom.abs() > f32::EPSILON {
+        true => {
+            let v = (d11 * d20 - d01 * d21) / denom;
+            let w = (d00 * d21 - d01 * d20) / denom;
+            (1.0 - v - w, v, w)
+        }
+        false => (1.0, 0.0, 0.0)
+    };
+
+    Penetration {
+        normal: face.normal,
+        depth: face.distance.max(0.0),
+        point_a: a.a * u + b.a * v + c.a * w,
+        point_b: a.b * u + b.b * v + c.b * w,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::super::tests::{assert_near, assert_vec};
+    use super::*;
+
+    #[test]
+    fn separated_spheres() {
+        let sphere = Shape::sphere(1.0);
+        let iso_a = Isometry::IDENTITY;
+        let iso_b = Isometry::from_position(Vec3::new(3.0, 0.0, 0.0));
+
+        let (distance, point_a, point_b) =
+            super::distance(&Posed::new(&sphere, &iso_a), &Posed::new(&sphere, &iso_b)).unwrap();
+        assert_near(distance, 1.0);
+        assert_vec(point_a, Vec3::X);
+        assert_vec(point_b, Vec3::new(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn box_penetration() {
+        let cube = Shape::cuboid(Vec3::ONE);
+        let iso_a = Isometry::IDENTITY;
+        let iso_b = Isometry::from_position(Vec3::new(0.2, 1.7, 0.1));
+
+        let pen = penetration(&Posed::new(&cube, &iso_a), &Posed::new(&cube, &iso_b)).unwrap();
+        assert_vec(pen.normal, Vec3::Y);
+        assert_near(pen.depth, 0.3);
+    }
+
+    #[test]
+    fn hull_matches_box() {
+        let corners: Vec<Vec3> = (0..8)
+            .map(|i| Vec3::new(
+                match i & 1 { 0 => -1.0, _ => 1.0 },
+                match i & 2 { 0 => -1.0, _ => 1.0 },
+                match i & 4 { 0 => -1.0, _ => 1.0 },
+            ))
+            .collect();
+        let hull = ConvexHull::new(corners);
+
+        // Rotated corner edge reaches x = sqrt(2);
+        let iso_a = Isometry::new(Vec3::ZERO, Quat::from_rotation_y(std::f32::consts::FRAC_PI_4));
+        let iso_b = Isometry::from_position(Vec3::new(2.3, 0.0, 0.0));
+        let sphere = Shape::sphere(1.0);
+
+        assert!(intersects(&Posed::new(&hull, &iso_a), &Posed::new(&sphere, &iso_b)));
+        assert!(!intersects(&Posed::new(&hull, &Isometry::IDENTITY), &Posed::new(&sphere, &iso_b)));
+    }
+
+    #[test]
+    fn touching_is_not_separated() {
+        let cube = Shape::cuboid(Vec3::ONE);
+        let iso_b = Isometry::from_position(Vec3::new(2.0, 0.0, 0.0));
+        let pen = penetration(&Posed::new(&cube, &Isometry::IDENTITY), &Posed::new(&cube, &iso_b));
+
+        if let Some(pen) = pen {
+            assert_near(pen.depth, 0.0);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use glam::Vec3;
+
+use super::{collide as convex, polyhedral, ContactPoint, Manifold};
+use super::super::mesh::{Triangle, Triangles};
+use super::super::shape::{Isometry, Shape};
+
+/// Contact normals this close share one group of at most four points;
+const SAME_NORMAL: f32 = 0.999;
+/// Distance from an edge for a contact to lie on it, loose enough for EPA round off;
+const EDGE_TOLERANCE: f32 = 1e-2;
+
+/// Convex shape against the triangles of a mesh collider, normal points from the shape to the mesh;
+/// Every touched triangle adds its own points, grouped by normal so coplanar triangles act as one face;
+pub(super) fn collide(shape: &Shape, iso: &Isometry, mesh: &dyn Triangles, mesh_iso: &Isometry) -> Option<Manifold> {
+    let local = mesh_iso.inverse() * *iso;
+
+    let mut candidates = Vec::new();
+    mesh.query_aabb(&shape.aabb(&local), &mut |index| candidates.push(index));
+
+    let mut groups: Vec<Manifold> = Vec::new();
+    for triangle in candidates.into_iter().map(|index| mesh.triangle(index)) {
+        let Some(manifold) = collide_triangle(shape, &local, &triangle) else { continue };
+
+        match groups.iter_mut().find(|group| group.normal.dot(manifold.normal) > SAME_NORMAL) {
+            Some(group) => group.points.extend(manifold.points),
+            None => groups.push(manifold)
+        }
+    }
+
+    let mut normal = Vec3::ZERO;
+    let mut points = Vec::new();
+    for mut group in groups {
+        polyhedral::reduce(&mut group);
+        normal += group.normal * group.max_depth().max(f32::EPSILON);
+        points.extend(group.points);
+    }
+
+    let first = points.first()?.normal;
+    let mut manifold = Manifold::new(mesh_iso.transform_vector(normal.try_normalize().unwrap_or(first)));
+    manifold.points = points.into_iter()
+        .map(|p| ContactPoint {
+            point: mesh_iso.transform_point(p.point),
+            depth: p.depth,
+            normal: mesh_iso.transform_vector(p.normal),
+        })
+        .collect();
+
+    Some(manifold)
+}
+
+/// Convex shape against one triangle, in mesh space;
+/// Only contacts on active edges keep their normal, the rest are pushed out along the face normal,
+/// so bodies sliding over seams don't catch on them;
+fn collide_triangle(shape: &Shape, iso: &Isometry, triangle: &Triangle) -> Option<Manifold> {
+    // One sided: shapes centered behind the triangle pass through it;
+    if triangle.is_degenerate() || triangle.normal.dot(iso.position - triangle.points[0]) < 0.0 {
+        return None;
+    }
+
+    let hull = triangle.hull();
+    if let Some(manifold) = convex(shape, iso, &hull, &Isometry::IDENTITY) {
+        // Points lie midway, the triangle surface is half the depth back along the normal;
+        let deepest = manifold.points.iter().max_by(|a, b| a.depth.total_cmp(&b.depth))?;
+        let surface = deepest.point - manifold.normal * deepest.depth * 0.5;
+        if triangle.on_active_edge(triangle.closest_point(surface), EDGE_TOLERANCE) {
+            return Some(manifold);
+        }
+    }
+
+    // Face or seam contact, also catches resting shapes GJK sees as just apart;
+    // Depth is along the face normal, points beyond the triangle belong to its neighbors;
+    let normal = triangle.normal;
+    let lowest = iso.transform_point(shape.support(iso.inverse_transform_vector(-normal)));
+    let depth = normal.dot(triangle.points[0] - lowest);
+    if depth < 0.0 {
+        return None;
+    }
+
+    // Clipping falls back to a support point when the patches miss, so points must touch both;
+    let mut manifold = polyhedral::clip(shape, iso, &hull, &Isometry::IDENTITY, -normal, depth);
+    manifold.points.retain(|p| {
+        let surface = p.point + normal * p.depth * 0.5;
+        triangle.contains_projection(p.point, EDGE_TOLERANCE) && shape.distance(iso, surface).0 <= EDGE_TOLERANCE
+    });
+
+    (!manifold.is_empty()).then_some(manifold)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::super::collide as narrow;
+    use super::super::tests::{assert_near, assert_vec};
+    use super::super::super::mesh::{HeightField, TriMesh};
+    use super::*;
+
+    fn at(x: f32, y: f32, z: f32) -> Isometry {
+        Isometry::from_position(Vec3::new(x, y, z))
+    }
+
+    /// Flat 4x4 grid of unit cells on y = 0, spanning [0; 4] in x && z;
+    fn floor() -> Shape {
+        let at = |column: usize, row: usize| Vec3::new(column as f32, 0.0, row as f32);
+        Shape::TriMesh(TriMesh::from_triangles((0..16).flat_map(|cell| {
+            let (c, r) = (cell % 4, cell / 4);
+            [[at(c, r), at(c, r + 1), at(c + 1, r + 1)], [at(c, r), at(c + 1, r + 1), at(c + 1, r)]]
+        })))
+    }
+
+    #[test]
+    fn sphere_on_seams_sees_the_face() {
+        // On a cell edge, a diagonal && a shared corner;
+        for (x, z) in [(1.0, 1.5), (1.5, 1.5), (2.0, 2.0), (1.02, 1.3)] {
+            let m = narrow(&Shape::sphere(0.5), &at(x, 0.4, z), &floor(), &Isometry::IDENTITY).unwrap();
+            assert_vec(m.normal, Vec3::NEG_Y);
+            for p in &m.points {
+                assert_vec(p.normal, Vec3::NEG_Y);
+                assert_near(p.depth, 0.1);
+            }
+        }
+
+        // Back side;
+        assert!(narrow(&Shape::sphere(0.5), &at(1.5, -0.4, 1.5), &floor(), &Isometry::IDENTITY).is_none());
+    }
+
+    #[test]
+    fn box_across_seams() {
+        let m = narrow(&floor(), &Isometry::IDENTITY, &Shape::cuboid(Vec3::new(0.8, 0.5, 0.6)), &at(2.0, 0.45, 2.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert!(!m.points.is_empty() && m.points.len() <= 4);
+        for p in &m.points {
+            assert_vec(p.normal, Vec3::Y);
+            assert_near(p.depth, 0.05);
+        }
+
+        // Tilted onto an edge that runs along a seam;
+        let tilted = Isometry::new(Vec3::new(2.0, 0.6, 2.0), Quat::from_rotation_z(0.3));
+        let m = narrow(&Shape::cuboid(Vec3::splat(0.5)), &tilted, &floor(), &Isometry::IDENTITY).unwrap();
+        for p in &m.points {
+            assert_vec(p.normal, Vec3::NEG_Y);
+        }
+    }
+
+    #[test]
+    fn ridge_edge_is_real() {
+        // Roof with its ridge along z at x = 1;
+        let field = Shape::HeightField(HeightField::new(vec![0.0, 1.0, 0.0, 0.0, 1.0, 0.0], 3, 2, Vec3::new(1.0, 1.0, 4.0)));
+        let m = narrow(&Shape::sphere(0.5), &at(0.0, 1.4, 0.0), &field, &Isometry::IDENTITY).unwrap();
+
+        // The sphere sits right above the ridge, so it is pushed straight up, up to EPA precision;
+        assert!(m.normal.y < -0.999, "{m:?}");
+        assert_near(m.max_depth(), 0.1);
+
+        // Slightly off the ridge the edge normal tilts towards the sphere;
+        let m = narrow(&Shape::sphere(0.5), &at(0.1, 1.4, 0.0), &field, &Isometry::IDENTITY).unwrap();
+        assert!(m.normal.x < -0.1 && m.normal.y < 0.0, "{m:?}");
+    }
+
+    #[test]
+    fn valley_pushes_from_both_walls() {
+        let field = Shape::HeightField(HeightField::new(vec![1.0, 0.0, 1.0, 1.0, 0.0, 1.0], 3, 2, Vec3::new(1.0, 1.0, 4.0)));
+        let m = narrow(&Shape::sphere(0.5), &at(0.0, 0.6, 0.0), &field, &Isometry::IDENTITY).unwrap();
+
+        let normals: Vec<Vec3> = m.points.iter().map(|p| p.normal).collect();
+        assert!(normals.iter().any(|n| n.x > 0.5) && normals.iter().any(|n| n.x < -0.5), "{normals:?}");
+        assert_vec(m.normal, Vec3::NEG_Y);
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+
+use glam::Vec3;
+
+use super::shape::{Isometry, Shape};
+
+pub use gjk::{distance, intersects, penetration, Penetration, Posed, SupportMap};
+pub use ray::cast_ray;
+pub use toi::{time_of_impact, Motion};
+
+mod sphere;
+mod capsule;
+mod plane;
+mod polyhedral;
+mod mesh;
+mod gjk;
+mod ray;
+mod toi;
+
+/// Single contact point, `point` lies midway between both surfaces;
+/// The normal only differs from the manifold normal against mesh colliders;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint {
+    pub point: Vec3,
+    pub depth: f32,
+    pub normal: Vec3,
+}
+
+/// Contact manifold between two shapes;
+/// Normal points from the first shape to the second, averaged over the points against meshes;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifold {
+    pub normal: Vec3,
+    pub points: Vec<ContactPoint>,
+}
+
+impl Manifold {
+    pub fn new(normal: Vec3) -> Self {
+        Self { normal, points: Vec::new() }
+    }
+
+    pub fn single(normal: Vec3, point: Vec3, depth: f32) -> Self {
+        Self { normal, points: vec![ContactPoint { point, depth, normal }] }
+    }
+
+    pub fn push(&mut self, point: Vec3, depth: f32) {
+        self.points.push(ContactPoint { point, depth, normal: self.normal });
+    }
+
+    /// Same contact seen from the other shape;
+    pub fn flipped(mut self) -> Self {
+        self.normal = -self.normal;
+        for point in &mut self.points {
+            point.normal = -point.normal;
+        }
+        self
+    }
+
+    pub fn max_depth(&self) -> f32 {
+        self.points.iter()
+            .map(|p| p.depth)
+            .fold(f32::MIN, f32::max)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+/// Narrow phase entry: contact manifold between two posed shapes;
+pub fn collide(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry) -> Option<Manifold> {
+    use Shape::*;
+
+    let manifold = match (a, b) {
+        (Plane { .. }, Plane { .. }) => None,
+
+        // Static geometry never collides with itself;
+        (TriMesh(_) | HeightField(_), TriMesh(_) | HeightField(_) | Plane { .. }) => None,
+        (Plane { .. }, TriMesh(_) | HeightField(_)) => None,
+        (_, TriMesh(_) | HeightField(_)) => mesh::collide(a, iso_a, b.triangles()?, iso_b),
+        (TriMesh(_) | HeightField(_), _) => mesh::collide(b, iso_b, a.triangles()?, iso_a).map(Manifold::flipped),
+
+        (ConvexHull(_), Plane { normal, offset }) => plane::collide(a, iso_a, *normal, *offset, iso_b),
+        (Plane { normal, offset }, ConvexHull(_)) => plane::collide(b, iso_b, *normal, *offset, iso_a).map(Manifold::flipped),
+        (ConvexHull(_), _) | (_, ConvexHull(_)) => convex(a, iso_a, b, iso_b),
+
+        (Sphere { radius }, _) => sphere::collide(*radius, iso_a.position, b, iso_b),
+        (_, Sphere { radius }) => sphere::collide(*radius, iso_b.position, a, iso_a).map(Manifold::flipped),
+
+        (_, Plane { normal, offset }) => plane::collide(a, iso_a, *normal, *offset, iso_b),
+        (Plane { normal, offset }, _) => plane::collide(b, iso_b, *normal, *offset, iso_a).map(Manifold::flipped),
+
+        (Capsule { half_height, radius }, _) => capsule::collide(*half_height, *radius, iso_a, b, iso_b),
+        (_, Capsule { half_height, radius }) => capsule::collide(*half_height, *radius, iso_b, a, iso_a).map(Manifold::flipped),
+
+        _ => polyhedral::collide(a, iso_a, b, iso_b)
+    };
+
+    manifold.filter(|m| !m.is_empty())
+}
+
+/// Any pair of support mapped shapes: GJK && EPA for the normal, then feature clipping;
+pub(super) fn convex(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry) -> Option<Manifold> {
+    let pen = penetration(&Posed::new(a, iso_a), &Posed::new(b, iso_b))?;
+    Some(polyhedral::clip(a, iso_a, b, iso_b, pen.normal, pen.depth))
+}
+
+/// Closest points between segments [p1; q1] && [p2; q2], returns both parameters;
+pub(super) fn closest_segment_segment(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> (f32, f32) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+
+    if a <= f32::EPSILON && e <= f32::EPSILON {
+        return (0.0, 0.0);
+    }
+
+    if a <= f32::EPSILON {
+        return (0.0, (f / e).clamp(0.0, 1.0));
+    }
+
+    let c = d1.dot(r);
+    if e <= f32::EPSILON {
+        return ((-c / a).clamp(0.0, 1.0), 0.0);
+    }
+
+    let b = d1.dot(d2);
+    let denom = a * e - b * b;
+
+    let mut s = match denom > f32::EPSILON {
+        true => ((b * f - c * e) / denom).clamp(0.0, 1.0),
+        false => 0.0
+    };
+
+    let mut t = (b * s + f) / e;
+    if t < 0.0 {
+        t = 0.0;
+        s = (-c / a).clamp(0.0, 1.0);
+    } else if t > 1.0 {
+        t = 1.0;
+        s = ((b - c) / a).clamp(0.0, 1.0);
+    }
+
+    (s, t)
+}
+
+/// Outward triangles of the convex hull around the points, built incrementally;
+/// Each point outside the hull so far replaces the faces it sees with a fan from their horizon,
+/// so the cost grows with points times faces rather than with every triple of points;
+/// Flat or degenerate clouds have no volume && give no triangles;
+pub(super) fn hull_triangles(points: &[Vec3]) -> Vec<[Vec3; 3]> {
+    let size = points.iter().map(|p| p.length()).fold(0.0, f32::max).max(f32::EPSILON);
+    let tolerance = 1e-4 * size;
+
+    let farthest = |distance: &dyn Fn(Vec3) -> f32| {
+        (0..points.len()).max_by(|a, b| distance(points[*a]).total_cmp(&distance(points[*b])))
+    };
+
+    // Starting tetrahedron: the point farthest along X, the farthest from it, from their line && from their plane;
+    let Some(a) = farthest(&|p| p.x) else { return Vec::new() };
+    let b = farthest(&|p| p.distance_squared(points[a])).unwrap();
+    let Some(line) = (points[b] - points[a]).try_normalize() else { return Vec::new() };
+    let c = farthest(&|p| (p - points[a]).cross(line).length_squared()).unwrap();
+    let Some(normal) = line.cross(points[c] - points[a]).try_normalize() else { return Vec::new() };
+    let d = farthest(&|p| normal.dot(p - points[a]).abs()).unwrap();
+    if normal.dot(points[d] - points[a]).abs() <= tolerance || (points[c] - points[a]).cross(line).length() <= tolerance {
+        return Vec::new();
+    }
+
+    let plane = |[i, j, k]: [usize; 3]| {
+        let normal = (points[j] - points[i]).cross(points[k] - points[i]).normalize_or_zero();
+        (normal, normal.dot(points[i]))
+    };
+    let outward = |face: [usize; 3], inside: Vec3| {
+        let (normal, offset) = plane(face);
+        match normal.dot(inside) > offset {
+            true => [face[0], face[2], face[1]],
+            false => face
+        }
+    };
+
+    let inside = (points[a] + points[b] + points[c] + points[d]) * 0.25;
+    let mut faces: Vec<[usize; 3]> = [[a, b, c], [a, b, d], [a, c, d], [b, c, d]]
+        .map(|face| outward(face, inside))
+        .to_vec();
+
+    for (index, point) in points.iter().enumerate() {
+        let (visible, hidden): (Vec<[usize; 3]>, Vec<[usize; 3]>) = faces.iter()
+            .partition(|face| {
+                let (normal, offset) = plane(**face);
+                normal.dot(*point) - offset > tolerance
+            });
+
+        if visible.is_empty() {
+            continue;
+        }
+
+        // Edges of seen faces whose twin belongs to an unseen one, kept in the seen face's winding;
+        let edges = |face: &[usize; 3]| [(face[0], face[1]), (face[1], face[2]), (face[2], face[0])];
+        let seen: HashSet<(usize, usize)> = visible.iter().flat_map(edges).collect();
+        let horizon = visible.iter()
+            .flat_map(edges)
+            .filter(|(i, j)| !seen.contains(&(*j, *i)));
+
+        faces = hidden;
+        faces.extend(horizon.map(|(i, j)| [i, j, index]));
+    }
+
+    faces.into_iter()
+        .map(|[i, j, k]| [points[i], points[j], points[k]])
+        .collect()
+}
+
+/// Any unit vector perpendicular to v;
+pub(super) fn any_perpendicular(v: Vec3) -> Vec3 {
+    let other = match v.x.abs() < 0.9 {
+        true => Vec3::X,
+        false => Vec3::Y
+    };
+
+    v.cross(other).normalize()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    pub fn assert_vec(actual: Vec3, expected: Vec3) {
+        assert!(
+            actual.abs_diff_eq(expected, 1e-3),
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    pub fn assert_near(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn plane_plane_never_collides() {
+        let plane = Shape::plane(Vec3::Y, 0.0);
+        let result = collide(&plane, &Isometry::IDENTITY, &plane, &Isometry::IDENTITY);
+        assert!(result.is_none());
+    }
+
+    fn cube_hull() -> Shape {
+        Shape::convex_hull((0..8).map(|i| Vec3::new(
+            match i & 1 { 0 => -1.0, _ => 1.0 },
+            match i & 2 { 0 => -1.0, _ => 1.0 },
+            match i & 4 { 0 => -1.0, _ => 1.0 },
+        )))
+    }
+
+    #[test]
+    fn hull_hull_face_contact() {
+        let hull = cube_hull();
+        let above = Isometry::from_position(Vec3::new(0.5, 1.8, 0.0));
+
+        let m = collide(&hull, &Isometry::IDENTITY, &hull, &above).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert_near(p.depth, 0.2);
+        }
+
+        let apart = Isometry::from_position(Vec3::new(0.0, 2.1, 0.0));
+        assert!(collide(&hull, &Isometry::IDENTITY, &hull, &apart).is_none());
+    }
+
+    #[test]
+    fn sphere_hull() {
+        let sphere = Shape::sphere(0.5);
+        let above = Isometry::from_position(Vec3::new(0.2, 1.3, 0.0));
+
+        let m = collide(&sphere, &above, &cube_hull(), &Isometry::IDENTITY).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 0.2);
+    }
+
+    #[test]
+    fn segment_segment_crossing() {
+        let (s, t) = closest_segment_segment(
+            Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, -1.0), Vec3::new(0.0, 1.0, 1.0),
+        );
+
+        assert_near(s, 0.5);
+        assert_near(t, 0.5);
+    }
+}
@@ -0,0 +1,149 @@
+use glam::Vec3;
+
+use super::{any_perpendicular, Manifold};
+use super::super::shape::{Isometry, Shape};
+
+/// Convex shape against an infinite plane;
+/// Tests candidate surface points, each is a (point, radius) pair;
+pub(super) fn collide(shape: &Shape, iso: &Isometry, normal: Vec3, offset: f32, plane_iso: &Isometry) -> Option<Manifold> {
+    let normal = plane_iso.transform_vector(normal);
+    let offset = offset + normal.dot(plane_iso.position);
+
+    let mut manifold = Manifold::new(-normal);
+    for (point, radius) in candidates(shape, iso, normal) {
+        let distance = normal.dot(point) - offset - radius;
+        if distance <= 0.0 {
+            let deepest = point - normal * radius;
+            manifold.push(deepest - normal * distance * 0.5, -distance);
+        }
+    }
+
+    Some(manifold)
+}
+
+fn candidates(shape: &Shape, iso: &Isometry, normal: Vec3) -> Vec<(Vec3, f32)> {
+    match shape {
+        Shape::Sphere { radius } => vec![(iso.position, *radius)],
+        Shape::Box { half_extents } => {
+            let he = *half_extents;
+            (0..8)
+                .map(|i| {
+                    let corner = Vec3::new(
+                        match i & 1 { 0 => -he.x, _ => he.x },
+                        match i & 2 { 0 => -he.y, _ => he.y },
+                        match i & 4 { 0 => -he.z, _ => he.z },
+                    );
+                    (iso.transform_point(corner), 0.0)
+                })
+                .collect()
+        }
+        Shape::Capsule { half_height, radius } => {
+            vec![
+                (iso.transform_point(Vec3::Y * *half_height), *radius),
+                (iso.transform_point(Vec3::NEG_Y * *half_height), *radius),
+            ]
+        }
+        Shape::Cylinder { half_height, radius } => {
+            // Deepest rim point of every cap, or a ring of points when lying flat;
+            let local = iso.inverse_transform_vector(-normal);
+            let radial = Vec3::new(local.x, 0.0, local.z);
+
+            let directions = match radial.length() > 1e-3 {
+                true => vec![radial.normalize()],
+                false => {
+                    let u = any_perpendicular(Vec3::Y);
+                    let v = Vec3::Y.cross(u);
+                    vec![u, v, -u, -v]
+                }
+            };
+
+            let mut points = Vec::new();
+            for cap in [*half_height, -*half_height] {
+                for dir in &directions {
+                    points.push((iso.transform_point(Vec3::Y * cap + *dir * *radius), 0.0));
+                }
+            }
+
+            points
+        }
+        Shape::ConvexHull(hull) => hull.points()
+            .iter()
+            .map(|p| (iso.transform_point(*p), 0.0))
+            .collect(),
+        Shape::Plane { .. } | Shape::TriMesh(_) | Shape::HeightField(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::super::collide as narrow;
+    use super::super::tests::{assert_near, assert_vec};
+    use super::*;
+
+    fn at(x: f32, y: f32, z: f32) -> Isometry {
+        Isometry::from_position(Vec3::new(x, y, z))
+    }
+
+    fn ground() -> Shape {
+        Shape::plane(Vec3::Y, 0.0)
+    }
+
+    #[test]
+    fn box_plane() {
+        let m = narrow(&Shape::cuboid(Vec3::ONE), &at(0.0, 0.9, 0.0), &ground(), &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+            assert_near(p.point.y, -0.05);
+        }
+
+        // Balanced on an edge;
+        let tilted = Isometry::new(Vec3::new(0.0, 1.3, 0.0), Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+        let m = narrow(&Shape::cuboid(Vec3::ONE), &tilted, &ground(), &at(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(m.points.len(), 2);
+        assert_near(m.max_depth(), 2.0f32.sqrt() - 1.3);
+
+        assert!(narrow(&Shape::cuboid(Vec3::ONE), &at(0.0, 1.1, 0.0), &ground(), &at(0.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn capsule_plane() {
+        // Lying on its side;
+        let lying = Isometry::new(Vec3::new(0.0, 0.4, 0.0), Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let m = narrow(&ground(), &at(0.0, 0.0, 0.0), &Shape::capsule(1.0, 0.5), &lying).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+        }
+
+        // Standing up;
+        let m = narrow(&Shape::capsule(1.0, 0.5), &at(0.0, 1.3, 0.0), &ground(), &at(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 0.2);
+        assert_vec(m.points[0].point, Vec3::new(0.0, -0.1, 0.0));
+    }
+
+    #[test]
+    fn cylinder_plane() {
+        // Standing on a cap;
+        let m = narrow(&Shape::cylinder(1.0, 0.5), &at(0.0, 0.8, 0.0), &ground(), &at(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert_near(p.depth, 0.2);
+        }
+
+        // Lying on its side;
+        let lying = Isometry::new(Vec3::new(0.0, 0.45, 0.0), Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+        let m = narrow(&Shape::cylinder(1.0, 0.5), &lying, &ground(), &at(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.05);
+        }
+
+        assert!(narrow(&Shape::cylinder(1.0, 0.5), &lying, &ground(), &at(0.0, -1.0, 0.0)).is_none());
+    }
+}
@@ -0,0 +1,534 @@
+use std::f32::consts::TAU;
+
+use glam::Vec3;
+
+use super::{closest_segment_segment, Manifold};
+use super::super::shape::{Isometry, Shape};
+
+/// Below this a feature direction component counts as zero;
+const FEATURE_TOLERANCE: f32 = 0.02;
+/// Edge axes must beat face axes by this factor to be picked;
+const EDGE_BIAS: f32 = 0.95;
+const CAP_SEGMENTS: usize = 8;
+const MAX_POINTS: usize = 4;
+/// Depths this close count as equally deep when reducing a manifold;
+const DEPTH_TIE: f32 = 1e-3;
+
+/// Box && cylinder pairs: separating axis test over candidate axes, then feature clipping;
+pub(super) fn collide(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry) -> Option<Manifold> {
+    let mut best: Option<(Vec3, f32)> = None;
+
+    for (axis, is_edge) in candidate_axes(a, iso_a, b, iso_b) {
+        let Some(axis) = axis.try_normalize() else { continue };
+
+        // Overlap along both orientations, keep the smaller one;
+        let forward = support(a, iso_a, axis).dot(axis) - support(b, iso_b, -axis).dot(axis);
+        let backward = support(b, iso_b, axis).dot(axis) - support(a, iso_a, -axis).dot(axis);
+
+        let (normal, depth) = match forward <= backward {
+            true => (axis, forward),
+            false => (-axis, backward)
+        };
+
+        if depth < 0.0 {
+            return None;
+        }
+
+        let better = match best {
+            None => true,
+            Some((_, best_depth)) => match is_edge {
+                true => depth < best_depth * EDGE_BIAS,
+                false => depth < best_depth
+            }
+        };
+
+        if better {
+            best = Some((normal, depth));
+        }
+    }
+
+    let (normal, depth) = best?;
+    Some(clip(a, iso_a, b, iso_b, normal, depth))
+}
+
+/// World space support point;
+pub(super) fn support(shape: &Shape, iso: &Isometry, dir: Vec3) -> Vec3 {
+    iso.transform_point(shape.support(iso.inverse_transform_vector(dir)))
+}
+
+/// World space edge directions, the cylinder contributes its axis;
+fn edges(shape: &Shape, iso: &Isometry) -> Vec<Vec3> {
+    match shape {
+        Shape::Box { .. } => vec![
+            iso.transform_vector(Vec3::X),
+            iso.transform_vector(Vec3::Y),
+            iso.transform_vector(Vec3::Z),
+        ],
+        Shape::Cylinder { .. } | Shape::Capsule { .. } => vec![iso.transform_vector(Vec3::Y)],
+        _ => Vec::new(),
+    }
+}
+
+/// Box corners or cylinder cap centers;
+fn key_points(shape: &Shape, iso: &Isometry) -> Vec<Vec3> {
+    match shape {
+        Shape::Box { half_extents } => (0..8)
+            .map(|i| {
+                let corner = Vec3::new(
+                    match i & 1 { 0 => -half_extents.x, _ => half_extents.x },
+                    match i & 2 { 0 => -half_extents.y, _ => half_extents.y },
+                    match i & 4 { 0 => -half_extents.z, _ => half_extents.z },
+                );
+                iso.transform_point(corner)
+            })
+            .collect(),
+        Shape::Cylinder { half_height, .. } | Shape::Capsule { half_height, .. } => vec![
+            iso.transform_point(Vec3::Y * *half_height),
+            iso.transform_point(Vec3::NEG_Y * *half_height),
+        ],
+        _ => vec![iso.position],
+    }
+}
+
+/// Radial directions from a cylinder axis towards the other shape;
+fn radial_axes(shape: &Shape, iso: &Isometry, other: &Shape, other_iso: &Isometry) -> Vec<Vec3> {
+    let Shape::Cylinder { half_height, .. } = shape else { return Vec::new() };
+
+    let axis = iso.transform_vector(Vec3::Y);
+    let radial = |v: Vec3| v - axis * axis.dot(v);
+
+    let mut axes = vec![radial(other_iso.position - iso.position)];
+    axes.extend(key_points(other, other_iso).into_iter().map(|p| radial(p - iso.position)));
+
+    if let Shape::Cylinder { half_height: other_height, .. } = other {
+        let top = iso.transform_point(Vec3::Y * *half_height);
+        let bottom = iso.transform_point(Vec3::NEG_Y * *half_height);
+        let other_top = other_iso.transform_point(Vec3::Y * *other_height);
+        let other_bottom = other_iso.transform_point(Vec3::NEG_Y * *other_height);
+
+        let (s, t) = closest_segment_segment(bottom, top, other_bottom, other_top);
+        let closest = bottom.lerp(top, s);
+        let other_closest = other_bottom.lerp(other_top, t);
+        axes.push(other_closest - closest);
+    }
+
+    axes
+}
+
+fn candidate_axes(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry) -> Vec<(Vec3, bool)> {
+    let edges_a = edges(a, iso_a);
+    let edges_b = edges(b, iso_b);
+
+    let mut axes: Vec<(Vec3, bool)> = Vec::new();
+    axes.extend(edges_a.iter().map(|e| (*e, false)));
+    axes.extend(edges_b.iter().map(|e| (*e, false)));
+    axes.extend(radial_axes(a, iso_a, b, iso_b).into_iter().map(|e| (e, false)));
+    axes.extend(radial_axes(b, iso_b, a, iso_a).into_iter().map(|e| (e, false)));
+
+    for ea in &edges_a {
+        for eb in &edges_b {
+            let cross = ea.cross(*eb);
+            if cross.length_squared() > 1e-6 {
+                axes.push((cross, true));
+            }
+        }
+    }
+
+    axes
+}
+
+/// Support feature in world space: face polygon, edge or single point;
+pub(super) fn feature(shape: &Shape, iso: &Isometry, dir: Vec3) -> Vec<Vec3> {
+    let local = iso.inverse_transform_vector(dir).normalize();
+
+    let points = match shape {
+        Shape::Box { half_extents } => {
+            let he = *half_extents;
+            let free: Vec<usize> = (0..3).filter(|i| local[*i].abs() < FEATURE_TOLERANCE).collect();
+            let corner = |signs: Vec3| he * signs;
+            let fixed = Vec3::new(sign(local.x), sign(local.y), sign(local.z));
+
+            match free.len() {
+                2 => {
+                    let (j, k) = (free[0], free[1]);
+                    [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)]
+                        .into_iter()
+                        .map(|(sj, sk)| {
+                            let mut signs = fixed;
+                            signs[j] = sj;
+                            signs[k] = sk;
+                            corner(signs)
+                        })
+                        .collect()
+                }
+                1 => {
+                    let j = free[0];
+                    [1.0, -1.0]
+                        .into_iter()
+                        .map(|sj| {
+                            let mut signs = fixed;
+                            signs[j] = sj;
+                            corner(signs)
+                        })
+                        .collect()
+                }
+                _ => vec![corner(fixed)]
+            }
+        }
+        Shape::Cylinder { half_height, radius } => {
+            let radial = Vec3::new(local.x, 0.0, local.z);
+
+            if local.y.abs() > 1.0 - FEATURE_TOLERANCE {
+                let cap = Vec3::Y * half_height.copysign(local.y);
+                (0..CAP_SEGMENTS)
+                    .map(|i| {
+                        let angle = TAU * i as f32 / CAP_SEGMENTS as f32;
+                        cap + Vec3::new(angle.cos(), 0.0, angle.sin()) * *radius
+                    })
+                    .collect()
+            } else if local.y.abs() < FEATURE_TOLERANCE {
+                let side = radial.normalize() * *radius;
+                vec![side + Vec3::Y * *half_height, side - Vec3::Y * *half_height]
+            } else {
+                vec![shape.support(local)]
+            }
+        }
+        Shape::ConvexHull(hull) => hull_feature(hull.points(), local),
+        _ => vec![shape.support(local)],
+    };
+
+    points.into_iter().map(|p| iso.transform_point(p)).collect()
+}
+
+/// Hull points close to the supporting plane, wrapped into a convex polygon;
+/// Polygons are counter clockwise seen from the direction;
+pub(super) fn hull_feature(points: &[Vec3], dir: Vec3) -> Vec<Vec3> {
+    let max = points.iter().map(|p| p.dot(dir)).fold(f32::MIN, f32::max);
+    let size = points.iter().map(|p| p.length()).fold(0.0, f32::max);
+    let tolerance = FEATURE_TOLERANCE * size.max(f32::EPSILON);
+
+    let on_plane: Vec<Vec3> = points.iter()
+        .copied()
+        .filter(|p| max - p.dot(dir) <= tolerance)
+        .collect();
+
+    if on_plane.len() < 3 {
+        return on_plane;
+    }
+
+    // Monotone chain over plane coordinates;
+    let u = super::any_perpendicular(dir);
+    let v = dir.cross(u);
+    let mut sorted: Vec<(f32, f32, Vec3)> = on_plane.iter().map(|p| (p.dot(u), p.dot(v), *p)).collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let cross = |o: &(f32, f32, Vec3), a: &(f32, f32, Vec3), b: &(f32, f32, Vec3)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    let mut hull: Vec<(f32, f32, Vec3)> = Vec::new();
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item=&(f32, f32, Vec3)>> = match pass {
+            0 => Box::new(sorted.iter()),
+            _ => Box::new(sorted.iter().rev())
+        };
+
+        for p in iter {
+            while hull.len() >= start + 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], p) <= 1e-6 {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+
+        hull.pop();
+    }
+
+    if hull.len() < 3 {
+        // Collinear: keep both extremes;
+        let first = sorted.first().unwrap().2;
+        let last = sorted.last().unwrap().2;
+        return vec![first, last];
+    }
+
+    hull.into_iter().map(|p| p.2).collect()
+}
+
+fn sign(value: f32) -> f32 {
+    match value < 0.0 {
+        true => -1.0,
+        false => 1.0
+    }
+}
+
+/// Build contact points from support features along the separating normal (a -> b);
+pub(super) fn clip(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry, normal: Vec3, depth: f32) -> Manifold {
+    let feature_a = feature(a, iso_a, normal);
+    let feature_b = feature(b, iso_b, -normal);
+
+    let mut manifold = Manifold::new(normal);
+
+    // Reference face is the feature with more points;
+    let (reference, incident, outward) = match feature_a.len() >= feature_b.len() {
+        true => (&feature_a, &feature_b, normal),
+        false => (&feature_b, &feature_a, -normal)
+    };
+
+    if reference.len() >= 3 {
+        let mut face_normal = (reference[1] - reference[0])
+            .cross(reference[2] - reference[0])
+            .normalize();
+        if face_normal.dot(outward) < 0.0 {
+            face_normal = -face_normal;
+        }
+
+        let clipped = clip_to_polygon(incident, reference, face_normal);
+        for point in clipped {
+            let point_depth = face_normal.dot(reference[0] - point);
+            if point_depth >= 0.0 {
+                manifold.push(point + face_normal * point_depth * 0.5, point_depth);
+            }
+        }
+
+        reduce(&mut manifold);
+    } else if reference.len() == 2 && incident.len() == 2 {
+        let ref_dir = (reference[1] - reference[0]).normalize();
+        let inc_dir = (incident[1] - incident[0]).normalize();
+
+        match ref_dir.dot(inc_dir).abs() > 1.0 - FEATURE_TOLERANCE {
+            true => {
+                // Parallel edges: clip the incident edge to the reference extent;
+                let length = (reference[1] - reference[0]).length();
+                for point in incident {
+                    let along = (*point - reference[0]).dot(ref_dir).clamp(0.0, length);
+                    let on_reference = reference[0] + ref_dir * along;
+                    let clamped = *point + ref_dir * (along - (*point - reference[0]).dot(ref_dir));
+                    let point_depth = outward.dot(on_reference - clamped);
+                    if point_depth >= 0.0 {
+                        manifold.push(clamped + outward * point_depth * 0.5, point_depth);
+                    }
+                }
+            }
+            false => {
+                let (s, t) = closest_segment_segment(reference[0], reference[1], incident[0], incident[1]);
+                let mid = (reference[0].lerp(reference[1], s) + incident[0].lerp(incident[1], t)) * 0.5;
+                manifold.push(mid, depth);
+            }
+        }
+    }
+
+    if manifold.points.is_empty() {
+        // Point feature or degenerate clip: single point from the supports;
+        let deepest_a = support(a, iso_a, normal);
+        let deepest_b = support(b, iso_b, -normal);
+        let point = match feature_a.len() <= feature_b.len() {
+            true => deepest_a - normal * depth * 0.5,
+            false => deepest_b + normal * depth * 0.5
+        };
+
+        manifold.push(point, depth);
+    }
+
+    manifold
+}
+
+/// Clip a point, segment or polygon against the side planes of a face;
+fn clip_to_polygon(incident: &[Vec3], face: &[Vec3], face_normal: Vec3) -> Vec<Vec3> {
+    let center = face.iter().sum::<Vec3>() / face.len() as f32;
+    let mut points = incident.to_vec();
+
+    for i in 0..face.len() {
+        let start = face[i];
+        let end = face[(i + 1) % face.len()];
+
+        let mut side = (end - start).cross(face_normal);
+        if side.dot(center - start) > 0.0 {
+            side = -side;
+        }
+
+        points = clip_by_plane(&points, side, side.dot(start));
+        if points.is_empty() {
+            break;
+        }
+    }
+
+    points
+}
+
+/// Keep the part with `normal.dot(p) <= offset`;
+fn clip_by_plane(points: &[Vec3], normal: Vec3, offset: f32) -> Vec<Vec3> {
+    let distance = |p: Vec3| normal.dot(p) - offset;
+
+    match points.len() {
+        0 => Vec::new(),
+        1 => points.iter().copied().filter(|p| distance(*p) <= 0.0).collect(),
+        2 => {
+            let (p, q) = (points[0], points[1]);
+            let (dp, dq) = (distance(p), distance(q));
+            match (dp <= 0.0, dq <= 0.0) {
+                (true, true) => vec![p, q],
+                (false, false) => Vec::new(),
+                (true, false) => vec![p, p.lerp(q, dp / (dp - dq))],
+                (false, true) => vec![p.lerp(q, dp / (dp - dq)), q],
+            }
+        }
+        _ => {
+            let mut result = Vec::new();
+            for i in 0..points.len() {
+                let p = points[i];
+                let q = points[(i + 1) % points.len()];
+                let (dp, dq) = (distance(p), distance(q));
+
+                if dp <= 0.0 {
+                    result.push(p);
+                }
+
+                if (dp <= 0.0) != (dq <= 0.0) {
+                    result.push(p.lerp(q, dp / (dp - dq)));
+                }
+            }
+
+            result
+        }
+    }
+}
+
+/// Keep at most four points: deepest, farthest from it, then the widest on each side;
+pub(super) fn reduce(manifold: &mut Manifold) {
+    if manifold.points.len() <= MAX_POINTS {
+        return;
+    }
+
+    let points = std::mem::take(&mut manifold.points);
+    let normal = manifold.normal;
+
+    // Flat contacts tie on depth, the point farthest out then keeps the patch wide;
+    let max_depth = points.iter().map(|p| p.depth).fold(f32::MIN, f32::max);
+    let center = points.iter().map(|p| p.point).sum::<Vec3>() / points.len() as f32;
+    let deepest = (0..points.len())
+        .filter(|i| points[*i].depth >= max_depth - DEPTH_TIE)
+        .max_by(|i, j| {
+            let di = points[*i].point.distance_squared(center);
+            let dj = points[*j].point.distance_squared(center);
+            di.total_cmp(&dj)
+        })
+        .unwrap();
+    let a = points[deepest].point;
+
+    let farthest = (0..points.len())
+        .max_by(|i, j| {
+            let di = points[*i].point.distance_squared(a);
+            let dj = points[*j].point.distance_squared(a);
+            di.total_cmp(&dj)
+        })
+        .unwrap();
+    let b = points[farthest].point;
+
+    let area = |i: usize| (points[i].point - a).cross(b - a).dot(normal);
+    let left = (0..points.len()).max_by(|i, j| area(*i).total_cmp(&area(*j))).unwrap();
+    let right = (0..points.len()).min_by(|i, j| area(*i).total_cmp(&area(*j))).unwrap();
+
+    let mut chosen = vec![deepest, farthest, left, right];
+    chosen.dedup();
+    chosen.sort_unstable();
+    chosen.dedup();
+
+    manifold.points = chosen.into_iter().map(|i| points[i]).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use glam::Quat;
+
+    use super::super::collide as narrow;
+    use super::super::tests::{assert_near, assert_vec};
+    use super::*;
+
+    fn at(x: f32, y: f32, z: f32) -> Isometry {
+        Isometry::from_position(Vec3::new(x, y, z))
+    }
+
+    #[test]
+    fn box_box() {
+        let cube = Shape::cuboid(Vec3::ONE);
+
+        // Stacked, offset: face contact with a clipped square;
+        let m = narrow(&cube, &at(0.0, 0.0, 0.0), &cube, &at(0.5, 1.9, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+            assert_near(p.point.y, 0.95);
+            assert!(p.point.x >= -0.5 - 1e-3 && p.point.x <= 1.0 + 1e-3);
+        }
+
+        // Edge down onto a face;
+        let tilted = Isometry::new(Vec3::new(0.0, 2.3, 0.0), Quat::from_rotation_z(FRAC_PI_4));
+        let m = narrow(&cube, &at(0.0, 0.0, 0.0), &cube, &tilted).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 1.0 + 2.0f32.sqrt() - 2.3);
+        }
+
+        // Crossed edges: both boxes rotated so only edges meet;
+        let a = Isometry::new(Vec3::ZERO, Quat::from_rotation_x(FRAC_PI_4));
+        let b = Isometry::new(Vec3::new(0.0, 2.0 * 2.0f32.sqrt() - 0.1, 0.0), Quat::from_rotation_z(FRAC_PI_4));
+        let m = narrow(&cube, &a, &cube, &b).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 0.1);
+
+        assert!(narrow(&cube, &at(0.0, 0.0, 0.0), &cube, &at(0.0, 2.1, 0.0)).is_none());
+    }
+
+    #[test]
+    fn box_cylinder() {
+        let cube = Shape::cuboid(Vec3::ONE);
+        let cylinder = Shape::cylinder(1.0, 0.5);
+
+        // Standing on the box;
+        let m = narrow(&cube, &at(0.0, 0.0, 0.0), &cylinder, &at(0.0, 1.8, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert_near(p.depth, 0.2);
+        }
+
+        // Lying on the box;
+        let lying = Isometry::new(Vec3::new(0.0, 1.4, 0.0), Quat::from_rotation_x(FRAC_PI_2));
+        let m = narrow(&cylinder, &lying, &cube, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+        }
+
+        assert!(narrow(&cube, &at(0.0, 0.0, 0.0), &cylinder, &at(0.0, 0.0, 1.6)).is_none());
+    }
+
+    #[test]
+    fn cylinder_cylinder() {
+        let cylinder = Shape::cylinder(1.0, 0.5);
+
+        // Stacked caps;
+        let m = narrow(&cylinder, &at(0.0, 0.0, 0.0), &cylinder, &at(0.0, 1.9, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_eq!(m.points.len(), 4);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+        }
+
+        // Side by side, parallel;
+        let m = narrow(&cylinder, &at(0.0, 0.0, 0.0), &cylinder, &at(0.9, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::X);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+        }
+
+        assert!(narrow(&cylinder, &at(0.0, 0.0, 0.0), &cylinder, &at(0.0, 0.0, 1.1)).is_none());
+    }
+}
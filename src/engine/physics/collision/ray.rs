@@ -0,0 +1,185 @@
+use glam::Vec3;
+
+use super::gjk::{distance, Posed};
+use super::super::shape::{Isometry, Shape};
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f32 = 1e-4;
+
+/// First hit of the segment [origin; origin + translation] with a posed shape;
+/// Returns the fraction along the translation && the outward world normal;
+/// Origins inside the shape hit at fraction zero, facing back along the ray;
+pub fn cast_ray(shape: &Shape, iso: &Isometry, origin: Vec3, translation: Vec3) -> Option<(f32, Vec3)> {
+    let local_origin = iso.inverse_transform_point(origin);
+    let local_translation = iso.inverse_transform_vector(translation);
+
+    let (fraction, normal) = match shape {
+        Shape::Sphere { radius } => sphere(*radius, local_origin, local_translation),
+        Shape::Box { half_extents } => cuboid(*half_extents, local_origin, local_translation),
+        Shape::Plane { normal, offset } => plane(*normal, *offset, local_origin, local_translation),
+        Shape::TriMesh(_) | Shape::HeightField(_) => shape.triangles()?.cast_ray(local_origin, local_translation),
+        _ => march(shape, local_origin, local_translation),
+    }?;
+
+    Some((fraction, iso.transform_vector(normal)))
+}
+
+fn inside(translation: Vec3) -> Option<(f32, Vec3)> {
+    Some((0.0, -translation.normalize_or_zero()))
+}
+
+fn sphere(radius: f32, origin: Vec3, translation: Vec3) -> Option<(f32, Vec3)> {
+    let c = origin.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return inside(translation);
+    }
+
+    let a = translation.length_squared();
+    let b = origin.dot(translation);
+    let discriminant = b * b - a * c;
+    if b >= 0.0 || discriminant < 0.0 || a <= f32::EPSILON {
+        return None;
+    }
+
+    let fraction = (-b - discriminant.sqrt()) / a;
+    match fraction <= 1.0 {
+        true => Some((fraction, (origin + translation * fraction) / radius)),
+        false => None
+    }
+}
+
+/// Slab test, the last entered slab gives the normal;
+fn cuboid(half_extents: Vec3, origin: Vec3, translation: Vec3) -> Option<(f32, Vec3)> {
+    if (origin.abs() - half_extents).max_element() <= 0.0 {
+        return inside(translation);
+    }
+
+    let mut enter = 0.0f32;
+    let mut exit = 1.0f32;
+    let mut normal = Vec3::ZERO;
+
+    for axis in 0..3 {
+        let (o, d, h) = (origin[axis], translation[axis], half_extents[axis]);
+        if d.abs() <= f32::EPSILON {
+            if o.abs() > h {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far) = ((-h.copysign(d) - o) / d, (h.copysign(d) - o) / d);
+        if near > enter {
+            enter = near;
+            normal = Vec3::ZERO;
+            normal[axis] = -d.signum();
+        }
+        exit = exit.min(far);
+
+        if enter > exit {
+            return None;
+        }
+    }
+
+    Some((enter, normal))
+}
+
+fn plane(normal: Vec3, offset: f32, origin: Vec3, translation: Vec3) -> Option<(f32, Vec3)> {
+    let height = normal.dot(origin) - offset;
+    if height <= 0.0 {
+        return inside(translation);
+    }
+
+    let speed = normal.dot(translation);
+    match speed < 0.0 && height <= -speed {
+        true => Some((height / -speed, normal)),
+        false => None
+    }
+}
+
+/// Sphere tracing with GJK distances, any convex shape;
+fn march(shape: &Shape, origin: Vec3, translation: Vec3) -> Option<(f32, Vec3)> {
+    let length = translation.length();
+    if length <= f32::EPSILON {
+        return None;
+    }
+
+    let dir = translation / length;
+    let posed = Posed::new(shape, &Isometry::IDENTITY);
+
+    let mut t = 0.0;
+    for _ in 0..MAX_ITERATIONS {
+        let point = origin + dir * t;
+        let Some((gap, _, closest)) = distance(&point, &posed) else {
+            return match t == 0.0 {
+                true => inside(translation),
+                false => Some((t / length, -dir))
+            };
+        };
+
+        let normal = (point - closest).try_normalize().unwrap_or(-dir);
+        if gap <= TOLERANCE {
+            return Some((t / length, normal));
+        }
+
+        // Heading away from a convex shape never hits it;
+        if dir.dot(normal) >= 0.0 {
+            return None;
+        }
+
+        t += gap;
+        if t > length {
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::*;
+    use super::super::tests::{assert_near, assert_vec};
+
+    #[test]
+    fn ray_sphere() {
+        let iso = Isometry::from_position(Vec3::new(5.0, 0.0, 0.0));
+        let (fraction, normal) = cast_ray(&Shape::sphere(1.0), &iso, Vec3::ZERO, Vec3::X * 10.0).unwrap();
+        assert_near(fraction, 0.4);
+        assert_vec(normal, Vec3::NEG_X);
+
+        assert!(cast_ray(&Shape::sphere(1.0), &iso, Vec3::ZERO, Vec3::X * 3.0).is_none());
+        assert!(cast_ray(&Shape::sphere(1.0), &iso, Vec3::ZERO, Vec3::NEG_X * 10.0).is_none());
+    }
+
+    #[test]
+    fn ray_rotated_box() {
+        let iso = Isometry::new(Vec3::new(0.0, 5.0, 0.0), Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+        let (fraction, normal) = cast_ray(&Shape::cuboid(Vec3::ONE), &iso, Vec3::ZERO, Vec3::Y * 10.0).unwrap();
+
+        // Hits the lower corner, sqrt(2) below the center;
+        assert_near(fraction * 10.0, 5.0 - 2.0f32.sqrt());
+        assert!(normal.y < 0.0);
+
+        let (fraction, normal) = cast_ray(&Shape::cuboid(Vec3::ONE), &Isometry::IDENTITY, Vec3::ZERO, Vec3::X).unwrap();
+        assert_near(fraction, 0.0);
+        assert_vec(normal, Vec3::NEG_X);
+    }
+
+    #[test]
+    fn ray_plane_and_capsule() {
+        let ground = Shape::plane(Vec3::Y, 0.0);
+        let (fraction, normal) = cast_ray(&ground, &Isometry::IDENTITY, Vec3::new(1.0, 4.0, 0.0), Vec3::new(0.0, -8.0, 0.0)).unwrap();
+        assert_near(fraction, 0.5);
+        assert_vec(normal, Vec3::Y);
+
+        let capsule = Shape::capsule(1.0, 0.5);
+        let (fraction, normal) = cast_ray(&capsule, &Isometry::IDENTITY, Vec3::new(-3.0, 0.5, 0.0), Vec3::X * 5.0).unwrap();
+        assert_near(fraction * 5.0, 2.5);
+        assert_vec(normal, Vec3::NEG_X);
+
+        // Grazing past the cap;
+        assert!(cast_ray(&capsule, &Isometry::IDENTITY, Vec3::new(-3.0, 1.6, 0.0), Vec3::X * 5.0).is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use glam::Vec3;
+
+use super::Manifold;
+use super::super::shape::{Isometry, Shape};
+
+/// Sphere against any shape via the shape's signed distance;
+pub(super) fn collide(radius: f32, center: Vec3, other: &Shape, iso: &Isometry) -> Option<Manifold> {
+    let (distance, outward) = other.distance(iso, center);
+    if distance > radius {
+        return None;
+    }
+
+    // Midway between the deepest sphere point && the other surface;
+    let point = center - outward * (radius + distance) * 0.5;
+    Some(Manifold::single(-outward, point, radius - distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::super::collide as narrow;
+    use super::super::tests::{assert_near, assert_vec};
+    use super::*;
+
+    fn at(x: f32, y: f32, z: f32) -> Isometry {
+        Isometry::from_position(Vec3::new(x, y, z))
+    }
+
+    #[test]
+    fn sphere_sphere() {
+        let m = narrow(&Shape::sphere(1.0), &at(0.0, 0.0, 0.0), &Shape::sphere(1.0), &at(1.5, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::X);
+        assert_near(m.points[0].depth, 0.5);
+        assert_vec(m.points[0].point, Vec3::new(0.75, 0.0, 0.0));
+
+        assert!(narrow(&Shape::sphere(1.0), &at(0.0, 0.0, 0.0), &Shape::sphere(1.0), &at(2.1, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn sphere_box() {
+        let cube = Shape::cuboid(Vec3::ONE);
+
+        // Face contact from above;
+        let m = narrow(&Shape::sphere(0.5), &at(0.0, 1.4, 0.0), &cube, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_near(m.points[0].depth, 0.1);
+        assert_vec(m.points[0].point, Vec3::new(0.0, 0.95, 0.0));
+
+        // Rotated box, corner contact;
+        let rotated = Isometry::new(Vec3::ZERO, Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+        let corner = 2.0f32.sqrt();
+        let m = narrow(&cube, &rotated, &Shape::sphere(0.5), &at(corner + 0.4, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::X);
+        assert_near(m.points[0].depth, 0.1);
+
+        // Center inside the box;
+        let m = narrow(&Shape::sphere(0.5), &at(0.0, 0.8, 0.0), &cube, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_near(m.points[0].depth, 0.7);
+
+        assert!(narrow(&Shape::sphere(0.5), &at(0.0, 1.6, 0.0), &cube, &at(0.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn sphere_capsule() {
+        let capsule = Shape::capsule(1.0, 0.5);
+
+        // Against the cylindrical part;
+        let m = narrow(&capsule, &at(0.0, 0.0, 0.0), &Shape::sphere(0.5), &at(0.8, 0.5, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::X);
+        assert_near(m.points[0].depth, 0.2);
+        assert_vec(m.points[0].point, Vec3::new(0.4, 0.5, 0.0));
+
+        // Against the top cap;
+        let m = narrow(&Shape::sphere(0.5), &at(0.0, 1.9, 0.0), &capsule, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_near(m.points[0].depth, 0.1);
+
+        assert!(narrow(&Shape::sphere(0.5), &at(1.1, 0.0, 0.0), &capsule, &at(0.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn sphere_cylinder() {
+        let cylinder = Shape::cylinder(1.0, 1.0);
+
+        // Side;
+        let m = narrow(&Shape::sphere(0.5), &at(0.0, 0.0, 1.25), &cylinder, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Z);
+        assert_near(m.points[0].depth, 0.25);
+
+        // Cap;
+        let m = narrow(&Shape::sphere(0.5), &at(0.5, -1.3, 0.0), &cylinder, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_near(m.points[0].depth, 0.2);
+
+        // Rim: closest point is (1, 1, 0);
+        let m = narrow(&Shape::sphere(0.5), &at(1.24, 1.32, 0.0), &cylinder, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::new(0.6, 0.8, 0.0));
+        assert_near(m.points[0].depth, 0.1);
+
+        assert!(narrow(&Shape::sphere(0.5), &at(1.4, 1.4, 0.0), &cylinder, &at(0.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn sphere_plane() {
+        let plane = Shape::plane(Vec3::Y, 1.0);
+        let m = narrow(&Shape::sphere(1.0), &at(2.0, 1.5, 0.0), &plane, &at(0.0, 0.0, 0.0)).unwrap();
+        assert_vec(m.normal, -Vec3::Y);
+        assert_near(m.points[0].depth, 0.5);
+        assert_vec(m.points[0].point, Vec3::new(2.0, 0.75, 0.0));
+
+        // Plane moved by its body transform;
+        let m = narrow(&plane, &at(0.0, -1.0, 0.0), &Shape::sphere(1.0), &at(0.0, 0.8, 0.0)).unwrap();
+        assert_vec(m.normal, Vec3::Y);
+        assert_near(m.points[0].depth, 0.2);
+
+        assert!(narrow(&Shape::sphere(1.0), &at(0.0, 2.1, 0.0), &plane, &at(0.0, 0.0, 0.0)).is_none());
+    }
+}
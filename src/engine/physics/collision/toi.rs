@@ -0,0 +1,192 @@
+use glam::{Quat, Vec3};
+
+use super::gjk::{distance, Posed};
+use super::super::mesh::Triangles;
+use super::super::shape::{Aabb, Isometry, Shape};
+
+const MAX_ITERATIONS: usize = 32;
+
+/// Constant velocities of a shape during a sweep;
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Motion {
+    pub linear: Vec3,
+    pub angular: Vec3,
+}
+
+impl Motion {
+    pub fn new(linear: Vec3, angular: Vec3) -> Self {
+        Self { linear, angular }
+    }
+
+    /// Max speed of a surface point due to rotation;
+    fn spin_speed(&self, shape: &Shape) -> f32 {
+        match self.angular == Vec3::ZERO {
+            true => 0.0,
+            false => self.angular.length() * shape.bounding_radius()
+        }
+    }
+
+    /// Pose after moving for t seconds;
+    pub fn at(&self, iso: &Isometry, t: f32) -> Isometry {
+        Isometry::new(
+            iso.position + self.linear * t,
+            (Quat::from_scaled_axis(self.angular * t) * iso.rotation).normalize(),
+        )
+    }
+}
+
+/// First time in [0; max_time] where both sweeping shapes come within tolerance;
+/// Conservative advancement: step by the distance over the max approach speed;
+/// None when they stay apart or already overlap at the start;
+/// Against mesh colliders it is the first hit of any triangle not yet touched;
+pub fn time_of_impact(
+    a: &Posed<Shape>, motion_a: &Motion,
+    b: &Posed<Shape>, motion_b: &Motion,
+    max_time: f32,
+    tolerance: f32,
+) -> Option<f32> {
+    if let Some(mesh) = b.shape.triangles() {
+        return mesh_time_of_impact(a, motion_a, mesh, b.iso, motion_b, max_time, tolerance);
+    }
+    if let Some(mesh) = a.shape.triangles() {
+        return mesh_time_of_impact(b, motion_b, mesh, a.iso, motion_a, max_time, tolerance);
+    }
+
+    let (Posed { shape: a, iso: iso_a }, Posed { shape: b, iso: iso_b }) = (a, b);
+    let spin = motion_a.spin_speed(a) + motion_b.spin_speed(b);
+
+    // A noisy normal near contact can step past it, the last time still apart is the hit then;
+    let mut t = 0.0;
+    let mut apart = None;
+    for _ in 0..MAX_ITERATIONS {
+        let (pose_a, pose_b) = (motion_a.at(iso_a, t), motion_b.at(iso_b, t));
+        let Some((distance, normal)) = separation(a, &pose_a, b, &pose_b) else { return apart };
+
+        if distance <= tolerance {
+            return Some(t);
+        }
+
+        let approach = (motion_a.linear - motion_b.linear).dot(normal) + spin;
+        if approach <= f32::EPSILON {
+            return None;
+        }
+
+        apart = Some(t);
+        t += (distance - tolerance * 0.5) / approach;
+        if t > max_time {
+            return None;
+        }
+    }
+
+    Some(t)
+}
+
+/// Earliest hit among the triangles near the sweep, meshes are assumed to stay in place;
+fn mesh_time_of_impact(
+    shape: &Posed<Shape>, motion: &Motion,
+    mesh: &dyn Triangles, mesh_iso: &Isometry, mesh_motion: &Motion,
+    max_time: f32,
+    tolerance: f32,
+) -> Option<f32> {
+    if !shape.shape.is_convex() {
+        return None;
+    }
+
+    let start = shape.iso.position;
+    let end = start + (motion.linear - mesh_motion.linear) * max_time;
+    let sweep = Aabb::from_points([start, end]).expanded(shape.shape.bounding_radius() + tolerance);
+
+    let local_start = mesh_iso.inverse_transform_point(start);
+    let mut candidates = Vec::new();
+    mesh.query_aabb(&sweep.transformed(&mesh_iso.inverse()), &mut |index| candidates.push(index));
+
+    candidates.into_iter()
+        .map(|index| mesh.triangle(index))
+        .filter(|t| !t.is_degenerate() && t.normal.dot(local_start - t.points[0]) >= 0.0)
+        .filter_map(|t| {
+            let hull = t.hull();
+            time_of_impact(shape, motion, &Posed::new(&hull, mesh_iso), mesh_motion, max_time, tolerance)
+        })
+        .min_by(f32::total_cmp)
+}
+
+/// Distance && unit direction from A to B, None while overlapping;
+fn separation(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry) -> Option<(f32, Vec3)> {
+    match (a, b) {
+        (Shape::Plane { .. }, Shape::Plane { .. }) => None,
+        (_, Shape::Plane { normal, offset }) => plane_separation(a, iso_a, *normal, *offset, iso_b),
+        (Shape::Plane { normal, offset }, _) => {
+            plane_separation(b, iso_b, *normal, *offset, iso_a).map(|(d, n)| (d, -n))
+        }
+        _ => {
+            let (distance, point_a, point_b) = distance(&Posed::new(a, iso_a), &Posed::new(b, iso_b))?;
+            match distance > f32::EPSILON {
+                true => Some((distance, (point_b - point_a) / distance)),
+                false => None
+            }
+        }
+    }
+}
+
+/// Gap between a convex shape && a plane, direction from the shape to the plane;
+fn plane_separation(shape: &Shape, iso: &Isometry, normal: Vec3, offset: f32, plane_iso: &Isometry) -> Option<(f32, Vec3)> {
+    let normal = plane_iso.transform_vector(normal);
+    let offset = offset + normal.dot(plane_iso.position);
+
+    let deepest = iso.transform_point(shape.support(iso.inverse_transform_vector(-normal)));
+    let distance = normal.dot(deepest) - offset;
+
+    match distance > 0.0 {
+        true => Some((distance, -normal)),
+        false => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::tests::assert_near;
+
+    #[test]
+    fn sphere_hits_box() {
+        let sphere = Shape::sphere(0.5);
+        let wall = Shape::cuboid(Vec3::new(0.05, 2.0, 2.0));
+        let start = Isometry::from_position(Vec3::new(-10.0, 0.0, 0.0));
+
+        let toi = time_of_impact(
+            &Posed::new(&sphere, &start), &Motion::new(Vec3::X * 100.0, Vec3::ZERO),
+            &Posed::new(&wall, &Isometry::IDENTITY), &Motion::default(),
+            1.0, 1e-3,
+        ).unwrap();
+
+        // Surfaces meet after 10 - 0.5 - 0.05 = 9.45 m;
+        assert!((toi * 100.0 - 9.45).abs() < 2e-3, "{toi}");
+
+        let miss = time_of_impact(
+            &Posed::new(&sphere, &start), &Motion::new(Vec3::NEG_X * 100.0, Vec3::ZERO),
+            &Posed::new(&wall, &Isometry::IDENTITY), &Motion::default(),
+            1.0, 1e-3,
+        );
+        assert!(miss.is_none());
+    }
+
+    #[test]
+    fn box_spins_into_plane() {
+        let plate = Shape::cuboid(Vec3::new(1.0, 0.1, 1.0));
+        let ground = Shape::plane(Vec3::Y, 0.0);
+        let start = Isometry::from_position(Vec3::new(0.0, 0.5, 0.0));
+
+        // Quarter turn about Z within a second brings the 1.0 half extent down;
+        let spin = Motion::new(Vec3::ZERO, Vec3::Z * std::f32::consts::FRAC_PI_2);
+        let toi = time_of_impact(
+            &Posed::new(&plate, &start), &spin,
+            &Posed::new(&ground, &Isometry::IDENTITY), &Motion::default(),
+            1.0, 1e-3,
+        ).unwrap();
+
+        let pose = spin.at(&start, toi);
+        let lowest = pose.transform_point(plate.support(pose.inverse_transform_vector(Vec3::NEG_Y)));
+        assert_near(lowest.y.max(0.0), 0.0);
+        assert!(toi > 0.0 && toi < 1.0);
+    }
+}
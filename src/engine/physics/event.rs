@@ -0,0 +1,131 @@
+use glam::Vec3;
+
+use super::body::BodyHandle;
+use super::collision::ContactPoint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionEventKind {
+    /// First step the bodies touch;
+    Started,
+    /// Every following step they keep touching while awake;
+    Persisted,
+    /// First step they no longer touch, points are empty;
+    Ended,
+}
+
+/// Touch change between two bodies, reported after each step;
+#[derive(Debug, Clone)]
+pub struct CollisionEvent {
+    pub kind: CollisionEventKind,
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    /// Points from A to B;
+    pub normal: Vec3,
+    pub points: Vec<ContactPoint>,
+    /// Total normal impulse applied by the step;
+    pub impulse: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEventKind {
+    Enter,
+    Exit,
+}
+
+/// Body entering or leaving a sensor, reported after each step;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub kind: TriggerEventKind,
+    pub sensor: BodyHandle,
+    pub body: BodyHandle,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Collider, RigidBody, Shape, World, WorldBuilder};
+    use super::*;
+
+    const DT: f32 = 1.0 / 120.0;
+
+    #[test]
+    fn touches_start_persist_and_end_in_order() {
+        let mut world = WorldBuilder::new().with_sleeping(false).build();
+        let ground = world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let ball = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::Y * 2.0)
+                .with_collider(Collider::new(Shape::sphere(0.5))),
+        );
+
+        let mut kinds = Vec::new();
+        let mut landing = 0.0f32;
+        for _ in 0..120 {
+            world.step(DT);
+            for event in world.drain_collision_events() {
+                assert_eq!((event.body_a, event.body_b), (ground, ball));
+                if event.kind == CollisionEventKind::Started {
+                    assert!(!event.points.is_empty());
+                    assert!(event.normal.abs_diff_eq(Vec3::Y, 1e-3), "{:?}", event.normal);
+                    landing = event.impulse;
+                }
+                kinds.push(event.kind);
+            }
+        }
+
+        // Falling 1.5 m, the landing stops about m sqrt(2 g h);
+        assert!(landing > 4.0, "{landing}");
+        assert_eq!(kinds[0], CollisionEventKind::Started);
+        assert!(kinds.len() > 30 && kinds[1..].iter().all(|kind| *kind == CollisionEventKind::Persisted));
+
+        world.body_mut(ball).unwrap().set_position(Vec3::Y * 5.0);
+        world.step(DT);
+        let ended: Vec<CollisionEvent> = world.drain_collision_events().collect();
+        assert_eq!(ended.len(), 1);
+        assert_eq!(ended[0].kind, CollisionEventKind::Ended);
+        assert!(ended[0].points.is_empty());
+
+        world.step(DT);
+        assert_eq!(world.drain_collision_events().count(), 0);
+    }
+
+    #[test]
+    fn sensor_reports_enter_and_exit_without_pushing() {
+        let mut world = World::new();
+        let zone = world.add_body(
+            RigidBody::fixed()
+                .with_position(Vec3::Y * 5.0)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(2.0, 1.0, 2.0))).with_sensor(true)),
+        );
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let ball = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::Y * 8.0)
+                .with_collider(Collider::new(Shape::sphere(0.5))),
+        );
+
+        let mut log = Vec::new();
+        let mut seen_inside = false;
+        for _ in 0..240 {
+            world.step(DT);
+            log.extend(world.drain_trigger_events());
+            assert!(world.drain_collision_events().all(|event| event.body_a != zone && event.body_b != zone));
+            seen_inside |= world.sensed_bodies(zone).any(|body| body == ball);
+
+            // Free fall through the zone, as if it wasn't there;
+            let body = world.body(ball).unwrap();
+            if body.position().y > 1.0 {
+                assert!(body.linear_velocity().x.abs() < 1e-6 && body.linear_velocity().y < 0.0);
+            }
+        }
+
+        assert!(seen_inside);
+        assert_eq!(log, vec![
+            TriggerEvent { kind: TriggerEventKind::Enter, sensor: zone, body: ball },
+            TriggerEvent { kind: TriggerEventKind::Exit, sensor: zone, body: ball },
+        ]);
+
+        // Only the ground holds it, sensors make no contacts or collision events;
+        assert!((world.body(ball).unwrap().position().y - 0.5).abs() < 0.05);
+        assert_eq!(world.contacts().count(), 1);
+    }
+}
@@ -0,0 +1,554 @@
+use glam::Vec3;
+
+use super::body::{BodyHandle, RigidBody};
+use super::slot::{SlotHandle, Slots};
+use super::World;
+
+/// Force generator slot inside the physics world && its generation;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ForceHandle(pub(super) usize, pub(super) u32);
+
+impl ForceHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+}
+
+impl SlotHandle for ForceHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+/// Source of forces the world evaluates before integrating each step;
+/// Forces applied here last for the coming step only;
+/// Handlers register their own through `context.physics_mut().add_force_generator(..)`;
+pub trait ForceGenerator {
+    fn apply(&mut self, bodies: &mut ForceBodies, dt: f32);
+
+    /// Finished generators are removed by the world, like spent explosions;
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+/// Bodies of the world as seen by force generators;
+pub struct ForceBodies<'a> {
+    bodies: &'a mut Slots<BodyHandle, RigidBody>,
+}
+
+impl<'a> ForceBodies<'a> {
+    pub(super) fn new(bodies: &'a mut Slots<BodyHandle, RigidBody>) -> Self {
+        Self { bodies }
+    }
+
+    pub fn get(&self, handle: BodyHandle) -> Option<&RigidBody> {
+        self.bodies.get(handle)
+    }
+
+    /// Any body, forces applied through its public methods wake it;
+    pub fn get_mut(&mut self, handle: BodyHandle) -> Option<&mut RigidBody> {
+        self.bodies.get_mut(handle)
+    }
+
+    /// Moving dynamic bodies, continuous forces skip sleeping ones so they can stay asleep;
+    pub fn awake_mut(&mut self) -> impl Iterator<Item=(BodyHandle, &mut RigidBody)> {
+        self.dynamic_mut().filter(|(_, body)| !body.is_sleeping())
+    }
+
+    /// Every dynamic body, sleeping or not;
+    pub fn dynamic_mut(&mut self) -> impl Iterator<Item=(BodyHandle, &mut RigidBody)> {
+        self.bodies.iter_mut()
+            .filter(|(_, body)| body.is_dynamic())
+    }
+}
+
+/// Optional set of bodies a generator is limited to;
+fn affects(bodies: &Option<Vec<BodyHandle>>, handle: BodyHandle) -> bool {
+    bodies.as_ref().is_none_or(|bodies| bodies.contains(&handle))
+}
+
+/// Constant acceleration, on top of the world's gravity;
+#[derive(Debug, Clone)]
+pub struct UniformGravity {
+    acceleration: Vec3,
+    bodies: Option<Vec<BodyHandle>>,
+}
+
+impl UniformGravity {
+    pub fn new(acceleration: Vec3) -> Self {
+        Self { acceleration, bodies: None }
+    }
+
+    /// Only pull these bodies;
+    pub fn with_bodies(mut self, bodies: Vec<BodyHandle>) -> Self {
+        self.bodies = Some(bodies);
+        self
+    }
+}
+
+impl ForceGenerator for UniformGravity {
+    fn apply(&mut self, bodies: &mut ForceBodies, _dt: f32) {
+        for (handle, body) in bodies.awake_mut() {
+            if affects(&self.bodies, handle) {
+                let force = self.acceleration * body.mass();
+                body.add_force(force);
+            }
+        }
+    }
+}
+
+/// Air resistance: linear && quadratic in speed, plus angular damping;
+#[derive(Debug, Clone)]
+pub struct Drag {
+    linear: f32,
+    quadratic: f32,
+    angular: f32,
+    bodies: Option<Vec<BodyHandle>>,
+}
+
+impl Drag {
+    /// Force `-(linear * |v| + quadratic * |v|²) * v / |v|`;
+    pub fn new(linear: f32, quadratic: f32) -> Self {
+        Self { linear: linear.max(0.0), quadratic: quadratic.max(0.0), angular: 0.0, bodies: None }
+    }
+
+    /// Torque `-angular * ω`;
+    pub fn with_angular(mut self, angular: f32) -> Self {
+        self.angular = angular.max(0.0);
+        self
+    }
+
+    pub fn with_bodies(mut self, bodies: Vec<BodyHandle>) -> Self {
+        self.bodies = Some(bodies);
+        self
+    }
+}
+
+impl ForceGenerator for Drag {
+    fn apply(&mut self, bodies: &mut ForceBodies, dt: f32) {
+        for (handle, body) in bodies.awake_mut() {
+            if !affects(&self.bodies, handle) {
+                continue;
+            }
+
+            // Never more than stops the body within the step;
+            let velocity = body.linear_velocity();
+            let speed = velocity.length();
+            let drag = (self.linear + self.quadratic * speed).min(body.mass() / dt);
+            body.add_force(-velocity * drag);
+
+            if self.angular > 0.0 {
+                let torque = -body.angular_velocity() * self.angular;
+                body.add_torque(torque);
+            }
+        }
+    }
+}
+
+/// Inverse square pull toward a point, negative strength pushes away;
+#[derive(Debug, Clone)]
+pub struct PointAttractor {
+    position: Vec3,
+    /// Acceleration at one meter;
+    strength: f32,
+    range: f32,
+    /// Distance below which the pull stops growing;
+    softening: f32,
+    bodies: Option<Vec<BodyHandle>>,
+}
+
+impl PointAttractor {
+    pub fn new(position: Vec3, strength: f32) -> Self {
+        Self { position, strength, range: f32::INFINITY, softening: 0.1, bodies: None }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range.max(0.0);
+        self
+    }
+
+    pub fn with_softening(mut self, softening: f32) -> Self {
+        self.softening = softening.max(f32::EPSILON);
+        self
+    }
+
+    pub fn with_bodies(mut self, bodies: Vec<BodyHandle>) -> Self {
+        self.bodies = Some(bodies);
+        self
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+}
+
+impl ForceGenerator for PointAttractor {
+    fn apply(&mut self, bodies: &mut ForceBodies, _dt: f32) {
+        for (handle, body) in bodies.awake_mut() {
+            let offset = self.position - body.center_of_mass();
+            let distance = offset.length();
+            if distance > self.range || distance <= f32::EPSILON || !affects(&self.bodies, handle) {
+                continue;
+            }
+
+            let acceleration = self.strength / distance.max(self.softening).powi(2);
+            let force = offset / distance * acceleration * body.mass();
+            body.add_force(force);
+        }
+    }
+}
+
+/// Drag toward a wind velocity that varies with gusts;
+#[derive(Debug, Clone)]
+pub struct Wind {
+    velocity: Vec3,
+    /// Force per m/s of relative speed;
+    coefficient: f32,
+    /// Relative speed change of gusts, zero for steady wind;
+    gust_strength: f32,
+    gust_frequency: f32,
+    time: f32,
+    bodies: Option<Vec<BodyHandle>>,
+}
+
+impl Wind {
+    pub fn new(velocity: Vec3, coefficient: f32) -> Self {
+        Self { velocity, coefficient: coefficient.max(0.0), gust_strength: 0.0, gust_frequency: 0.5, time: 0.0, bodies: None }
+    }
+
+    /// Gusts scale the wind speed by up to `1 ± strength`, about `frequency` times per second;
+    pub fn with_gusts(mut self, strength: f32, frequency: f32) -> Self {
+        self.gust_strength = strength.max(0.0);
+        self.gust_frequency = frequency.max(0.0);
+        self
+    }
+
+    pub fn with_bodies(mut self, bodies: Vec<BodyHandle>) -> Self {
+        self.bodies = Some(bodies);
+        self
+    }
+
+    /// Wind velocity at the current time;
+    pub fn velocity(&self) -> Vec3 {
+        // Two incommensurate waves, never quite repeating;
+        let phase = std::f32::consts::TAU * self.gust_frequency * self.time;
+        let gust = 0.7 * phase.sin() + 0.3 * (2.37 * phase + 1.3).sin();
+        self.velocity * (1.0 + self.gust_strength * gust)
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vec3) {
+        self.velocity = velocity;
+    }
+}
+
+impl ForceGenerator for Wind {
+    fn apply(&mut self, bodies: &mut ForceBodies, dt: f32) {
+        let wind = self.velocity();
+        self.time += dt;
+
+        for (handle, body) in bodies.awake_mut() {
+            if affects(&self.bodies, handle) {
+                let coefficient = self.coefficient.min(body.mass() / dt);
+                let force = (wind - body.linear_velocity()) * coefficient;
+                body.add_force(force);
+            }
+        }
+    }
+}
+
+/// Damped spring between anchors of two bodies, or a body && a world point;
+#[derive(Debug, Clone)]
+pub struct Spring {
+    body_a: BodyHandle,
+    body_b: Option<BodyHandle>,
+    /// Local to body A;
+    anchor_a: Vec3,
+    /// Local to body B, world space without one;
+    anchor_b: Vec3,
+    rest_length: f32,
+    stiffness: f32,
+    damping: f32,
+}
+
+impl Spring {
+    /// Anchors are given in world space at the bodies' current poses, like joints;
+    /// The rest length is the current distance;
+    pub fn new(world: &World, body_a: BodyHandle, body_b: Option<BodyHandle>, anchor_a: Vec3, anchor_b: Vec3) -> Self {
+        let local = |handle: Option<BodyHandle>, point: Vec3| {
+            match handle.and_then(|h| world.body(h)) {
+                Some(body) => body.isometry().inverse_transform_point(point),
+                None => point
+            }
+        };
+
+        Self {
+            body_a,
+            body_b,
+            anchor_a: local(Some(body_a), anchor_a),
+            anchor_b: local(body_b, anchor_b),
+            rest_length: anchor_a.distance(anchor_b),
+            stiffness: 100.0,
+            damping: 1.0,
+        }
+    }
+
+    /// Anchors in each body's local space, world space for a missing body B;
+    pub fn local(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor_a: Vec3, anchor_b: Vec3, rest_length: f32) -> Self {
+        Self { body_a, body_b, anchor_a, anchor_b, rest_length, stiffness: 100.0, damping: 1.0 }
+    }
+
+    pub fn with_rest_length(mut self, rest_length: f32) -> Self {
+        self.rest_length = rest_length.max(0.0);
+        self
+    }
+
+    /// Newtons per meter of stretch;
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness.max(0.0);
+        self
+    }
+
+    /// Newtons per m/s of stretching speed;
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.max(0.0);
+        self
+    }
+
+    /// World space anchors && their velocities;
+    fn ends(&self, bodies: &ForceBodies) -> Option<((Vec3, Vec3), (Vec3, Vec3))> {
+        let body_a = bodies.get(self.body_a)?;
+        let a = body_a.isometry().transform_point(self.anchor_a);
+
+        let b = match self.body_b {
+            Some(handle) => {
+                let body_b = bodies.get(handle)?;
+                let b = body_b.isometry().transform_point(self.anchor_b);
+                (b, body_b.velocity_at(b))
+            }
+            None => (self.anchor_b, Vec3::ZERO)
+        };
+
+        Some(((a, body_a.velocity_at(a)), b))
+    }
+}
+
+impl ForceGenerator for Spring {
+    fn apply(&mut self, bodies: &mut ForceBodies, _dt: f32) {
+        let Some(((a, va), (b, vb))) = self.ends(bodies) else { return };
+
+        let delta = b - a;
+        let length = delta.length();
+        if length <= f32::EPSILON {
+            return;
+        }
+
+        let dir = delta / length;
+        let stretch = length - self.rest_length;
+        let force = dir * (self.stiffness * stretch + self.damping * (vb - va).dot(dir));
+
+        // Like other continuous forces, sleeping ends are left asleep;
+        for (handle, point, force) in [(Some(self.body_a), a, force), (self.body_b, b, -force)] {
+            if let Some(body) = handle.and_then(|h| bodies.get_mut(h)) {
+                if body.is_active() {
+                    body.add_force_at(force, point);
+                }
+            }
+        }
+    }
+}
+
+/// How an explosion weakens with distance;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Falloff {
+    Constant,
+    /// Full at the center, none at the radius;
+    Linear,
+    /// Linear falloff squared, sharper near the edge;
+    Quadratic,
+}
+
+/// One shot radial impulse, removed after its step;
+#[derive(Debug, Clone)]
+pub struct Explosion {
+    center: Vec3,
+    radius: f32,
+    /// Impulse at the center, in newton seconds;
+    impulse: f32,
+    falloff: Falloff,
+    done: bool,
+}
+
+impl Explosion {
+    pub fn new(center: Vec3, radius: f32, impulse: f32) -> Self {
+        Self { center, radius: radius.max(f32::EPSILON), impulse, falloff: Falloff::Linear, done: false }
+    }
+
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Impulse at a distance from the center;
+    pub fn impulse_at(&self, distance: f32) -> f32 {
+        let t = (1.0 - distance / self.radius).clamp(0.0, 1.0);
+        match self.falloff {
+            Falloff::Constant if distance <= self.radius => self.impulse,
+            Falloff::Constant => 0.0,
+            Falloff::Linear => self.impulse * t,
+            Falloff::Quadratic => self.impulse * t * t,
+        }
+    }
+}
+
+impl ForceGenerator for Explosion {
+    fn apply(&mut self, bodies: &mut ForceBodies, _dt: f32) {
+        self.done = true;
+
+        // Sleeping bodies are blown too;
+        for (_, body) in bodies.dynamic_mut() {
+            let offset = body.center_of_mass() - self.center;
+            let distance = offset.length();
+            let impulse = self.impulse_at(distance);
+            if impulse == 0.0 {
+                continue;
+            }
+
+            let dir = match distance > f32::EPSILON {
+                true => offset / distance,
+                false => Vec3::Y
+            };
+            body.apply_impulse(dir * impulse);
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Collider, Shape, WorldBuilder};
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn floating_ball(world: &mut World, position: Vec3) -> BodyHandle {
+        world.add_body(
+            RigidBody::new()
+                .with_position(position)
+                .with_collider(Collider::new(Shape::sphere(0.5)))
+                .with_mass(2.0),
+        )
+    }
+
+    #[test]
+    fn drag_reaches_terminal_speed() {
+        let mut world = WorldBuilder::new().build();
+        let ball = floating_ball(&mut world, Vec3::ZERO);
+        world.add_force_generator(Drag::new(0.0, 0.5));
+
+        for _ in 0..600 {
+            world.step(DT);
+        }
+
+        // m g = k v²;
+        let terminal = (2.0 * 9.81 / 0.5f32).sqrt();
+        let speed = world.body(ball).unwrap().linear_velocity().length();
+        assert!((speed - terminal).abs() < 0.05, "{speed} vs {terminal}");
+    }
+
+    #[test]
+    fn spring_settles_at_stretched_length() {
+        let mut world = WorldBuilder::new().build();
+        let ball = floating_ball(&mut world, Vec3::new(0.0, -1.0, 0.0));
+
+        let spring = Spring::local(ball, None, Vec3::ZERO, Vec3::ZERO, 1.0)
+            .with_stiffness(200.0)
+            .with_damping(10.0);
+        world.add_force_generator(spring);
+
+        for _ in 0..600 {
+            world.step(DT);
+        }
+
+        // Stretched by m g / k below the rest length;
+        let y = world.body(ball).unwrap().position().y;
+        let expected = -1.0 - 2.0 * 9.81 / 200.0;
+        assert!((y - expected).abs() < 0.01, "{y} vs {expected}");
+    }
+
+    #[test]
+    fn attractor_pulls_and_wind_pushes() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let pulled = floating_ball(&mut world, Vec3::new(5.0, 0.0, 0.0));
+        let blown = floating_ball(&mut world, Vec3::new(0.0, 50.0, 0.0));
+
+        world.add_force_generator(PointAttractor::new(Vec3::ZERO, 25.0).with_bodies(vec![pulled]));
+        world.add_force_generator(Wind::new(Vec3::Z * 4.0, 1.0).with_gusts(0.5, 1.0).with_bodies(vec![blown]));
+
+        for _ in 0..120 {
+            world.step(DT);
+        }
+
+        assert!(world.body(pulled).unwrap().position().x < 4.0);
+
+        let velocity = world.body(blown).unwrap().linear_velocity();
+        assert!(velocity.z > 1.0 && velocity.z < 6.0, "{velocity:?}");
+        assert!(velocity.x.abs() < 1e-4 && velocity.y.abs() < 1e-4);
+    }
+
+    #[test]
+    fn continuous_forces_let_a_resting_stack_sleep() {
+        let mut world = WorldBuilder::new().build();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        for i in 0..3 {
+            world.add_body(
+                RigidBody::new()
+                    .with_position(Vec3::new(0.0, 0.5 + i as f32, 0.0))
+                    .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.5))))
+                    .with_density(1.0),
+            );
+        }
+
+        world.add_force_generator(UniformGravity::new(Vec3::NEG_Y * 2.0));
+        world.add_force_generator(Drag::new(0.01, 0.0).with_angular(0.01));
+
+        for _ in 0..600 {
+            world.step(DT);
+        }
+
+        assert_eq!(world.sleeping_count(), 3);
+    }
+
+    #[test]
+    fn explosion_fires_once_with_falloff() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let near = floating_ball(&mut world, Vec3::new(1.0, 0.0, 0.0));
+        let far = floating_ball(&mut world, Vec3::new(0.0, 0.0, -3.0));
+        let outside = floating_ball(&mut world, Vec3::new(0.0, 6.0, 0.0));
+
+        world.add_force_generator(Explosion::new(Vec3::ZERO, 4.0, 8.0));
+        world.step(DT);
+        assert_eq!(world.force_generator_count(), 0);
+
+        // Impulse over mass, 8 * (1 - d / 4) / 2;
+        let velocity = |handle| world.body(handle).unwrap().linear_velocity();
+        assert!((velocity(near) - Vec3::X * 3.0).length() < 1e-4, "{:?}", velocity(near));
+        assert!((velocity(far) - Vec3::Z * -1.0).length() < 1e-4, "{:?}", velocity(far));
+        assert_eq!(velocity(outside), Vec3::ZERO);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+/// When resting bodies are put to sleep;
+#[derive(Debug, Clone, Copy)]
+pub struct SleepConfig {
+    pub(super) enabled: bool,
+    pub(super) linear_threshold: f32,
+    pub(super) angular_threshold: f32,
+    /// Seconds a whole island must stay below both thresholds;
+    pub(super) time: f32,
+}
+
+impl Default for SleepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            linear_threshold: 0.05,
+            angular_threshold: 0.05,
+            time: 0.5,
+        }
+    }
+}
+
+impl SleepConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn linear_threshold(&self) -> f32 {
+        self.linear_threshold
+    }
+
+    pub fn angular_threshold(&self) -> f32 {
+        self.angular_threshold
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+}
+
+/// Disjoint sets over body slots, joined by contacts && joints;
+pub(super) struct Islands {
+    parent: Vec<usize>,
+}
+
+impl Islands {
+    pub(super) fn new(count: usize) -> Self {
+        Self { parent: (0..count).collect() }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]];
+            index = self.parent[index];
+        }
+
+        index
+    }
+
+    pub(super) fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a != root_b {
+            self.parent[root_a.max(root_b)] = root_a.min(root_b);
+        }
+    }
+
+    /// Members grouped by island, ordered by lowest member;
+    pub(super) fn groups(&mut self, members: impl IntoIterator<Item=usize>) -> Vec<Vec<usize>> {
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for member in members {
+            let root = self.find(member);
+            groups.entry(root).or_default().push(member);
+        }
+
+        groups.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::super::{BodyHandle, Collider, RigidBody, Shape, World};
+
+    const DT: f32 = 1.0 / 120.0;
+
+    fn unit_box(world: &mut World, position: Vec3) -> BodyHandle {
+        world.add_body(
+            RigidBody::new()
+                .with_position(position)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.5))))
+                .with_density(1.0),
+        )
+    }
+
+    /// Four boxes stacked on the ground && one resting on its own;
+    fn stack_and_loner() -> (World, Vec<BodyHandle>, BodyHandle) {
+        let mut world = World::new();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let stack = (0..4).map(|i| unit_box(&mut world, Vec3::new(0.0, 0.5 + i as f32, 0.0))).collect();
+        let loner = unit_box(&mut world, Vec3::new(10.0, 0.5, 0.0));
+
+        for _ in 0..360 {
+            world.step(DT);
+        }
+
+        (world, stack, loner)
+    }
+
+    #[test]
+    fn resting_stack_falls_asleep_and_stays_put() {
+        let (mut world, stack, _) = stack_and_loner();
+        assert_eq!(world.sleeping_count(), 5);
+
+        let before: Vec<Vec3> = stack.iter().map(|h| world.body(*h).unwrap().position()).collect();
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        for (handle, position) in stack.iter().zip(&before) {
+            assert_eq!(world.body(*handle).unwrap().position(), *position);
+        }
+    }
+
+    #[test]
+    fn impulse_wakes_the_whole_island_only() {
+        let (mut world, stack, loner) = stack_and_loner();
+
+        world.body_mut(stack[0]).unwrap().apply_impulse(Vec3::X * 0.01);
+        world.step(DT);
+        for handle in &stack {
+            assert!(!world.body(*handle).unwrap().is_sleeping());
+        }
+        assert!(world.body(loner).unwrap().is_sleeping());
+
+        for _ in 0..360 {
+            world.step(DT);
+        }
+        assert_eq!(world.sleeping_count(), 5);
+    }
+
+    #[test]
+    fn falling_body_and_removal_wake_sleepers() {
+        let (mut world, stack, _) = stack_and_loner();
+
+        let ball = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::new(0.0, 6.0, 0.0))
+                .with_collider(Collider::new(Shape::sphere(0.3))),
+        );
+        let mut woke = false;
+        for _ in 0..120 {
+            world.step(DT);
+            woke |= !world.body(stack[3]).unwrap().is_sleeping();
+        }
+        assert!(woke);
+        assert!(world.body(ball).unwrap().position().y > 4.0);
+
+        for _ in 0..600 {
+            world.step(DT);
+        }
+        assert!(world.body(stack[1]).unwrap().is_sleeping());
+
+        world.remove_body(stack[0]);
+        world.step(DT);
+        assert!(!world.body(stack[1]).unwrap().is_sleeping());
+    }
+}
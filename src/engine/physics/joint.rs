@@ -0,0 +1,297 @@
+use glam::{Quat, Vec3};
+
+use super::body::BodyHandle;
+use super::shape::Isometry;
+use super::slot::SlotHandle;
+
+/// Joint slot inside the physics world && its generation;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JointHandle(pub(super) usize, pub(super) u32);
+
+impl JointHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+}
+
+impl SlotHandle for JointHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointKind {
+    /// Shared anchor point, free rotation;
+    Ball,
+    /// Shared anchor point, rotation about the frame X axis only;
+    Hinge,
+    /// Fixed relative rotation, translation along the frame X axis only;
+    Prismatic,
+    /// No relative motion;
+    Fixed,
+    /// Anchors kept within a distance range;
+    Distance,
+}
+
+/// Velocity motor driving the free axis of hinges && prismatic joints;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motor {
+    /// Target angular (rad/s) or linear (m/s) speed;
+    pub velocity: f32,
+    /// Max torque or force the motor may apply;
+    pub max_force: f32,
+}
+
+/// Constraint between two bodies, or a body && the world;
+/// Frames are given in world space && stored in body space once added to the world;
+/// The joint axis is the frame X axis, the frame Y axis is the zero angle reference for hinges;
+/// Limits && motors measure body A relative to body B (or the world);
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub(super) kind: JointKind,
+    pub(super) body_a: BodyHandle,
+    /// None attaches to the world;
+    pub(super) body_b: Option<BodyHandle>,
+    pub(super) frame_a: Isometry,
+    pub(super) frame_b: Isometry,
+
+    /// Angle (hinge), translation (prismatic) or length (distance) range;
+    pub(super) limits: Option<(f32, f32)>,
+    pub(super) motor: Option<Motor>,
+    /// Let the connected bodies collide with each other;
+    pub(super) collisions: bool,
+
+    /// Accumulated impulse per constraint row, kept for warm starting;
+    pub(super) impulses: [f32; Joint::MAX_ROWS],
+}
+
+impl Joint {
+    pub(super) const MAX_ROWS: usize = 8;
+
+    fn new(kind: JointKind, body_a: BodyHandle, body_b: Option<BodyHandle>, frame_a: Isometry, frame_b: Isometry) -> Self {
+        Self {
+            kind,
+            body_a,
+            body_b,
+            frame_a,
+            frame_b,
+
+            limits: None,
+            motor: None,
+            collisions: false,
+
+            impulses: [0.0; Joint::MAX_ROWS],
+        }
+    }
+
+    /// Frame at a world anchor with its X axis along the world axis;
+    fn frame(anchor: Vec3, axis: Vec3) -> Isometry {
+        Isometry::new(anchor, Quat::from_rotation_arc(Vec3::X, axis.normalize()))
+    }
+
+    pub fn ball(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec3) -> Self {
+        let frame = Isometry::from_position(anchor);
+        Self::new(JointKind::Ball, body_a, body_b, frame, frame)
+    }
+
+    /// Door like joint rotating about the world axis through the anchor;
+    pub fn hinge(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec3, axis: Vec3) -> Self {
+        let frame = Self::frame(anchor, axis);
+        Self::new(JointKind::Hinge, body_a, body_b, frame, frame)
+    }
+
+    /// Piston like joint sliding along the world axis;
+    pub fn prismatic(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec3, axis: Vec3) -> Self {
+        let frame = Self::frame(anchor, axis);
+        Self::new(JointKind::Prismatic, body_a, body_b, frame, frame)
+    }
+
+    pub fn fixed(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec3) -> Self {
+        let frame = Isometry::from_position(anchor);
+        Self::new(JointKind::Fixed, body_a, body_b, frame, frame)
+    }
+
+    /// Rod between two world anchors, keeps their current distance unless limits are set;
+    pub fn distance(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor_a: Vec3, anchor_b: Vec3) -> Self {
+        let length = anchor_a.distance(anchor_b);
+        Self::new(
+            JointKind::Distance, body_a, body_b,
+            Isometry::from_position(anchor_a), Isometry::from_position(anchor_b),
+        ).with_limits(length, length)
+    }
+
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some((min.min(max), max.max(min)));
+        self
+    }
+
+    pub fn with_motor(mut self, velocity: f32, max_force: f32) -> Self {
+        self.motor = Some(Motor { velocity, max_force: max_force.max(0.0) });
+        self
+    }
+
+    pub fn with_collisions(mut self, collisions: bool) -> Self {
+        self.collisions = collisions;
+        self
+    }
+
+    pub fn kind(&self) -> JointKind {
+        self.kind
+    }
+
+    pub fn bodies(&self) -> (BodyHandle, Option<BodyHandle>) {
+        (self.body_a, self.body_b)
+    }
+
+    pub fn limits(&self) -> Option<(f32, f32)> {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Option<(f32, f32)>) {
+        self.limits = limits;
+    }
+
+    pub fn motor(&self) -> Option<Motor> {
+        self.motor
+    }
+
+    pub fn set_motor(&mut self, motor: Option<Motor>) {
+        self.motor = motor;
+    }
+
+    pub fn collisions(&self) -> bool {
+        self.collisions
+    }
+
+    /// Connects the body to something;
+    pub(super) fn involves(&self, body: BodyHandle) -> bool {
+        self.body_a == body || self.body_b == Some(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{RigidBody, World, WorldBuilder};
+    use super::*;
+
+    const DT: f32 = 1.0 / 120.0;
+
+    fn run(world: &mut World, steps: usize) {
+        for _ in 0..steps {
+            world.step(DT);
+        }
+    }
+
+    #[test]
+    fn pendulum_keeps_its_length() {
+        let mut world = World::new();
+        let bob = world.add_body(RigidBody::new().with_position(Vec3::X * 2.0));
+        world.add_joint(Joint::ball(bob, None, Vec3::ZERO));
+
+        let mut lowest = 0.0f32;
+        for _ in 0..240 {
+            world.step(DT);
+            let position = world.body(bob).unwrap().position();
+            lowest = lowest.min(position.y);
+            assert!((position.length() - 2.0).abs() < 0.05, "{position:?}");
+        }
+
+        assert!(lowest < -1.9, "{lowest}");
+    }
+
+    #[test]
+    fn hinge_motor_spins_and_limits_clamp() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let wheel = world.add_body(RigidBody::new().with_position(Vec3::Y));
+        world.add_joint(Joint::hinge(wheel, None, Vec3::Y, Vec3::Z).with_motor(3.0, 100.0));
+        run(&mut world, 120);
+
+        let body = world.body(wheel).unwrap();
+        assert!((body.angular_velocity() - Vec3::Z * 3.0).length() < 0.05, "{:?}", body.angular_velocity());
+        assert!((body.position() - Vec3::Y).length() < 0.01);
+
+        // A door swinging down under gravity stops at its lower limit;
+        let mut world = World::new();
+        let door = world.add_body(RigidBody::new().with_position(Vec3::X));
+        world.add_joint(Joint::hinge(door, None, Vec3::ZERO, Vec3::Z).with_limits(-0.5, 0.5));
+        run(&mut world, 240);
+
+        let position = world.body(door).unwrap().position();
+        assert!((position.y.atan2(position.x) + 0.5).abs() < 0.05, "{position:?}");
+    }
+
+    #[test]
+    fn prismatic_motor_stops_at_its_limit() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let piston = world.add_body(RigidBody::new().with_angular_velocity(Vec3::X));
+        world.add_joint(Joint::prismatic(piston, None, Vec3::ZERO, Vec3::Y).with_limits(-1.0, 1.0).with_motor(2.0, 50.0));
+        run(&mut world, 240);
+
+        let body = world.body(piston).unwrap();
+        assert!((body.position() - Vec3::Y).length() < 0.02, "{:?}", body.position());
+        assert!(body.linear_velocity().length() < 0.05, "{:?}", body.linear_velocity());
+        assert!(body.orientation().angle_between(Quat::IDENTITY) < 0.05);
+    }
+
+    #[test]
+    fn fixed_joint_carries_a_welded_body() {
+        let mut world = World::new();
+        let arm = world.add_body(RigidBody::new().with_position(Vec3::X));
+        let tip = world.add_body(RigidBody::new().with_position(Vec3::X * 2.0));
+        world.add_joint(Joint::ball(arm, None, Vec3::ZERO));
+        world.add_joint(Joint::fixed(arm, Some(tip), Vec3::X * 1.5));
+
+        let mut lowest = 0.0f32;
+        for _ in 0..240 {
+            world.step(DT);
+            let (a, b) = (world.body(arm).unwrap().position(), world.body(tip).unwrap().position());
+            lowest = lowest.min(b.y);
+            assert!((a.length() - 1.0).abs() < 0.05 && (b.length() - 2.0).abs() < 0.1, "{a:?} {b:?}");
+        }
+
+        assert!(lowest < -1.5, "{lowest}");
+    }
+
+    #[test]
+    fn distance_range_goes_slack_until_taut() {
+        let mut world = World::new();
+        let bob = world.add_body(RigidBody::new().with_position(Vec3::Y * -0.5));
+        world.add_joint(Joint::distance(bob, None, Vec3::Y * -0.5, Vec3::ZERO).with_limits(0.0, 2.0));
+
+        // Falls freely for the first 1.5 m;
+        run(&mut world, 30);
+        let y = world.body(bob).unwrap().position().y;
+        assert!((y + 0.5 + 0.5 * 9.81 * 0.25 * 0.25).abs() < 0.02, "{y}");
+
+        run(&mut world, 210);
+        let position = world.body(bob).unwrap().position();
+        assert!((position.y + 2.0).abs() < 0.05, "{position:?}");
+    }
+
+    #[test]
+    fn joints_on_stale_or_foreign_bodies_are_rejected() {
+        let mut world = World::new();
+        let old = world.add_body(RigidBody::new());
+        world.remove_body(old);
+        let new = world.add_body(RigidBody::new().with_position(Vec3::X * 5.0));
+        assert!(world.add_joint(Joint::ball(old, None, Vec3::ZERO)).is_none());
+        assert!(world.add_joint(Joint::ball(new, Some(old), Vec3::ZERO)).is_none());
+
+        let mut other = World::new();
+        let foreign = (0..3).map(|_| other.add_body(RigidBody::new())).last().unwrap();
+        assert!(world.add_joint(Joint::ball(foreign, None, Vec3::ZERO)).is_none());
+
+        run(&mut world, 60);
+        assert_eq!(world.joints().count(), 0);
+        assert_eq!(world.body(new).unwrap().position().x, 5.0);
+    }
+}
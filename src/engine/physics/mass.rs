@@ -0,0 +1,339 @@
+use std::f32::consts::PI;
+use std::iter::Sum;
+use std::ops::Add;
+
+use glam::{Mat3, Vec3};
+
+use super::collision::hull_triangles;
+use super::shape::{ConvexHull, Isometry, Shape};
+
+/// Mass, center of mass && inertia tensor about that center, in shape local space;
+/// Bodies turn about `center`, their position stays the origin of the shapes;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub mass: f32,
+    pub center: Vec3,
+    pub inertia: Mat3,
+}
+
+impl Default for MassProperties {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl MassProperties {
+    pub const ZERO: Self = Self { mass: 0.0, center: Vec3::ZERO, inertia: Mat3::ZERO };
+
+    pub fn new(mass: f32, center: Vec3, inertia: Mat3) -> Self {
+        Self { mass, center, inertia }
+    }
+
+    pub fn sphere(radius: f32, density: f32) -> Self {
+        let mass = density * 4.0 / 3.0 * PI * radius.powi(3);
+        Self::new(mass, Vec3::ZERO, Mat3::from_diagonal(Vec3::splat(0.4 * mass * radius * radius)))
+    }
+
+    pub fn cuboid(half_extents: Vec3, density: f32) -> Self {
+        let mass = density * 8.0 * half_extents.x * half_extents.y * half_extents.z;
+        let sq = half_extents * half_extents;
+        let diagonal = Vec3::new(sq.y + sq.z, sq.x + sq.z, sq.x + sq.y) * (mass / 3.0);
+
+        Self::new(mass, Vec3::ZERO, Mat3::from_diagonal(diagonal))
+    }
+
+    /// Y axis cylinder;
+    pub fn cylinder(half_height: f32, radius: f32, density: f32) -> Self {
+        let height = 2.0 * half_height;
+        let mass = density * PI * radius * radius * height;
+        let side = mass * (3.0 * radius * radius + height * height) / 12.0;
+
+        Self::new(mass, Vec3::ZERO, Mat3::from_diagonal(Vec3::new(side, 0.5 * mass * radius * radius, side)))
+    }
+
+    /// Y axis cylinder && two hemispherical caps;
+    pub fn capsule(half_height: f32, radius: f32, density: f32) -> Self {
+        let height = 2.0 * half_height;
+        let r2 = radius * radius;
+        let cylinder = density * PI * r2 * height;
+        let cap = density * 2.0 / 3.0 * PI * radius.powi(3);
+
+        // Cap inertia moved from its own center (3r/8 from the flat side) to the capsule center;
+        let side = cylinder * (height * height / 12.0 + r2 / 4.0)
+            + 2.0 * cap * (0.4 * r2 + height * height / 4.0 + 3.0 * height * radius / 8.0);
+        let axial = 0.5 * cylinder * r2 + 2.0 * cap * 0.4 * r2;
+
+        Self::new(cylinder + 2.0 * cap, Vec3::ZERO, Mat3::from_diagonal(Vec3::new(side, axial, side)))
+    }
+
+    pub fn convex_hull(hull: &ConvexHull, density: f32) -> Self {
+        Self::from_triangles(hull_triangles(hull.points()), density)
+    }
+
+    /// Closed triangle mesh, integrated with the divergence theorem;
+    /// Winding may be either way as long as it is consistent;
+    pub fn from_triangles(triangles: impl IntoIterator<Item=[Vec3; 3]>, density: f32) -> Self {
+        // Integrals of 1, x, y, z, x², y², z², xy, yz, zx over the volume;
+        let mut integral = [0.0f32; 10];
+
+        for [p0, p1, p2] in triangles {
+            let d = (p1 - p0).cross(p2 - p0);
+            let (fx, gx) = subexpressions(p0.x, p1.x, p2.x);
+            let (fy, gy) = subexpressions(p0.y, p1.y, p2.y);
+            let (fz, gz) = subexpressions(p0.z, p1.z, p2.z);
+
+            integral[0] += d.x * fx[0];
+            integral[1] += d.x * fx[1];
+            integral[2] += d.y * fy[1];
+            integral[3] += d.z * fz[1];
+            integral[4] += d.x * fx[2];
+            integral[5] += d.y * fy[2];
+            integral[6] += d.z * fz[2];
+            integral[7] += d.x * (p0.y * gx[0] + p1.y * gx[1] + p2.y * gx[2]);
+            integral[8] += d.y * (p0.z * gy[0] + p1.z * gy[1] + p2.z * gy[2]);
+            integral[9] += d.z * (p0.x * gz[0] + p1.x * gz[1] + p2.x * gz[2]);
+        }
+
+        let scale = [1.0 / 6.0, 1.0 / 24.0, 1.0 / 24.0, 1.0 / 24.0, 1.0 / 60.0, 1.0 / 60.0, 1.0 / 60.0, 1.0 / 120.0, 1.0 / 120.0, 1.0 / 120.0];
+        for (value, scale) in integral.iter_mut().zip(scale) {
+            *value *= scale;
+        }
+
+        // Inward winding flips every integral;
+        if integral[0] < 0.0 {
+            integral.iter_mut().for_each(|value| *value = -*value);
+        }
+
+        let volume = integral[0];
+        if volume <= f32::EPSILON {
+            return Self::ZERO;
+        }
+
+        let c = Vec3::new(integral[1], integral[2], integral[3]) / volume;
+        let xx = integral[5] + integral[6] - volume * (c.y * c.y + c.z * c.z);
+        let yy = integral[4] + integral[6] - volume * (c.z * c.z + c.x * c.x);
+        let zz = integral[4] + integral[5] - volume * (c.x * c.x + c.y * c.y);
+        let xy = -(integral[7] - volume * c.x * c.y);
+        let yz = -(integral[8] - volume * c.y * c.z);
+        let zx = -(integral[9] - volume * c.z * c.x);
+
+        let inertia = Mat3::from_cols(
+            Vec3::new(xx, xy, zx),
+            Vec3::new(xy, yy, yz),
+            Vec3::new(zx, yz, zz),
+        );
+
+        Self::new(density * volume, c, inertia * density)
+    }
+
+    /// Same body seen from a parent frame, the tensor stays about the moved center;
+    pub fn transformed(&self, iso: &Isometry) -> Self {
+        let rotation = Mat3::from_quat(iso.rotation);
+        Self::new(self.mass, iso.transform_point(self.center), rotation * self.inertia * rotation.transpose())
+    }
+
+    /// Inertia about a point, parallel axis theorem;
+    pub fn inertia_about(&self, point: Vec3) -> Mat3 {
+        let d = self.center - point;
+        let shift = Mat3::from_diagonal(Vec3::splat(d.length_squared())) - outer(d, d);
+        self.inertia + shift * self.mass
+    }
+}
+
+/// Compound of two parts in the same frame;
+impl Add for MassProperties {
+    type Output = MassProperties;
+
+    fn add(self, other: MassProperties) -> MassProperties {
+        let mass = self.mass + other.mass;
+        if mass <= f32::EPSILON {
+            return MassProperties::ZERO;
+        }
+
+        let center = (self.center * self.mass + other.center * other.mass) / mass;
+        MassProperties::new(mass, center, self.inertia_about(center) + other.inertia_about(center))
+    }
+}
+
+impl Sum for MassProperties {
+    fn sum<I: Iterator<Item=MassProperties>>(iter: I) -> MassProperties {
+        iter.fold(MassProperties::ZERO, Add::add)
+    }
+}
+
+impl Shape {
+    /// Planes && mesh colliders are static geometry && get no mass;
+    pub fn mass_properties(&self, density: f32) -> MassProperties {
+        match self {
+            Shape::Sphere { radius } => MassProperties::sphere(*radius, density),
+            Shape::Box { half_extents } => MassProperties::cuboid(*half_extents, density),
+            Shape::Capsule { half_height, radius } => MassProperties::capsule(*half_height, *radius, density),
+            Shape::Cylinder { half_height, radius } => MassProperties::cylinder(*half_height, *radius, density),
+            Shape::Plane { .. } | Shape::TriMesh(_) | Shape::HeightField(_) => MassProperties::ZERO,
+            Shape::ConvexHull(hull) => MassProperties::convex_hull(hull, density),
+        }
+    }
+}
+
+fn outer(a: Vec3, b: Vec3) -> Mat3 {
+    Mat3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
+/// Per axis polynomial terms of a triangle, see Eberly's polyhedral mass properties;
+fn subexpressions(w0: f32, w1: f32, w2: f32) -> ([f32; 3], [f32; 3]) {
+    let temp0 = w0 + w1;
+    let f1 = temp0 + w2;
+    let temp1 = w0 * w0;
+    let temp2 = temp1 + w1 * temp0;
+    let f2 = temp2 + w2 * f1;
+    let f3 = w0 * temp1 + w1 * temp2 + w2 * f2;
+
+    let g = [f2 + w0 * (f1 + w0), f2 + w1 * (f1 + w1), f2 + w2 * (f1 + w2)];
+    ([f1, f2, f3], g)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::*;
+    use super::super::collision::tests::assert_near;
+    use super::super::{Collider, RigidBody, WorldBuilder};
+
+    fn assert_mat(actual: Mat3, expected: Mat3) {
+        assert!(actual.abs_diff_eq(expected, 1e-3), "expected {expected:?}, got {actual:?}");
+    }
+
+    /// Outward triangles of an axis aligned box;
+    fn box_triangles(min: Vec3, max: Vec3) -> Vec<[Vec3; 3]> {
+        let corner = |i: usize| Vec3::new(
+            match i & 1 { 0 => min.x, _ => max.x },
+            match i & 2 { 0 => min.y, _ => max.y },
+            match i & 4 { 0 => min.z, _ => max.z },
+        );
+
+        let faces = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+        faces.iter()
+            .flat_map(|f| [[corner(f[0]), corner(f[1]), corner(f[2])], [corner(f[0]), corner(f[2]), corner(f[3])]])
+            .collect()
+    }
+
+    #[test]
+    fn mesh_matches_box() {
+        let half = Vec3::new(1.0, 0.5, 0.25);
+        let offset = Vec3::new(3.0, -1.0, 2.0);
+        let mesh = MassProperties::from_triangles(box_triangles(offset - half, offset + half), 2.0);
+        let cuboid = MassProperties::cuboid(half, 2.0);
+
+        assert_near(mesh.mass, cuboid.mass);
+        assert!(mesh.center.abs_diff_eq(offset, 1e-4));
+        assert_mat(mesh.inertia, cuboid.inertia);
+
+        // Inward winding gives the same result;
+        let flipped = box_triangles(offset - half, offset + half).into_iter().map(|[a, b, c]| [a, c, b]);
+        assert_near(MassProperties::from_triangles(flipped, 2.0).mass, cuboid.mass);
+    }
+
+    #[test]
+    fn hull_matches_box() {
+        let half = Vec3::new(1.0, 2.0, 0.5);
+        let hull = ConvexHull::new((0..8).map(|i| Vec3::new(
+            match i & 1 { 0 => -half.x, _ => half.x },
+            match i & 2 { 0 => -half.y, _ => half.y },
+            match i & 4 { 0 => -half.z, _ => half.z },
+        )).chain([Vec3::ZERO]));
+
+        let props = MassProperties::convex_hull(&hull, 1.0);
+        let cuboid = MassProperties::cuboid(half, 1.0);
+        assert_near(props.mass, cuboid.mass);
+        assert!(props.center.abs_diff_eq(Vec3::ZERO, 1e-4));
+        assert_mat(props.inertia, cuboid.inertia);
+    }
+
+    #[test]
+    fn compound_by_parallel_axis() {
+        // Two unit cubes side by side are a 2x1x1 box;
+        let cube = MassProperties::cuboid(Vec3::splat(0.5), 1.0);
+        let left = cube.transformed(&Isometry::from_position(Vec3::new(-0.5, 0.0, 0.0)));
+        let right = cube.transformed(&Isometry::new(Vec3::new(0.5, 0.0, 0.0), Quat::from_rotation_y(1.0)));
+
+        let compound: MassProperties = [left, right].into_iter().sum();
+        let expected = MassProperties::cuboid(Vec3::new(1.0, 0.5, 0.5), 1.0);
+
+        assert_near(compound.mass, expected.mass);
+        assert!(compound.center.abs_diff_eq(Vec3::ZERO, 1e-5));
+        assert_mat(compound.inertia, expected.inertia);
+    }
+
+    #[test]
+    fn capsule_matches_closed_form() {
+        // Half height 1 && radius 0.5: a cylinder of mass π/2 && a sphere of mass π/6 split at its equator;
+        let capsule = MassProperties::capsule(1.0, 0.5, 1.0);
+        assert_near(capsule.mass, 2.0 * PI / 3.0);
+
+        // About the axis: cylinder m r²/2 plus sphere 2 m r²/5;
+        // Across: cylinder m (h²/12 + r²/4), each cap 2 m r²/5 + m (h²/4 + 3 h r / 8) by the parallel axis theorem;
+        assert_mat(capsule.inertia, Mat3::from_diagonal(Vec3::new(1.394082, 0.248709, 1.394082)));
+    }
+
+    #[test]
+    fn hull_of_many_points_approaches_sphere() {
+        // Fibonacci lattice on the unit sphere;
+        let count = 1000;
+        let points = (0..count).map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let (ring, angle) = ((1.0 - y * y).sqrt(), i as f32 * 2.399963);
+            Vec3::new(ring * angle.cos(), y, ring * angle.sin())
+        });
+
+        let props = MassProperties::convex_hull(&ConvexHull::new(points), 1.0);
+        let sphere = MassProperties::sphere(1.0, 1.0);
+        assert!((props.mass / sphere.mass - 1.0).abs() < 0.01, "{} vs {}", props.mass, sphere.mass);
+        assert!(props.center.length() < 1e-3);
+        assert!(props.inertia.abs_diff_eq(sphere.inertia, 0.03), "{:?}", props.inertia);
+    }
+
+    #[test]
+    fn off_center_body_turns_about_its_center_of_mass() {
+        let offset = Vec3::new(2.0, 0.0, 1.0);
+        let properties = MassProperties::sphere(0.5, 1.0).transformed(&Isometry::from_position(offset));
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let handle = world.add_body(RigidBody::new()
+            .with_position(Vec3::Y)
+            .with_collider(Collider::new(Shape::sphere(0.5)))
+            .with_mass_properties(properties)
+            .with_angular_velocity(Vec3::Y));
+
+        // The collider && position are kept, the center of mass is the offset;
+        let body = world.body(handle).unwrap();
+        assert!(matches!(body.collider().unwrap().shape(), Shape::Sphere { .. }));
+        assert!(body.center_of_mass().abs_diff_eq(Vec3::Y + offset, 1e-6));
+
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+        }
+
+        // Half a radian on the center stays put while the origin swings around it;
+        let body = world.body(handle).unwrap();
+        assert!(body.center_of_mass().abs_diff_eq(Vec3::Y + offset, 1e-4), "{:?}", body.center_of_mass());
+        assert_near(body.position().distance(body.center_of_mass()), offset.length());
+        assert!(body.position().distance(Vec3::Y) > 0.5, "{:?}", body.position());
+        assert!(body.velocity_at(body.center_of_mass()).length() < 1e-4);
+    }
+
+    #[test]
+    fn impulse_at_the_center_of_mass_adds_no_spin() {
+        let cube = MassProperties::cuboid(Vec3::splat(0.5), 1.0);
+        let compound: MassProperties = [cube, cube.transformed(&Isometry::from_position(Vec3::X * 2.0))].into_iter().sum();
+        let mut body = RigidBody::new()
+            .with_orientation(Quat::from_rotation_z(1.0))
+            .with_mass_properties(compound);
+
+        assert!(body.local_center_of_mass().abs_diff_eq(Vec3::X, 1e-5));
+        body.apply_impulse_at(Vec3::Y, body.center_of_mass());
+        assert!(body.angular_velocity().abs_diff_eq(Vec3::ZERO, 1e-6));
+
+        body.apply_impulse_at(Vec3::Y, body.position());
+        assert!(body.angular_velocity().z < 0.0, "{:?}", body.angular_velocity());
+    }
+}
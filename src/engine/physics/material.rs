@@ -0,0 +1,472 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::collider::Collider;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MaterialHandle(pub(super) usize);
+
+impl MaterialHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// How two materials' coefficients are merged;
+/// When the two materials disagree the later mode wins, so max beats multiply beats min beats average beats geometric mean;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CombineMode {
+    /// `sqrt(a * b)`, the friction rule of colliders without a material;
+    #[serde(rename = "geometric_mean")]
+    GeometricMean,
+    #[default]
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl CombineMode {
+    pub fn combine(self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineMode::GeometricMean => (a * b).sqrt(),
+            CombineMode::Average => (a + b) * 0.5,
+            CombineMode::Min => a.min(b),
+            CombineMode::Multiply => a * b,
+            CombineMode::Max => a.max(b),
+        }
+    }
+}
+
+/// Named surface description shared by colliders;
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Material {
+    pub(super) name: String,
+    /// Friction holding resting contacts;
+    pub(super) static_friction: f32,
+    /// Friction of sliding contacts;
+    pub(super) dynamic_friction: f32,
+    pub(super) restitution: f32,
+    /// Resistance to rolling, in meters of lever arm;
+    pub(super) rolling_friction: f32,
+    pub(super) friction_combine: CombineMode,
+    pub(super) restitution_combine: CombineMode,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            static_friction: 0.5,
+            dynamic_friction: 0.5,
+            restitution: 0.0,
+            rolling_friction: 0.0,
+            friction_combine: CombineMode::Average,
+            restitution_combine: CombineMode::Average,
+        }
+    }
+}
+
+impl Material {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Self::default() }
+    }
+
+    /// Static && dynamic friction, dynamic is capped by static;
+    pub fn with_friction(mut self, static_friction: f32, dynamic_friction: f32) -> Self {
+        self.static_friction = static_friction.max(0.0);
+        self.dynamic_friction = dynamic_friction.clamp(0.0, self.static_friction);
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_rolling_friction(mut self, rolling_friction: f32) -> Self {
+        self.rolling_friction = rolling_friction.max(0.0);
+        self
+    }
+
+    pub fn with_friction_combine(mut self, mode: CombineMode) -> Self {
+        self.friction_combine = mode;
+        self
+    }
+
+    pub fn with_restitution_combine(mut self, mode: CombineMode) -> Self {
+        self.restitution_combine = mode;
+        self
+    }
+
+    /// Unnamed material of a collider without one, keeps the collider's own coefficients;
+    fn from_collider(collider: &Collider) -> Self {
+        Self {
+            name: String::new(),
+            static_friction: collider.friction,
+            dynamic_friction: collider.friction,
+            restitution: collider.restitution,
+            rolling_friction: 0.0,
+            friction_combine: CombineMode::GeometricMean,
+            restitution_combine: CombineMode::Max,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn static_friction(&self) -> f32 {
+        self.static_friction
+    }
+
+    pub fn dynamic_friction(&self) -> f32 {
+        self.dynamic_friction
+    }
+
+    pub fn restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    pub fn rolling_friction(&self) -> f32 {
+        self.rolling_friction
+    }
+
+    pub fn friction_combine(&self) -> CombineMode {
+        self.friction_combine
+    }
+
+    pub fn restitution_combine(&self) -> CombineMode {
+        self.restitution_combine
+    }
+}
+
+/// Coefficients of one contact pair, combined or taken from an override;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactMaterial {
+    pub static_friction: f32,
+    pub dynamic_friction: f32,
+    pub restitution: f32,
+    pub rolling_friction: f32,
+}
+
+impl ContactMaterial {
+    pub fn combine(a: &Material, b: &Material) -> Self {
+        let friction = a.friction_combine.max(b.friction_combine);
+        let restitution = a.restitution_combine.max(b.restitution_combine);
+
+        Self {
+            static_friction: friction.combine(a.static_friction, b.static_friction),
+            dynamic_friction: friction.combine(a.dynamic_friction, b.dynamic_friction),
+            restitution: restitution.combine(a.restitution, b.restitution),
+            rolling_friction: friction.combine(a.rolling_friction, b.rolling_friction),
+        }
+    }
+
+    /// What breaks the rules the builders clamp to, for coefficients read from json;
+    fn invalid(&self) -> Option<&'static str> {
+        let frictions = [self.static_friction, self.dynamic_friction, self.rolling_friction];
+        if !frictions.iter().all(|friction| *friction >= 0.0) {
+            return Some("negative friction");
+        }
+
+        if self.dynamic_friction > self.static_friction {
+            return Some("dynamic friction above static friction");
+        }
+
+        match (0.0..=1.0).contains(&self.restitution) {
+            true => None,
+            false => Some("restitution outside [0; 1]")
+        }
+    }
+}
+
+/// Override of a pair as stored in json, materials referenced by name;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PairOverride {
+    a: String,
+    b: String,
+    #[serde(flatten)]
+    material: ContactMaterial,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct MaterialFile {
+    materials: Vec<Material>,
+    overrides: Vec<PairOverride>,
+}
+
+/// Registered materials && per pair overrides;
+/// Colliders without a material use their own friction && restitution;
+#[derive(Debug, Clone, Default)]
+pub struct MaterialTable {
+    materials: Vec<Material>,
+    overrides: BTreeMap<(MaterialHandle, MaterialHandle), ContactMaterial>,
+}
+
+impl MaterialTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a material, a named one replaces the one with its name && keeps its handle;
+    pub fn add(&mut self, material: Material) -> MaterialHandle {
+        match self.find(&material.name) {
+            Some(handle) => {
+                self.materials[handle.0] = material;
+                handle
+            }
+            None => {
+                self.materials.push(material);
+                MaterialHandle(self.materials.len() - 1)
+            }
+        }
+    }
+
+    /// Material registered under the name, unnamed ones are only reached by handle;
+    pub fn find(&self, name: &str) -> Option<MaterialHandle> {
+        self.materials.iter()
+            .position(|m| !m.name.is_empty() && m.name == name)
+            .map(MaterialHandle)
+    }
+
+    pub fn get(&self, handle: MaterialHandle) -> Option<&Material> {
+        self.materials.get(handle.0)
+    }
+
+    pub fn get_mut(&mut self, handle: MaterialHandle) -> Option<&mut Material> {
+        self.materials.get_mut(handle.0)
+    }
+
+    pub fn materials(&self) -> impl Iterator<Item=(MaterialHandle, &Material)> {
+        self.materials.iter()
+            .enumerate()
+            .map(|(i, m)| (MaterialHandle(i), m))
+    }
+
+    /// Fixed coefficients for a pair, in either order, instead of combining;
+    /// False && nothing set unless both materials are in this table;
+    pub fn set_override(&mut self, a: MaterialHandle, b: MaterialHandle, material: ContactMaterial) -> bool {
+        let known = self.get(a).is_some() && self.get(b).is_some();
+        if known {
+            self.overrides.insert(Self::key(a, b), material);
+        }
+
+        known
+    }
+
+    pub fn remove_override(&mut self, a: MaterialHandle, b: MaterialHandle) -> Option<ContactMaterial> {
+        self.overrides.remove(&Self::key(a, b))
+    }
+
+    pub fn get_override(&self, a: MaterialHandle, b: MaterialHandle) -> Option<&ContactMaterial> {
+        self.overrides.get(&Self::key(a, b))
+    }
+
+    fn key(a: MaterialHandle, b: MaterialHandle) -> (MaterialHandle, MaterialHandle) {
+        match a < b {
+            true => (a, b),
+            false => (b, a)
+        }
+    }
+
+    fn material_of(&self, collider: &Collider) -> Cow<'_, Material> {
+        match collider.material.and_then(|handle| self.get(handle)) {
+            Some(material) => Cow::Borrowed(material),
+            None => Cow::Owned(Material::from_collider(collider))
+        }
+    }
+
+    /// Coefficients used by contacts between the two colliders;
+    pub fn resolve(&self, a: &Collider, b: &Collider) -> ContactMaterial {
+        if let (Some(ma), Some(mb)) = (a.material, b.material) {
+            if let Some(material) = self.get_override(ma, mb) {
+                return *material;
+            }
+        }
+
+        ContactMaterial::combine(&self.material_of(a), &self.material_of(b))
+    }
+
+    /// Parse `{ "materials": [..], "overrides": [{ "a": name, "b": name, .. }] }`;
+    /// Coefficients the builders would clamp are rejected;
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let file: MaterialFile = serde_json::from_str(json)?;
+
+        let mut table = Self::new();
+        for material in file.materials {
+            let coefficients = ContactMaterial {
+                static_friction: material.static_friction,
+                dynamic_friction: material.dynamic_friction,
+                restitution: material.restitution,
+                rolling_friction: material.rolling_friction,
+            };
+            if let Some(reason) = coefficients.invalid() {
+                return Err(serde::de::Error::custom(format!("material {}: {reason}", material.name)));
+            }
+            table.add(material);
+        }
+
+        for pair in file.overrides {
+            let (Some(a), Some(b)) = (table.find(&pair.a), table.find(&pair.b)) else {
+                let missing = format!("override references unknown material {} or {}", pair.a, pair.b);
+                return Err(serde::de::Error::custom(missing));
+            };
+            if let Some(reason) = pair.material.invalid() {
+                return Err(serde::de::Error::custom(format!("override of {} with {}: {reason}", pair.a, pair.b)));
+            }
+            table.set_override(a, b, pair.material);
+        }
+
+        Ok(table)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        let overrides = self.overrides.iter()
+            .map(|(&(a, b), &material)| PairOverride {
+                a: self.materials[a.0].name.clone(),
+                b: self.materials[b.0].name.clone(),
+                material,
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&MaterialFile { materials: self.materials.clone(), overrides })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(Self::from_json(&json)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::shape::Shape;
+    use super::*;
+
+    fn ice_and_rubber() -> (MaterialTable, MaterialHandle, MaterialHandle) {
+        let mut table = MaterialTable::new();
+        let ice = table.add(Material::new("ice").with_friction(0.1, 0.03).with_friction_combine(CombineMode::Min));
+        let rubber = table.add(Material::new("rubber").with_friction(1.0, 0.8).with_restitution(0.8));
+        (table, ice, rubber)
+    }
+
+    #[test]
+    fn stronger_combine_mode_wins() {
+        let (table, ice, rubber) = ice_and_rubber();
+        let a = Collider::new(Shape::sphere(1.0)).with_material(ice);
+        let b = Collider::new(Shape::sphere(1.0)).with_material(rubber);
+
+        let pair = table.resolve(&a, &b);
+        assert_eq!(pair.static_friction, 0.1);
+        assert_eq!(pair.dynamic_friction, 0.03);
+        assert_eq!(pair.restitution, 0.4);
+    }
+
+    #[test]
+    fn override_replaces_combination() {
+        let (mut table, ice, rubber) = ice_and_rubber();
+        let pair = ContactMaterial { static_friction: 0.3, dynamic_friction: 0.2, restitution: 0.0, rolling_friction: 0.0 };
+        assert!(table.set_override(rubber, ice, pair));
+
+        let a = Collider::new(Shape::sphere(1.0)).with_material(ice);
+        let b = Collider::new(Shape::sphere(1.0)).with_material(rubber);
+        assert_eq!(table.resolve(&a, &b), pair);
+        assert_eq!(table.resolve(&b, &a), pair);
+    }
+
+    #[test]
+    fn unnamed_materials_and_foreign_handles() {
+        let (mut table, ice, _) = ice_and_rubber();
+        let sticky = table.add(Material::default().with_friction(2.0, 2.0));
+        let bouncy = table.add(Material::default().with_restitution(1.0));
+        assert_ne!(sticky, bouncy);
+        assert_eq!(table.get(sticky).unwrap().static_friction(), 2.0);
+        assert_eq!(table.find(""), None);
+
+        let mut other = MaterialTable::new();
+        let foreign = (0..6).map(|_| other.add(Material::default())).last().unwrap();
+        assert!(!table.set_override(ice, foreign, ContactMaterial::default()));
+        assert_eq!(table.get_override(ice, foreign), None);
+        assert!(table.to_json().is_ok());
+    }
+
+    #[test]
+    fn colliders_without_material_keep_their_coefficients() {
+        let table = MaterialTable::new();
+        let resolve = |(fa, ra): (f32, f32), (fb, rb): (f32, f32)| {
+            let a = Collider::new(Shape::sphere(1.0)).with_friction(fa).with_restitution(ra);
+            let b = Collider::new(Shape::sphere(1.0)).with_friction(fb).with_restitution(rb);
+            table.resolve(&a, &b)
+        };
+
+        let pair = resolve((0.4, 0.2), (0.4, 0.6));
+        assert_eq!(pair.static_friction, 0.4);
+        assert_eq!(pair.dynamic_friction, 0.4);
+        assert_eq!(pair.restitution, 0.6);
+
+        // Geometric mean, a frictionless collider stays frictionless;
+        assert_eq!(resolve((0.0, 0.0), (1.0, 0.0)).static_friction, 0.0);
+        let pair = resolve((0.2, 0.0), (0.8, 0.0));
+        assert!((pair.static_friction - 0.4).abs() < 1e-6 && (pair.dynamic_friction - 0.4).abs() < 1e-6);
+
+        // A material's own combine mode still wins over a bare collider's;
+        let (mut table, ice, _) = ice_and_rubber();
+        let rough = table.add(Material::new("rough").with_friction(1.0, 1.0));
+        let bare = Collider::new(Shape::sphere(1.0)).with_friction(0.0);
+        assert_eq!(table.resolve(&bare, &Collider::new(Shape::sphere(1.0)).with_material(rough)).static_friction, 0.5);
+        assert_eq!(table.resolve(&bare, &Collider::new(Shape::sphere(1.0)).with_material(ice)).static_friction, 0.0);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = r#"{
+            "materials": [
+                { "name": "ice", "static_friction": 0.1, "dynamic_friction": 0.03, "friction_combine": "min" },
+                { "name": "rubber", "static_friction": 1.0, "dynamic_friction": 0.8, "restitution": 0.8 }
+            ],
+            "overrides": [
+                { "a": "ice", "b": "rubber", "static_friction": 0.3, "dynamic_friction": 0.2 }
+            ]
+        }"#;
+
+        let table = MaterialTable::from_json(json).unwrap();
+        let (ice, rubber) = (table.find("ice").unwrap(), table.find("rubber").unwrap());
+        assert_eq!(table.get(ice).unwrap().friction_combine(), CombineMode::Min);
+        assert_eq!(table.get(rubber).unwrap().rolling_friction(), 0.0);
+        assert_eq!(table.get_override(ice, rubber).unwrap().static_friction, 0.3);
+
+        let again = MaterialTable::from_json(&table.to_json().unwrap()).unwrap();
+        assert_eq!(again.materials, table.materials);
+        assert_eq!(again.overrides, table.overrides);
+
+        assert!(MaterialTable::from_json(r#"{ "overrides": [{ "a": "ice", "b": "lava" }] }"#).is_err());
+    }
+
+    #[test]
+    fn json_coefficients_are_validated() {
+        for json in [
+            r#"{ "materials": [{ "name": "tar", "static_friction": -0.5, "dynamic_friction": -0.5 }] }"#,
+            r#"{ "materials": [{ "name": "soap", "static_friction": 0.2, "dynamic_friction": 0.6 }] }"#,
+            r#"{ "materials": [{ "name": "flubber", "restitution": 1.5 }] }"#,
+            r#"{ "materials": [{ "name": "ice" }], "overrides": [{ "a": "ice", "b": "ice", "rolling_friction": -1.0 }] }"#,
+        ] {
+            assert!(MaterialTable::from_json(json).is_err(), "{json}");
+        }
+
+        assert!(MaterialTable::from_json(r#"{ "materials": [{ "name": "ice", "static_friction": 0.1, "dynamic_friction": 0.1 }] }"#).is_ok());
+    }
+}
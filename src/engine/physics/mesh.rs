@@ -0,0 +1,577 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+use super::shape::{Aabb, Shape};
+
+/// Neighbors closer to coplanar than this share a smooth surface, their edge never pushes sideways;
+const ACTIVE_EDGE_COS: f32 = 0.999;
+const LEAF_SIZE: usize = 4;
+
+/// Mesh triangle with its outward normal && which edges are real corners;
+/// Edge i runs from point i to point i + 1, inactive edges are flat or concave seams;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Triangle {
+    pub(super) points: [Vec3; 3],
+    /// Zero for degenerate triangles;
+    pub(super) normal: Vec3,
+    pub(super) active: [bool; 3],
+}
+
+impl Triangle {
+    /// Neighbors are given by the vertex opposite to each shared edge, None on open borders;
+    fn new(points: [Vec3; 3], neighbors: [Option<Vec3>; 3]) -> Self {
+        let normal = (points[1] - points[0]).cross(points[2] - points[0]).normalize_or_zero();
+        let active = std::array::from_fn(|i| {
+            edge_active(points[i], points[(i + 1) % 3], normal, neighbors[i])
+        });
+
+        Self { points, normal, active }
+    }
+
+    pub(super) fn is_degenerate(&self) -> bool {
+        self.normal == Vec3::ZERO
+    }
+
+    /// Triangle as a flat convex hull, for the convex narrow phase;
+    pub(super) fn hull(&self) -> Shape {
+        Shape::convex_hull(self.points)
+    }
+
+    /// Closest point on the triangle, see Ericson's Real-Time Collision Detection;
+    pub(super) fn closest_point(&self, p: Vec3) -> Vec3 {
+        let [a, b, c] = self.points;
+        let (ab, ac) = (b - a, c - a);
+
+        let ap = p - a;
+        let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+
+        let bp = p - b;
+        let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = p - c;
+        let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = 1.0 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+
+    /// Whether a point on the triangle lies on one of its active edges, corners count for both edges;
+    pub(super) fn on_active_edge(&self, point: Vec3, tolerance: f32) -> bool {
+        (0..3).any(|i| {
+            let (a, b) = (self.points[i], self.points[(i + 1) % 3]);
+            let along = (point - a).dot(b - a) / (b - a).length_squared();
+            self.active[i] && point.distance(a.lerp(b, along.clamp(0.0, 1.0))) <= tolerance
+        })
+    }
+
+    /// Whether the point projected on the triangle plane falls inside the triangle;
+    pub(super) fn contains_projection(&self, point: Vec3, tolerance: f32) -> bool {
+        (0..3).all(|i| {
+            let (a, b) = (self.points[i], self.points[(i + 1) % 3]);
+            let edge = b - a;
+            edge.cross(point - a).dot(self.normal) >= -tolerance * edge.length()
+        })
+    }
+
+    /// Fraction along the segment where it crosses the triangle, from either side;
+    pub(super) fn cast_ray(&self, origin: Vec3, translation: Vec3) -> Option<f32> {
+        let [a, b, c] = self.points;
+        let (e1, e2) = (b - a, c - a);
+
+        let p = translation.cross(e2);
+        let det = e1.dot(p);
+        if det.abs() <= f32::EPSILON * e1.length() * e2.length() * translation.length() {
+            return None;
+        }
+
+        let s = origin - a;
+        let u = s.dot(p) / det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(e1);
+        let v = translation.dot(q) / det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = e2.dot(q) / det;
+        (0.0..=1.0).contains(&t).then_some(t)
+    }
+}
+
+/// Convex edges are active, flat && concave seams are not, open borders always are;
+fn edge_active(a: Vec3, b: Vec3, normal: Vec3, opposite: Option<Vec3>) -> bool {
+    let Some(opposite) = opposite else { return true };
+
+    // The neighbor walks the shared edge the other way round;
+    let neighbor = (a - b).cross(opposite - b).normalize_or_zero();
+    let convex = normal.dot(opposite - a) < 0.0;
+
+    convex && normal.dot(neighbor) < ACTIVE_EDGE_COS
+}
+
+/// Static triangle sources behind the mesh colliders, all in collider local space;
+pub(super) trait Triangles {
+    fn triangle_count(&self) -> usize;
+
+    fn triangle(&self, index: usize) -> Triangle;
+
+    fn local_aabb(&self) -> Aabb;
+
+    fn support(&self, dir: Vec3) -> Vec3;
+
+    /// Triangles whose bounds overlap the box;
+    fn query_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(usize));
+
+    /// Triangles whose bounds the segment may cross;
+    fn query_ray(&self, origin: Vec3, translation: Vec3, callback: &mut dyn FnMut(usize)) {
+        self.query_aabb(&Aabb::from_points([origin, origin + translation]), callback);
+    }
+
+    /// First triangle crossed by the segment, the normal faces back along the ray;
+    fn cast_ray(&self, origin: Vec3, translation: Vec3) -> Option<(f32, Vec3)> {
+        let mut best: Option<(f32, Vec3)> = None;
+        self.query_ray(origin, translation, &mut |index| {
+            let triangle = self.triangle(index);
+            if let Some(fraction) = triangle.cast_ray(origin, translation) {
+                if best.is_none_or(|(closest, _)| fraction < closest) {
+                    best = Some((fraction, triangle.normal));
+                }
+            }
+        });
+
+        best.map(|(fraction, normal)| match normal.dot(translation) > 0.0 {
+            true => (fraction, -normal),
+            false => (fraction, normal)
+        })
+    }
+
+    /// Distance to the closest triangle, negative behind it;
+    fn local_distance(&self, point: Vec3) -> (f32, Vec3) {
+        (0..self.triangle_count())
+            .map(|i| self.triangle(i))
+            .filter(|t| !t.is_degenerate())
+            .map(|t| {
+                let delta = point - t.closest_point(point);
+                match t.normal.dot(delta) < 0.0 {
+                    true => (-delta.length(), t.normal),
+                    false => (delta.length(), delta.try_normalize().unwrap_or(t.normal))
+                }
+            })
+            .min_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+            .unwrap_or((f32::INFINITY, Vec3::Y))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Node {
+    aabb: Aabb,
+    /// Leaves: first slot in the triangle order, internal nodes: first of both children;
+    start: usize,
+    /// Triangles of a leaf, zero for internal nodes;
+    count: usize,
+}
+
+/// Static triangle mesh with a bounding volume hierarchy over its triangles;
+/// Triangles are one sided && face where their counter clockwise winding points;
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriMesh {
+    vertices: Vec<Vec3>,
+    indices: Vec<[u32; 3]>,
+    active: Vec<[bool; 3]>,
+    nodes: Vec<Node>,
+    order: Vec<usize>,
+}
+
+impl TriMesh {
+    pub fn new(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> Self {
+        assert!(!indices.is_empty(), "Triangle mesh needs at least one triangle");
+        assert!(
+            indices.iter().flatten().all(|i| (*i as usize) < vertices.len()),
+            "Triangle mesh index out of bounds"
+        );
+
+        // Triangles sharing each edge, by sorted vertex pair;
+        let mut edges: HashMap<(u32, u32), Vec<(usize, usize)>> = HashMap::new();
+        for (t, triangle) in indices.iter().enumerate() {
+            for e in 0..3 {
+                let (a, b) = (triangle[e], triangle[(e + 1) % 3]);
+                edges.entry((a.min(b), a.max(b))).or_default().push((t, e));
+            }
+        }
+
+        let active = indices.iter()
+            .enumerate()
+            .map(|(t, triangle)| {
+                let neighbors = std::array::from_fn(|e| {
+                    let (a, b) = (triangle[e], triangle[(e + 1) % 3]);
+                    match edges[&(a.min(b), a.max(b))].as_slice() {
+                        [first, second] => {
+                            let (other, edge) = match first.0 == t {
+                                true => *second,
+                                false => *first
+                            };
+                            Some(vertices[indices[other][(edge + 2) % 3] as usize])
+                        }
+                        _ => None
+                    }
+                });
+
+                Triangle::new(triangle.map(|i| vertices[i as usize]), neighbors).active
+            })
+            .collect();
+
+        let bounds: Vec<Aabb> = indices.iter()
+            .map(|triangle| Aabb::from_points(triangle.map(|i| vertices[i as usize])))
+            .collect();
+
+        let mut order: Vec<usize> = (0..indices.len()).collect();
+        let mut nodes = vec![Node { aabb: bounds[0], start: 0, count: 0 }];
+        build(&mut nodes, 0, &mut order, 0, &bounds);
+
+        Self { vertices, indices, active, nodes, order }
+    }
+
+    /// Unindexed triangles, vertices at the same position are welded so seams are found;
+    pub fn from_triangles(triangles: impl IntoIterator<Item=[Vec3; 3]>) -> Self {
+        let mut vertices = Vec::new();
+        let mut welded: HashMap<[u32; 3], u32> = HashMap::new();
+
+        let indices = triangles.into_iter()
+            .map(|triangle| triangle.map(|point| {
+                *welded.entry(point.to_array().map(f32::to_bits)).or_insert_with(|| {
+                    vertices.push(point);
+                    (vertices.len() - 1) as u32
+                })
+            }))
+            .collect();
+
+        Self::new(vertices, indices)
+    }
+
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[[u32; 3]] {
+        &self.indices
+    }
+
+    fn traverse(&self, overlaps: impl Fn(&Aabb) -> bool, callback: &mut dyn FnMut(usize)) {
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(&node.aabb) {
+                continue;
+            }
+
+            match node.count {
+                0 => stack.extend([node.start, node.start + 1]),
+                count => self.order[node.start..node.start + count].iter().for_each(|t| callback(*t))
+            }
+        }
+    }
+}
+
+/// Median split along the widest axis of the triangle centers;
+fn build(nodes: &mut Vec<Node>, index: usize, order: &mut [usize], offset: usize, bounds: &[Aabb]) {
+    let aabb = order.iter()
+        .map(|t| bounds[*t])
+        .reduce(|a, b| a.merged(&b))
+        .unwrap();
+
+    if order.len() <= LEAF_SIZE {
+        nodes[index] = Node { aabb, start: offset, count: order.len() };
+        return;
+    }
+
+    let centers = Aabb::from_points(order.iter().map(|t| bounds[*t].center()));
+    let size = centers.max - centers.min;
+    let axis = match (size.x >= size.y, size.x >= size.z, size.y >= size.z) {
+        (true, true, _) => 0,
+        (false, _, true) => 1,
+        _ => 2
+    };
+
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by(mid, |a, b| bounds[*a].center()[axis].total_cmp(&bounds[*b].center()[axis]));
+
+    let first = nodes.len();
+    nodes.extend([nodes[index]; 2]);
+    nodes[index] = Node { aabb, start: first, count: 0 };
+
+    let (left, right) = order.split_at_mut(mid);
+    build(nodes, first, left, offset, bounds);
+    build(nodes, first + 1, right, offset + mid, bounds);
+}
+
+impl Triangles for TriMesh {
+    fn triangle_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn triangle(&self, index: usize) -> Triangle {
+        let points = self.indices[index].map(|i| self.vertices[i as usize]);
+        let normal = (points[1] - points[0]).cross(points[2] - points[0]).normalize_or_zero();
+
+        Triangle { points, normal, active: self.active[index] }
+    }
+
+    fn local_aabb(&self) -> Aabb {
+        self.nodes[0].aabb
+    }
+
+    fn support(&self, dir: Vec3) -> Vec3 {
+        self.vertices.iter()
+            .copied()
+            .max_by(|a, b| a.dot(dir).total_cmp(&b.dot(dir)))
+            .unwrap()
+    }
+
+    fn query_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(usize)) {
+        self.traverse(|bounds| bounds.overlaps(aabb), callback);
+    }
+
+    fn query_ray(&self, origin: Vec3, translation: Vec3, callback: &mut dyn FnMut(usize)) {
+        self.traverse(|bounds| bounds.ray_fraction(origin, translation).is_some(), callback);
+    }
+}
+
+/// Regular grid of heights over the local XZ plane, centered on the origin;
+/// Sample (column, row) sits at x = column * scale.x && z = row * scale.z, shifted by half the grid size;
+/// Each cell is split into two upward facing triangles along its (0, 0) - (1, 1) diagonal;
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeightField {
+    heights: Vec<f32>,
+    columns: usize,
+    rows: usize,
+    scale: Vec3,
+}
+
+impl HeightField {
+    /// Heights are row major, `heights[row * columns + column]`, && get multiplied by scale.y;
+    pub fn new(heights: Vec<f32>, columns: usize, rows: usize, scale: Vec3) -> Self {
+        assert!(columns >= 2 && rows >= 2, "Height field needs at least 2x2 samples");
+        assert_eq!(heights.len(), columns * rows, "Height field needs columns * rows heights");
+        assert!(scale.cmpgt(Vec3::ZERO).all(), "Height field scale must be positive");
+
+        Self { heights, columns, rows, scale }
+    }
+
+    pub fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn scale(&self) -> Vec3 {
+        self.scale
+    }
+
+    /// Unscaled height of a sample;
+    pub fn height(&self, column: usize, row: usize) -> f32 {
+        self.heights[row * self.columns + column]
+    }
+
+    /// Local position of sample (0, 0);
+    fn origin(&self) -> Vec3 {
+        Vec3::new(
+            -0.5 * (self.columns - 1) as f32 * self.scale.x,
+            0.0,
+            -0.5 * (self.rows - 1) as f32 * self.scale.z,
+        )
+    }
+
+    fn sample(&self, column: isize, row: isize) -> Option<Vec3> {
+        let inside = (0..self.columns as isize).contains(&column) && (0..self.rows as isize).contains(&row);
+        inside.then(|| {
+            let height = self.height(column as usize, row as usize) * self.scale.y;
+            self.origin() + Vec3::new(column as f32 * self.scale.x, height, row as f32 * self.scale.z)
+        })
+    }
+
+    /// Cells covering [min; max] along one axis;
+    fn cell_range(min: f32, max: f32, origin: f32, step: f32, cells: usize) -> Option<(usize, usize)> {
+        let (first, last) = (((min - origin) / step).floor(), ((max - origin) / step).floor());
+        match last < 0.0 || first >= cells as f32 {
+            true => None,
+            false => Some((first.max(0.0) as usize, (last as usize).min(cells - 1)))
+        }
+    }
+}
+
+impl Triangles for HeightField {
+    fn triangle_count(&self) -> usize {
+        2 * (self.columns - 1) * (self.rows - 1)
+    }
+
+    fn triangle(&self, index: usize) -> Triangle {
+        let cell = index / 2;
+        let (column, row) = ((cell % (self.columns - 1)) as isize, (cell / (self.columns - 1)) as isize);
+        let at = |dc: isize, dr: isize| self.sample(column + dc, row + dr);
+        let corner = |dc: isize, dr: isize| at(dc, dr).unwrap();
+
+        match index % 2 {
+            0 => Triangle::new([corner(0, 0), corner(0, 1), corner(1, 1)], [at(-1, 0), at(1, 2), at(1, 0)]),
+            _ => Triangle::new([corner(0, 0), corner(1, 1), corner(1, 0)], [at(0, 1), at(2, 1), at(0, -1)])
+        }
+    }
+
+    fn local_aabb(&self) -> Aabb {
+        let (low, high) = self.heights.iter().fold((f32::MAX, f32::MIN), |(low, high), h| (low.min(*h), high.max(*h)));
+        let origin = self.origin();
+
+        Aabb::new(
+            Vec3::new(origin.x, low * self.scale.y, origin.z),
+            Vec3::new(-origin.x, high * self.scale.y, -origin.z),
+        )
+    }
+
+    fn support(&self, dir: Vec3) -> Vec3 {
+        (0..self.rows as isize)
+            .flat_map(|row| (0..self.columns as isize).map(move |column| (column, row)))
+            .map(|(column, row)| self.sample(column, row).unwrap())
+            .max_by(|a, b| a.dot(dir).total_cmp(&b.dot(dir)))
+            .unwrap()
+    }
+
+    fn query_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(usize)) {
+        let (cells_x, cells_z) = (self.columns - 1, self.rows - 1);
+        let origin = self.origin();
+
+        let Some((x0, x1)) = Self::cell_range(aabb.min.x, aabb.max.x, origin.x, self.scale.x, cells_x) else { return };
+        let Some((z0, z1)) = Self::cell_range(aabb.min.z, aabb.max.z, origin.z, self.scale.z, cells_z) else { return };
+
+        for row in z0..=z1 {
+            for column in x0..=x1 {
+                let heights = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(dc, dr)| self.height(column + dc, row + dr) * self.scale.y);
+                let (low, high) = heights.iter().fold((f32::MAX, f32::MIN), |(low, high), h| (low.min(*h), high.max(*h)));
+                if high < aabb.min.y || low > aabb.max.y {
+                    continue;
+                }
+
+                let cell = row * cells_x + column;
+                callback(2 * cell);
+                callback(2 * cell + 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat square grid of two triangles per cell, same layout as a height field;
+    fn grid(cells: usize) -> TriMesh {
+        let at = |column: usize, row: usize| Vec3::new(column as f32, 0.0, row as f32);
+        TriMesh::from_triangles((0..cells * cells).flat_map(|cell| {
+            let (c, r) = (cell % cells, cell / cells);
+            [[at(c, r), at(c, r + 1), at(c + 1, r + 1)], [at(c, r), at(c + 1, r + 1), at(c + 1, r)]]
+        }))
+    }
+
+    #[test]
+    fn bvh_query_matches_brute_force() {
+        let mesh = grid(12);
+        assert_eq!(mesh.vertices().len(), 13 * 13);
+
+        let query = Aabb::new(Vec3::new(2.5, -1.0, 3.2), Vec3::new(4.1, 1.0, 7.7));
+        let mut found = Vec::new();
+        mesh.query_aabb(&query, &mut |t| found.push(t));
+
+        // Leaves report all their triangles, so extra ones are fine but none may be missed or repeated;
+        let count = found.len();
+        found.sort();
+        found.dedup();
+        assert_eq!(found.len(), count);
+
+        for t in 0..mesh.triangle_count() {
+            if Aabb::from_points(mesh.triangle(t).points).overlaps(&query) {
+                assert!(found.contains(&t), "missed {t}");
+            }
+        }
+        assert!(found.len() < mesh.triangle_count() / 2);
+    }
+
+    #[test]
+    fn flat_seams_are_inactive() {
+        let mesh = grid(3);
+        for t in 0..mesh.triangle_count() {
+            let triangle = mesh.triangle(t);
+            assert!(triangle.normal.abs_diff_eq(Vec3::Y, 1e-6));
+
+            // Only edges on the grid border stay active;
+            for (i, active) in triangle.active.iter().enumerate() {
+                let (a, b) = (triangle.points[i], triangle.points[(i + 1) % 3]);
+                let border = (a.x == b.x && (a.x == 0.0 || a.x == 3.0)) || (a.z == b.z && (a.z == 0.0 || a.z == 3.0));
+                assert_eq!(*active, border, "{triangle:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn height_field_matches_mesh() {
+        let heights: Vec<f32> = (0..16).map(|i| ((i * 7) % 5) as f32 * 0.1).collect();
+        let field = HeightField::new(heights, 4, 4, Vec3::new(2.0, 1.0, 2.0));
+
+        let mesh = TriMesh::from_triangles((0..field.triangle_count()).map(|t| field.triangle(t).points));
+        for t in 0..field.triangle_count() {
+            let (a, b) = (field.triangle(t), mesh.triangle(t));
+            assert!(a.normal.y > 0.0);
+            assert_eq!(a.points, b.points);
+            assert_eq!(a.active, b.active);
+        }
+
+        // A ridge between two cells is active, its flat counterpart is not;
+        let ridge = HeightField::new(vec![0.0, 1.0, 0.0, 0.0, 1.0, 0.0], 3, 2, Vec3::ONE);
+        assert!(ridge.triangle(1).active[1]);
+        let valley = HeightField::new(vec![1.0, 0.0, 1.0, 1.0, 0.0, 1.0], 3, 2, Vec3::ONE);
+        assert!(!valley.triangle(1).active[1]);
+    }
+
+    #[test]
+    fn ray_hits_height_field() {
+        let field = HeightField::new(vec![0.0, 0.0, 1.0, 1.0], 2, 2, Vec3::new(2.0, 1.0, 2.0));
+
+        // Plane rising from z = -1 (height 0) to z = 1 (height 1);
+        let (fraction, normal) = field.cast_ray(Vec3::new(0.3, 5.0, 0.0), Vec3::NEG_Y * 10.0).unwrap();
+        assert!((fraction * 10.0 - 4.5).abs() < 1e-4, "{fraction}");
+        assert!(normal.y > 0.0 && normal.z < 0.0);
+
+        assert!(field.cast_ray(Vec3::new(3.0, 5.0, 0.0), Vec3::NEG_Y * 10.0).is_none());
+    }
+}
@@ -0,0 +1,1324 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::{Mat3, Quat, Vec3};
+
+pub use body::{BodyHandle, BodyType, RigidBody};
+pub use character::{CharacterController, Ground};
+pub use cloth::{Cloth, ClothHandle, LinkKind, Pin};
+pub use collider::{Collider, CollisionGroups};
+pub use force::{
+    Drag, Explosion, Falloff, ForceBodies, ForceGenerator, ForceHandle,
+    PointAttractor, Spring, UniformGravity, Wind,
+};
+pub use event::{CollisionEvent, CollisionEventKind, TriggerEvent, TriggerEventKind};
+pub use island::SleepConfig;
+pub use joint::{Joint, JointHandle, JointKind, Motor};
+pub use mass::MassProperties;
+pub use material::{CombineMode, ContactMaterial, Material, MaterialHandle, MaterialTable};
+pub use mesh::{HeightField, TriMesh};
+pub use nbody::{NBodyGravity, NBodyMode};
+pub use particles::{FluidHandle, Particle, ParticleSystem, SpatialHash, SphFluid};
+pub use query::{QueryFilter, QueryHit, QueryPredicate};
+pub use collision::{
+    cast_ray, collide, distance, intersects, penetration, time_of_impact,
+    ContactPoint, Manifold, Motion, Penetration, Posed, SupportMap,
+};
+pub use broad_phase::{BodyPair, BroadPhase, Bvh, PairEvents, SweepAndPrune};
+pub use shape::{Aabb, ConvexHull, Isometry, Shape};
+pub use rope::{Rope, RopeHandle};
+pub use soft::{SoftBody, SoftBodyHandle};
+pub use solver::{FrictionModel, PositionCorrection, SolverConfig};
+
+use island::Islands;
+use slot::Slots;
+use solver::Solver;
+
+mod body;
+mod broad_phase;
+mod character;
+mod cloth;
+mod collider;
+mod collision;
+mod event;
+mod force;
+mod island;
+mod joint;
+mod mass;
+mod material;
+mod mesh;
+mod nbody;
+mod particles;
+mod query;
+mod rope;
+mod shape;
+mod slot;
+mod soft;
+mod solver;
+
+/// User veto over body pairs, runs before the narrow phase;
+pub type PairFilter = Box<dyn Fn(BodyHandle, &RigidBody, BodyHandle, &RigidBody) -> bool>;
+
+/// Gap at which a sweep counts as an impact;
+const CCD_TOLERANCE: f32 = 1e-3;
+
+/// Body pair reported by the broad phase && their contact manifold;
+/// Lives as long as their bounds overlap, the manifold is empty while shapes don't touch;
+/// Pairs with a sensor never get a manifold, only an overlap flag;
+#[derive(Debug, Clone)]
+pub struct Contact {
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    pub manifold: Manifold,
+
+    impulses: Vec<CachedImpulse>,
+    sensing: bool,
+}
+
+/// Accumulated impulse of a manifold point, reused to warm start the next step;
+#[derive(Debug, Clone, Copy)]
+struct CachedImpulse {
+    /// Point in body A space;
+    local_a: Vec3,
+    normal: f32,
+    tangent: Vec3,
+}
+
+impl Contact {
+    fn new((body_a, body_b): BodyPair) -> Self {
+        Self { body_a, body_b, manifold: Manifold::new(Vec3::ZERO), impulses: Vec::new(), sensing: false }
+    }
+
+    pub fn is_touching(&self) -> bool {
+        !self.manifold.points.is_empty()
+    }
+
+    /// Sensor pair currently overlapping;
+    pub fn is_sensing(&self) -> bool {
+        self.sensing
+    }
+
+    /// Total normal impulse applied by the last step;
+    pub fn normal_impulse(&self) -> f32 {
+        self.impulses.iter().map(|i| i.normal).sum()
+    }
+}
+
+/// Physics world;
+/// Owns every rigid body && steps them;
+pub struct World {
+    gravity: Vec3,
+    bodies: Slots<BodyHandle, RigidBody>,
+    joints: Slots<JointHandle, Joint>,
+    forces: Slots<ForceHandle, Box<dyn ForceGenerator>>,
+    fluids: Slots<FluidHandle, SphFluid>,
+    cloths: Slots<ClothHandle, Cloth>,
+    soft_bodies: Slots<SoftBodyHandle, SoftBody>,
+    ropes: Slots<RopeHandle, Rope>,
+    solver: SolverConfig,
+    sleep: SleepConfig,
+    materials: MaterialTable,
+
+    broad_phase: Box<dyn BroadPhase>,
+    pairs: BTreeMap<BodyPair, Contact>,
+    pair_filter: Option<PairFilter>,
+
+    /// Pairs touching at the end of the last step;
+    touching: BTreeSet<BodyPair>,
+    events: Vec<CollisionEvent>,
+    /// Sensor pairs overlapping at the end of the last step;
+    sensing: BTreeSet<BodyPair>,
+    triggers: Vec<TriggerEvent>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        WorldBuilder::default().build()
+    }
+
+    /// Insert body && return its handle;
+    pub fn add_body(&mut self, body: RigidBody) -> BodyHandle {
+        self.bodies.insert(body)
+    }
+
+    pub fn remove_body(&mut self, handle: BodyHandle) -> Option<RigidBody> {
+        let body = self.bodies.remove(handle);
+        if body.is_some() {
+            // Whatever rested on the body must fall;
+            let touching = self.pairs.values()
+                .filter(|c| c.is_touching())
+                .filter_map(|c| match (c.body_a == handle, c.body_b == handle) {
+                    (true, _) => Some(c.body_b),
+                    (_, true) => Some(c.body_a),
+                    _ => None
+                });
+            let jointed = self.joints.values()
+                .filter(|j| j.involves(handle))
+                .flat_map(|j| [Some(j.body_a), j.body_b])
+                .flatten();
+
+            let neighbors: Vec<BodyHandle> = touching.chain(jointed).collect();
+            for neighbor in neighbors {
+                if let Some(body) = self.body_mut(neighbor) {
+                    body.wake();
+                }
+            }
+
+            self.broad_phase.remove(handle);
+            self.pairs.retain(|(a, b), _| *a != handle && *b != handle);
+
+            let attached: Vec<JointHandle> = self.joints()
+                .filter(|(_, joint)| joint.involves(handle))
+                .map(|(joint, _)| joint)
+                .collect();
+
+            for joint in attached {
+                self.remove_joint(joint);
+            }
+        }
+
+        body
+    }
+
+    /// Insert joint, its world frames are stored relative to the bodies' current poses;
+    /// None if a body is gone or from another world, the joint isn't added then;
+    pub fn add_joint(&mut self, mut joint: Joint) -> Option<JointHandle> {
+        assert_ne!(Some(joint.body_a), joint.body_b, "Joint needs two different bodies");
+
+        let iso_a = self.body(joint.body_a)?.isometry();
+        joint.frame_a = iso_a.inverse() * joint.frame_a;
+        if let Some(handle) = joint.body_b {
+            let iso_b = self.body(handle)?.isometry();
+            joint.frame_b = iso_b.inverse() * joint.frame_b;
+        }
+
+        Some(self.joints.insert(joint))
+    }
+
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint> {
+        self.joints.remove(handle)
+    }
+
+    /// Joint behind the handle, none once it was removed even if its slot holds a new joint;
+    pub fn joint(&self, handle: JointHandle) -> Option<&Joint> {
+        self.joints.get(handle)
+    }
+
+    pub fn joint_mut(&mut self, handle: JointHandle) -> Option<&mut Joint> {
+        self.joints.get_mut(handle)
+    }
+
+    pub fn joints(&self) -> impl Iterator<Item=(JointHandle, &Joint)> {
+        self.joints.iter()
+    }
+
+    /// Register a force generator, evaluated before every step;
+    pub fn add_force_generator(&mut self, generator: impl ForceGenerator + 'static) -> ForceHandle {
+        self.forces.insert(Box::new(generator))
+    }
+
+    pub fn remove_force_generator(&mut self, handle: ForceHandle) -> Option<Box<dyn ForceGenerator>> {
+        self.forces.remove(handle)
+    }
+
+    pub fn force_generator_count(&self) -> usize {
+        self.forces.len()
+    }
+
+    /// Evaluate force generators && drop the finished ones;
+    fn apply_forces(&mut self, dt: f32) {
+        let mut bodies = ForceBodies::new(&mut self.bodies);
+        self.forces.retain(|_, generator| {
+            generator.apply(&mut bodies, dt);
+            !generator.is_finished()
+        });
+    }
+
+    /// Insert a fluid, stepped after the rigid bodies && kept out of static colliders;
+    pub fn add_fluid(&mut self, fluid: SphFluid) -> FluidHandle {
+        self.fluids.insert(fluid)
+    }
+
+    pub fn remove_fluid(&mut self, handle: FluidHandle) -> Option<SphFluid> {
+        self.fluids.remove(handle)
+    }
+
+    pub fn fluid(&self, handle: FluidHandle) -> Option<&SphFluid> {
+        self.fluids.get(handle)
+    }
+
+    pub fn fluid_mut(&mut self, handle: FluidHandle) -> Option<&mut SphFluid> {
+        self.fluids.get_mut(handle)
+    }
+
+    pub fn fluids(&self) -> impl Iterator<Item=(FluidHandle, &SphFluid)> {
+        self.fluids.iter()
+    }
+
+    /// Advance fluids against the static colliders around them;
+    fn step_fluids(&mut self, dt: f32) {
+        for fluid in self.fluids.values_mut() {
+            let Some(aabb) = fluid.particles().aabb() else { continue };
+
+            let boundaries = boundaries(&self.bodies, self.broad_phase.as_ref(), &aabb.expanded(fluid.reach(dt)), |body| {
+                body.body_type == BodyType::Static
+            });
+            fluid.step(self.gravity, dt, &boundaries);
+        }
+    }
+
+    /// Insert a cloth, stepped after the rigid bodies && kept out of every solid collider;
+    pub fn add_cloth(&mut self, cloth: Cloth) -> ClothHandle {
+        self.cloths.insert(cloth)
+    }
+
+    pub fn remove_cloth(&mut self, handle: ClothHandle) -> Option<Cloth> {
+        self.cloths.remove(handle)
+    }
+
+    pub fn cloth(&self, handle: ClothHandle) -> Option<&Cloth> {
+        self.cloths.get(handle)
+    }
+
+    pub fn cloth_mut(&mut self, handle: ClothHandle) -> Option<&mut Cloth> {
+        self.cloths.get_mut(handle)
+    }
+
+    pub fn cloths(&self) -> impl Iterator<Item=(ClothHandle, &Cloth)> {
+        self.cloths.iter()
+    }
+
+    /// Advance cloths against the colliders around them, pins follow their bodies' new poses;
+    fn step_cloths(&mut self, dt: f32) {
+        for cloth in self.cloths.values_mut() {
+            let targets: Vec<Option<Vec3>> = cloth.pins().iter()
+                .map(|pin| match pin.body {
+                    Some(handle) => self.bodies.get(handle)
+                        .map(|body| body.isometry().transform_point(pin.point)),
+                    None => Some(pin.point)
+                })
+                .collect();
+
+            let boundaries = boundaries(&self.bodies, self.broad_phase.as_ref(), &cloth.aabb().expanded(cloth.reach(self.gravity, dt)), |_| true);
+            cloth.step(self.gravity, dt, &targets, &boundaries);
+        }
+    }
+
+    /// Insert a soft body, stepped after the rigid bodies && colliding both ways with them;
+    pub fn add_soft_body(&mut self, body: SoftBody) -> SoftBodyHandle {
+        self.soft_bodies.insert(body)
+    }
+
+    pub fn remove_soft_body(&mut self, handle: SoftBodyHandle) -> Option<SoftBody> {
+        self.soft_bodies.remove(handle)
+    }
+
+    pub fn soft_body(&self, handle: SoftBodyHandle) -> Option<&SoftBody> {
+        self.soft_bodies.get(handle)
+    }
+
+    pub fn soft_body_mut(&mut self, handle: SoftBodyHandle) -> Option<&mut SoftBody> {
+        self.soft_bodies.get_mut(handle)
+    }
+
+    pub fn soft_bodies(&self) -> impl Iterator<Item=(SoftBodyHandle, &SoftBody)> {
+        self.soft_bodies.iter()
+    }
+
+    /// Advance soft bodies against the colliders around them, then move && push the dynamic ones back;
+    /// The rigid solver only sees the reactions on the next step;
+    fn step_soft_bodies(&mut self, dt: f32) {
+        let mut reactions = Vec::new();
+        for soft in self.soft_bodies.values_mut() {
+            let boundaries = boundaries(&self.bodies, self.broad_phase.as_ref(), &soft.aabb().expanded(soft.reach(self.gravity, dt)), |_| true);
+            reactions.extend(soft.step(self.gravity, dt, &boundaries));
+        }
+
+        apply_reactions(&mut self.bodies, reactions, 0.0);
+    }
+
+    /// Insert a rope, stepped after the soft bodies, its contacts && attachments pull dynamic bodies;
+    pub fn add_rope(&mut self, rope: Rope) -> RopeHandle {
+        self.ropes.insert(rope)
+    }
+
+    pub fn remove_rope(&mut self, handle: RopeHandle) -> Option<Rope> {
+        self.ropes.remove(handle)
+    }
+
+    pub fn rope(&self, handle: RopeHandle) -> Option<&Rope> {
+        self.ropes.get(handle)
+    }
+
+    pub fn rope_mut(&mut self, handle: RopeHandle) -> Option<&mut Rope> {
+        self.ropes.get_mut(handle)
+    }
+
+    pub fn ropes(&self) -> impl Iterator<Item=(RopeHandle, &Rope)> {
+        self.ropes.iter()
+    }
+
+    /// Advance ropes in lockstep against the colliders around them && the bodies they're tied to;
+    /// Dynamic bodies are moved && pulled after each rope's substep, so the next one sees it, with the most substeps any rope asks for;
+    /// They leave with the average velocity change over the step, the last substep's alone jitters;
+    fn step_ropes(&mut self, dt: f32) {
+        let Some(substeps) = self.ropes.values().map(Rope::substeps).max() else { return };
+        let bounds: Vec<Aabb> = self.ropes.values()
+            .map(|rope| rope.aabb().expanded(rope.reach(self.gravity, dt)))
+            .collect();
+
+        let h = dt / substeps as f32;
+        let mut before: Vec<Support> = Vec::new();
+        for substep in 1..=substeps {
+            let lag = dt * (1.0 - substep as f32 / substeps as f32);
+            for (rope, bounds) in self.ropes.values_mut().zip(&bounds) {
+                let anchors: Vec<Option<Support>> = rope.pins().iter()
+                    .map(|pin| pin.body.and_then(|handle| self.bodies.get(handle)
+                        .map(|body| Support::new(handle, body))))
+                    .collect();
+
+                let boundaries = boundaries(&self.bodies, self.broad_phase.as_ref(), bounds, |_| true);
+                let reactions = rope.substep(self.gravity, h, lag, &anchors, &boundaries);
+                for (support, _) in &reactions {
+                    if !before.iter().any(|other| other.body == support.body) {
+                        before.push(*support);
+                    }
+                }
+                apply_reactions(&mut self.bodies, reactions, lag);
+            }
+        }
+
+        for support in before {
+            let Some(body) = self.bodies.get_mut(support.body).filter(|body| body.is_dynamic()) else { continue };
+            let iso = body.isometry();
+            let turn = iso.rotation * support.iso.rotation.inverse();
+            let turn = match turn.w < 0.0 {
+                true => -turn,
+                false => turn
+            };
+
+            let angular = support.motion.angular + turn.to_scaled_axis() / dt;
+            let linear = support.motion.linear + (iso.position - support.iso.position) / dt;
+            body.set_linear_velocity(linear + angular.cross(body.center_of_mass() - iso.position));
+            body.set_angular_velocity(angular);
+        }
+    }
+
+    /// Body behind the handle, none once it was removed even if its slot holds a new body;
+    pub fn body(&self, handle: BodyHandle) -> Option<&RigidBody> {
+        self.bodies.get(handle)
+    }
+
+    pub fn body_mut(&mut self, handle: BodyHandle) -> Option<&mut RigidBody> {
+        self.bodies.get_mut(handle)
+    }
+
+    /// Iterate over every alive body;
+    pub fn bodies(&self) -> impl Iterator<Item=(BodyHandle, &RigidBody)> {
+        self.bodies.iter()
+    }
+
+    pub fn bodies_mut(&mut self) -> impl Iterator<Item=(BodyHandle, &mut RigidBody)> {
+        self.bodies.iter_mut()
+    }
+
+    pub fn body_count(&self) -> usize {
+        self.bodies.len()
+    }
+
+    pub fn gravity(&self) -> Vec3 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vec3) {
+        self.gravity = gravity;
+    }
+
+    pub fn solver(&self) -> &SolverConfig {
+        &self.solver
+    }
+
+    pub fn sleep(&self) -> &SleepConfig {
+        &self.sleep
+    }
+
+    pub fn materials(&self) -> &MaterialTable {
+        &self.materials
+    }
+
+    pub fn materials_mut(&mut self) -> &mut MaterialTable {
+        &mut self.materials
+    }
+
+    pub fn sleeping_count(&self) -> usize {
+        self.bodies().filter(|(_, body)| body.is_sleeping()).count()
+    }
+
+    pub fn broad_phase(&self) -> &dyn BroadPhase {
+        self.broad_phase.as_ref()
+    }
+
+    /// Take collision events queued by the steps since the last drain;
+    pub fn drain_collision_events(&mut self) -> impl Iterator<Item=CollisionEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Take trigger events queued by the steps since the last drain;
+    pub fn drain_trigger_events(&mut self) -> impl Iterator<Item=TriggerEvent> + '_ {
+        self.triggers.drain(..)
+    }
+
+    /// Bodies overlapping the sensor at the end of the last step;
+    pub fn sensed_bodies(&self, sensor: BodyHandle) -> impl Iterator<Item=BodyHandle> + '_ {
+        self.sensing.iter().filter_map(move |(a, b)| match (*a == sensor, *b == sensor) {
+            (true, _) => Some(*b),
+            (_, true) => Some(*a),
+            _ => None
+        })
+    }
+
+    /// Touching pairs found by the last step;
+    pub fn contacts(&self) -> impl Iterator<Item=&Contact> {
+        self.pairs.values().filter(|c| c.is_touching())
+    }
+
+    /// Advance simulation by dt seconds;
+    pub fn step(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        self.apply_forces(dt);
+
+        let gravity = self.gravity;
+        for (_, body) in self.bodies_mut() {
+            body.integrate_velocity(gravity, dt);
+        }
+
+        self.find_contacts();
+        let islands = self.wake_islands();
+
+        let mut solver = Solver::new(&self.solver, &self.bodies, dt);
+        for (handle, joint) in self.joints.iter() {
+            solver.add_joint(handle, joint, &self.bodies);
+        }
+        for contact in self.pairs.values() {
+            solver.add_contact(contact, &self.bodies, &self.materials);
+        }
+
+        solver.solve();
+        solver.store_joint_impulses(&mut self.joints);
+        solver.store_impulses(&mut self.pairs);
+        solver.apply(&mut self.bodies);
+
+        let clamped = self.sweep_ccd_bodies(dt);
+        for (handle, body) in self.bodies_mut() {
+            match clamped.get(&handle) {
+                Some(time) => body.displace(body.linear_velocity, body.angular_velocity, *time),
+                None => body.integrate_position(dt)
+            }
+        }
+
+        self.step_fluids(dt);
+        self.step_cloths(dt);
+        self.step_soft_bodies(dt);
+        self.step_ropes(dt);
+
+        self.update_sleep(&islands, dt);
+        self.queue_collision_events();
+        self.queue_trigger_events();
+    }
+
+    /// Compare overlapping sensor pairs with the previous step;
+    fn queue_trigger_events(&mut self) {
+        let sensing: BTreeSet<BodyPair> = self.pairs.iter()
+            .filter(|(_, contact)| contact.sensing)
+            .map(|(pair, _)| *pair)
+            .collect();
+
+        let exits = self.sensing.difference(&sensing).map(|pair| (TriggerEventKind::Exit, *pair));
+        let enters = sensing.difference(&self.sensing).map(|pair| (TriggerEventKind::Enter, *pair));
+
+        for (kind, (a, b)) in exits.chain(enters) {
+            // Pairs of a removed body report the survivor as the sensor if it is one;
+            let a_is_sensor = self.body(a).and_then(|body| body.collider()).is_some_and(|c| c.sensor);
+            let b_is_sensor = self.body(b).and_then(|body| body.collider()).is_some_and(|c| c.sensor);
+
+            let (sensor, body) = match (a_is_sensor, b_is_sensor) {
+                (false, true) => (b, a),
+                _ => (a, b)
+            };
+
+            self.triggers.push(TriggerEvent { kind, sensor, body });
+        }
+
+        self.sensing = sensing;
+    }
+
+    /// Compare touching pairs with the previous step;
+    fn queue_collision_events(&mut self) {
+        let touching: BTreeSet<BodyPair> = self.pairs.iter()
+            .filter(|(_, contact)| contact.is_touching())
+            .map(|(pair, _)| *pair)
+            .collect();
+
+        for pair in self.touching.difference(&touching) {
+            self.events.push(CollisionEvent {
+                kind: CollisionEventKind::Ended,
+                body_a: pair.0,
+                body_b: pair.1,
+                normal: Vec3::ZERO,
+                points: Vec::new(),
+                impulse: 0.0,
+            });
+        }
+
+        for pair in &touching {
+            let kind = match self.touching.contains(pair) {
+                true => CollisionEventKind::Persisted,
+                false => CollisionEventKind::Started
+            };
+
+            // Sleeping pairs keep their manifold but nothing happens between them;
+            let awake = [pair.0, pair.1].iter().any(|h| self.body(*h).is_some_and(|b| b.is_active()));
+            if kind == CollisionEventKind::Persisted && !awake {
+                continue;
+            }
+
+            let contact = &self.pairs[pair];
+            self.events.push(CollisionEvent {
+                kind,
+                body_a: pair.0,
+                body_b: pair.1,
+                normal: contact.manifold.normal,
+                points: contact.manifold.points.clone(),
+                impulse: contact.normal_impulse(),
+            });
+        }
+
+        self.touching = touching;
+    }
+
+    /// Time each fast CCD body may move before its first impact;
+    /// Bodies stop slightly inside what they hit, so the next step's contact stops them;
+    fn sweep_ccd_bodies(&self, dt: f32) -> BTreeMap<BodyHandle, f32> {
+        let mut clamped = BTreeMap::new();
+
+        for (handle, body) in self.bodies() {
+            let Some(collider) = body.collider() else { continue };
+            if !body.ccd || !body.is_active() || collider.sensor {
+                continue;
+            }
+
+            // Slow bodies can't skip past anything thicker than their own size;
+            let iso = body.isometry();
+            let motion = body.motion();
+            let start = collider.shape.aabb(&iso);
+            if motion.linear.length() * dt < start.half_extents().min_element() && motion.angular == Vec3::ZERO {
+                continue;
+            }
+
+            let swept = start.merged(&collider.shape.aabb(&motion.at(&iso, dt)));
+            let mut candidates = Vec::new();
+            self.broad_phase.query_aabb(&swept, &mut |other| candidates.push(other));
+            candidates.sort();
+
+            let mut first: Option<(f32, Vec3)> = None;
+            for other_handle in candidates {
+                if other_handle == handle {
+                    continue;
+                }
+
+                let Some(other) = self.body(other_handle) else { continue };
+                let Some(other_collider) = other.collider() else { continue };
+                if other_collider.sensor || !can_collide(self.pair_filter.as_ref(), (handle, body), (other_handle, other)) {
+                    continue;
+                }
+
+                let toi = time_of_impact(
+                    &Posed::new(&collider.shape, &iso), &motion,
+                    &Posed::new(&other_collider.shape, &other.isometry()), &other.motion(),
+                    dt, CCD_TOLERANCE,
+                );
+
+                if let Some(time) = toi {
+                    if first.is_none_or(|(t, _)| time < t) {
+                        first = Some((time, motion.linear - other.motion().linear));
+                    }
+                }
+            }
+
+            if let Some((time, relative)) = first {
+                let speed = relative.length();
+                let overshoot = match speed > f32::EPSILON {
+                    true => 2.0 * self.solver.slop / speed,
+                    false => 0.0
+                };
+
+                clamped.insert(handle, (time + overshoot).min(dt));
+            }
+        }
+
+        clamped
+    }
+
+    /// Group dynamic bodies linked by contacts && joints, wake every island with an awake member;
+    fn wake_islands(&mut self) -> Vec<Vec<usize>> {
+        let mut islands = Islands::new(self.bodies.capacity());
+        let mut woken = Vec::new();
+
+        let is_dynamic = |handle: BodyHandle| self.body(handle).is_some_and(|b| b.is_dynamic());
+        let links = self.pairs.values()
+            .filter(|c| c.is_touching())
+            .map(|c| (c.body_a, Some(c.body_b)))
+            .chain(self.joints.values().map(|j| (j.body_a, j.body_b)));
+
+        for (a, b) in links {
+            let Some(b) = b else { continue };
+            match (is_dynamic(a), is_dynamic(b)) {
+                (true, true) => islands.union(a.0, b.0),
+                // Moving kinematic bodies push sleepers awake;
+                (true, false) if self.is_moving_kinematic(b) => woken.push(a.0),
+                (false, true) if self.is_moving_kinematic(a) => woken.push(b.0),
+                _ => ()
+            }
+        }
+
+        let dynamic = self.bodies().filter(|(_, b)| b.is_dynamic()).map(|(h, _)| h.0);
+        let groups = islands.groups(dynamic.collect::<Vec<_>>());
+
+        for group in &groups {
+            let awake = group.iter().any(|i| {
+                woken.contains(i) || self.bodies.at(*i).is_some_and(|b| !b.is_sleeping())
+            });
+
+            if awake {
+                for index in group {
+                    if let Some(body) = self.bodies.at_mut(*index) {
+                        if body.sleeping {
+                            body.wake();
+                        }
+                    }
+                }
+            }
+        }
+
+        groups
+    }
+
+    fn is_moving_kinematic(&self, handle: BodyHandle) -> bool {
+        self.body(handle).is_some_and(|b| {
+            b.body_type == BodyType::Kinematic
+                && (b.linear_velocity.length() > self.sleep.linear_threshold
+                    || b.angular_velocity.length() > self.sleep.angular_threshold)
+        })
+    }
+
+    /// Put islands to sleep once all their bodies rested long enough;
+    fn update_sleep(&mut self, islands: &[Vec<usize>], dt: f32) {
+        if !self.sleep.enabled {
+            return;
+        }
+
+        let sleep = self.sleep;
+        for (_, body) in self.bodies_mut() {
+            if !body.is_active() {
+                continue;
+            }
+
+            let resting = body.linear_velocity.length() < sleep.linear_threshold
+                && body.angular_velocity.length() < sleep.angular_threshold;
+
+            body.sleep_time = match resting {
+                true => body.sleep_time + dt,
+                false => 0.0
+            };
+        }
+
+        for island in islands {
+            let tired = island.iter().all(|i| {
+                self.bodies.at(*i).is_some_and(|b| b.is_active() && b.sleep_time >= sleep.time)
+            });
+
+            if tired {
+                for index in island {
+                    if let Some(body) = self.bodies.at_mut(*index) {
+                        body.sleep();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sync body bounds into the broad phase && collect overlap changes;
+    fn update_broad_phase(&mut self) -> PairEvents {
+        // Removed bodies already left the broad phase;
+        for (handle, body) in self.bodies.iter() {
+            match body.collider().map(|c| c.shape.aabb(&body.isometry())) {
+                Some(aabb) => match self.broad_phase.contains(handle) {
+                    true => self.broad_phase.set_aabb(handle, aabb),
+                    false => self.broad_phase.insert(handle, aabb)
+                },
+                None => self.broad_phase.remove(handle)
+            }
+        }
+
+        self.broad_phase.update()
+    }
+
+    /// Narrow phase over broad phase pairs with at least one moving body;
+    fn find_contacts(&mut self) {
+        let events = self.update_broad_phase();
+        for pair in &events.removed {
+            self.pairs.remove(pair);
+        }
+
+        for pair in &events.added {
+            self.pairs.insert(*pair, Contact::new(*pair));
+        }
+
+        // Jointed bodies don't collide unless asked to;
+        let jointed: BTreeSet<BodyPair> = self.joints.values()
+            .filter(|joint| !joint.collisions)
+            .filter_map(|joint| joint.body_b.map(|b| broad_phase::ordered(joint.body_a, b)))
+            .collect();
+
+        for (pair @ (handle_a, handle_b), contact) in self.pairs.iter_mut() {
+            if jointed.contains(pair) {
+                contact.manifold.points.clear();
+                contact.impulses.clear();
+                contact.sensing = false;
+                continue;
+            }
+
+            let (Some(body_a), Some(body_b)) = (self.bodies.get(*handle_a), self.bodies.get(*handle_b)) else { continue };
+            let (Some(collider_a), Some(collider_b)) = (body_a.collider(), body_b.collider()) else { continue };
+
+            if !can_collide(self.pair_filter.as_ref(), (*handle_a, body_a), (*handle_b, body_b)) {
+                contact.manifold.points.clear();
+                contact.impulses.clear();
+                contact.sensing = false;
+                continue;
+            }
+
+            // Sensors keep their last state while nothing around them moves;
+            if collider_a.sensor || collider_b.sensor {
+                let moving = |body: &RigidBody| body.is_active() || body.body_type == BodyType::Kinematic;
+                if moving(body_a) || moving(body_b) {
+                    contact.sensing = collide(
+                        &collider_a.shape, &body_a.isometry(),
+                        &collider_b.shape, &body_b.isometry(),
+                    ).is_some();
+                }
+
+                continue;
+            }
+
+            // Sleeping pairs keep their manifold, it still links their island;
+            let kinematic_sleeper = |x: &RigidBody, y: &RigidBody| x.body_type == BodyType::Kinematic && y.is_sleeping();
+            if !body_a.is_active() && !body_b.is_active()
+                && !kinematic_sleeper(body_a, body_b) && !kinematic_sleeper(body_b, body_a) {
+                continue;
+            }
+
+            contact.manifold.points.clear();
+            let manifold = collide(
+                &collider_a.shape, &body_a.isometry(),
+                &collider_b.shape, &body_b.isometry(),
+            );
+
+            match manifold {
+                Some(manifold) => contact.manifold = manifold,
+                None => contact.impulses.clear()
+            }
+        }
+    }
+}
+
+/// Body a deformable leans on or hangs from, at its pose after this step;
+#[derive(Debug, Clone, Copy)]
+struct Support {
+    body: BodyHandle,
+    iso: Isometry,
+    motion: Motion,
+    /// Zero unless the body is dynamic, deformables share their corrections with it by these;
+    inv_mass: f32,
+    /// In body space;
+    inv_inertia: Mat3,
+    /// Body space center of mass the corrections turn the body about;
+    center: Vec3,
+}
+
+impl Support {
+    fn new(handle: BodyHandle, body: &RigidBody) -> Self {
+        let (inv_mass, inv_inertia) = match body.is_dynamic() {
+            true => (body.inv_mass(), body.inv_inertia),
+            false => (0.0, Mat3::ZERO)
+        };
+
+        Self { body: handle, iso: body.isometry(), motion: body.motion(), inv_mass, inv_inertia, center: body.local_center_of_mass() }
+    }
+
+    /// Pose `lag` seconds before the end of the step, so substeps see moving bodies sweep;
+    fn pose(&self, lag: f32) -> Isometry {
+        self.motion.at(&self.iso, -lag)
+    }
+
+    /// Velocity of the body's material at a world point;
+    fn velocity_at(&self, point: Vec3) -> Vec3 {
+        self.motion.linear + self.motion.angular.cross(point - self.iso.position)
+    }
+
+    /// Inverse inertia in world space with the body turned by `rotation`;
+    fn world_inv_inertia(&self, rotation: Quat) -> Mat3 {
+        let rotation = Mat3::from_quat(rotation);
+        rotation * self.inv_inertia * rotation.transpose()
+    }
+}
+
+/// Collider particles && deformables are kept out of;
+struct Boundary<'a> {
+    shape: &'a Shape,
+    support: Support,
+}
+
+#[cfg(test)]
+impl<'a> Boundary<'a> {
+    /// Collider of a static body;
+    fn fixed(shape: &'a Shape, iso: Isometry) -> Self {
+        let support = Support { body: BodyHandle(0, 0), iso, motion: Motion::default(), inv_mass: 0.0, inv_inertia: Mat3::ZERO, center: Vec3::ZERO };
+        Self { shape, support }
+    }
+}
+
+/// What a deformable did to one supporting body over a step or substep;
+/// Corrections are split with dynamic bodies, which move within the step && keep the velocity change;
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Reaction {
+    /// Velocity changes, each taken with the inertia of the pose it happened at;
+    velocity: Vec3,
+    spin: Vec3,
+    /// Pose change on top of the body's own motion, the turn as a scaled axis;
+    shift: Vec3,
+    turn: Vec3,
+}
+
+impl Reaction {
+    /// Pose `lag` seconds before the end of the step, moved by the corrections so far;
+    fn pose(&self, support: &Support, lag: f32) -> Isometry {
+        let pose = support.pose(lag);
+        Isometry::new(pose.position + self.shift, Quat::from_scaled_axis(self.turn) * pose.rotation)
+    }
+
+    /// Pose at the end of the step, carried on from the corrected one `lag` seconds before with the new velocities;
+    /// Going back `lag` seconds from it lands exactly on the corrected pose, so the next substep starts where this one ended;
+    fn settle(&self, support: &Support, lag: f32) -> Isometry {
+        let motion = Motion::new(support.motion.linear + self.velocity, support.motion.angular + self.spin);
+        motion.at(&self.pose(support, lag), lag)
+    }
+
+    /// Carry the body on for `time` seconds with the velocity the corrections gave it;
+    fn advance(&mut self, time: f32) {
+        self.shift += self.velocity * time;
+        self.turn += self.spin * time;
+    }
+
+    /// Velocity of the body's material at a world point `lag` seconds before the end of the step, including what the corrections gave it;
+    fn velocity_at(&self, support: &Support, lag: f32, point: Vec3) -> Vec3 {
+        let arm = point - self.pose(support, lag).position;
+        support.motion.linear + self.velocity + (support.motion.angular + self.spin).cross(arm)
+    }
+
+    /// Move a particle at `point` along `correction` && the body against it, `lag` seconds before the end of the step;
+    /// The split follows the particle's && the body's inverse masses at that pose, returns the particle's move;
+    fn exchange(&mut self, support: &Support, lag: f32, point: Vec3, inv_mass: f32, correction: Vec3, h: f32) -> Vec3 {
+        let Some(direction) = correction.try_normalize() else { return Vec3::ZERO };
+        let iso = self.pose(support, lag);
+        let inv_inertia = support.world_inv_inertia(iso.rotation);
+        let center = iso.transform_point(support.center);
+        let lever = (point - center).cross(direction);
+        let weight = inv_mass + support.inv_mass + lever.dot(inv_inertia * lever);
+        if weight <= 0.0 {
+            return Vec3::ZERO;
+        }
+
+        let push = correction.length() / weight;
+        let turn = inv_inertia * lever * push;
+        // The center of mass moves straight, the origin also swings around it;
+        let shift = direction * (push * support.inv_mass) + turn.cross(iso.position - center);
+        self.shift -= shift;
+        self.turn -= turn;
+        self.velocity -= shift / h;
+        self.spin -= turn / h;
+
+        direction * (push * inv_mass)
+    }
+}
+
+/// Solid colliders of the bodies accepted by the filter whose bounds overlap the box;
+fn boundaries<'a>(bodies: &'a Slots<BodyHandle, RigidBody>, broad_phase: &dyn BroadPhase, aabb: &Aabb, filter: impl Fn(&RigidBody) -> bool) -> Vec<Boundary<'a>> {
+    let mut handles = Vec::new();
+    broad_phase.query_aabb(aabb, &mut |handle| handles.push(handle));
+
+    handles.into_iter()
+        .filter_map(|handle| bodies.get(handle).map(|body| (handle, body)))
+        .filter(|(_, body)| filter(body))
+        .filter_map(|(handle, body)| body.collider()
+            .filter(|collider| !collider.sensor)
+            .map(|collider| Boundary { shape: &collider.shape, support: Support::new(handle, body) }))
+        .collect()
+}
+
+/// Move && push the dynamic bodies deformables leaned on or hung from, corrected `lag` seconds before the end of the step;
+fn apply_reactions(bodies: &mut Slots<BodyHandle, RigidBody>, reactions: Vec<(Support, Reaction)>, lag: f32) {
+    for (support, reaction) in reactions {
+        let Some(body) = bodies.get_mut(support.body).filter(|body| body.is_dynamic()) else { continue };
+        if reaction != Reaction::default() {
+            let iso = reaction.settle(&support, lag);
+            body.set_position(iso.position);
+            body.set_orientation(iso.rotation);
+            let arm = body.center_of_mass() - iso.position;
+            body.set_linear_velocity(body.linear_velocity() + reaction.velocity + reaction.spin.cross(arm));
+            body.set_angular_velocity(body.angular_velocity() + reaction.spin);
+        }
+    }
+}
+
+/// Collision groups && the user filter both allow the pair;
+fn can_collide(filter: Option<&PairFilter>, (a, body_a): (BodyHandle, &RigidBody), (b, body_b): (BodyHandle, &RigidBody)) -> bool {
+    let groups = match (body_a.collider(), body_b.collider()) {
+        (Some(collider_a), Some(collider_b)) => collider_a.groups.interacts_with(&collider_b.groups),
+        _ => false
+    };
+
+    groups && filter.is_none_or(|filter| filter(a, body_a, b, body_b))
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Physics world settings;
+pub struct WorldBuilder {
+    gravity: Vec3,
+    solver: SolverConfig,
+    sleep: SleepConfig,
+    materials: MaterialTable,
+    broad_phase: Box<dyn BroadPhase>,
+    pair_filter: Option<PairFilter>,
+}
+
+impl Default for WorldBuilder {
+    fn default() -> Self {
+        Self {
+            gravity: Vec3::new(0.0, -9.81, 0.0),
+            solver: SolverConfig::default(),
+            sleep: SleepConfig::default(),
+            materials: MaterialTable::new(),
+            broad_phase: Box::new(Bvh::new()),
+            pair_filter: None,
+        }
+    }
+}
+
+impl WorldBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec3) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Solver passes over every contact per step, more is stiffer && slower;
+    pub fn with_velocity_iterations(mut self, iterations: u32) -> Self {
+        self.solver.velocity_iterations = iterations;
+        self
+    }
+
+    /// Split impulse passes per step;
+    pub fn with_position_iterations(mut self, iterations: u32) -> Self {
+        self.solver.position_iterations = iterations;
+        self
+    }
+
+    pub fn with_friction_model(mut self, model: FrictionModel) -> Self {
+        self.solver.friction_model = model;
+        self
+    }
+
+    pub fn with_position_correction(mut self, correction: PositionCorrection) -> Self {
+        self.solver.position_correction = correction;
+        self
+    }
+
+    pub fn with_warm_starting(mut self, warm_starting: bool) -> Self {
+        self.solver.warm_starting = warm_starting;
+        self
+    }
+
+    /// Approach speed below which contacts don't bounce;
+    pub fn with_restitution_threshold(mut self, threshold: f32) -> Self {
+        self.solver.restitution_threshold = threshold.max(0.0);
+        self
+    }
+
+    pub fn with_sleeping(mut self, enabled: bool) -> Self {
+        self.sleep.enabled = enabled;
+        self
+    }
+
+    /// Speeds below which a body counts as resting;
+    pub fn with_sleep_threshold(mut self, linear: f32, angular: f32) -> Self {
+        self.sleep.linear_threshold = linear.max(0.0);
+        self.sleep.angular_threshold = angular.max(0.0);
+        self
+    }
+
+    /// Seconds an island must rest before sleeping;
+    pub fn with_sleep_time(mut self, time: f32) -> Self {
+        self.sleep.time = time.max(0.0);
+        self
+    }
+
+    /// Named materials colliders can refer to, see `MaterialTable::load`;
+    pub fn with_materials(mut self, materials: MaterialTable) -> Self {
+        self.materials = materials;
+        self
+    }
+
+    /// Broad phase strategy, a dynamic AABB tree by default;
+    pub fn with_broad_phase(mut self, broad_phase: impl BroadPhase + 'static) -> Self {
+        self.broad_phase = Box::new(broad_phase);
+        self
+    }
+
+    /// Veto body pairs before the narrow phase, return false to skip a pair;
+    pub fn with_pair_filter(mut self, filter: impl Fn(BodyHandle, &RigidBody, BodyHandle, &RigidBody) -> bool + 'static) -> Self {
+        self.pair_filter = Some(Box::new(filter));
+        self
+    }
+
+    pub fn build(self) -> World {
+        World {
+            gravity: self.gravity,
+            bodies: Slots::new(),
+            joints: Slots::new(),
+            forces: Slots::new(),
+            fluids: Slots::new(),
+            cloths: Slots::new(),
+            soft_bodies: Slots::new(),
+            ropes: Slots::new(),
+            solver: self.solver,
+            sleep: self.sleep,
+            materials: self.materials,
+
+            broad_phase: self.broad_phase,
+            pairs: BTreeMap::new(),
+            pair_filter: self.pair_filter,
+
+            touching: BTreeSet::new(),
+            events: Vec::new(),
+            sensing: BTreeSet::new(),
+            triggers: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Static wall 2 cm thick at the origin && a 10 cm bullet flying at it;
+    fn shooting_range(speed: f32, ccd: bool) -> (World, BodyHandle) {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::cuboid(Vec3::new(0.01, 2.0, 2.0)))));
+
+        let bullet = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::new(-5.0, 0.0, 0.0))
+                .with_linear_velocity(Vec3::X * speed)
+                .with_collider(Collider::new(Shape::sphere(0.1)))
+                .with_ccd(ccd),
+        );
+
+        (world, bullet)
+    }
+
+    #[test]
+    fn fast_sphere_tunnels_without_ccd() {
+        let (mut world, bullet) = shooting_range(400.0, false);
+        for _ in 0..10 {
+            world.step(DT);
+        }
+
+        assert!(world.body(bullet).unwrap().position().x > 0.0);
+    }
+
+    #[test]
+    fn fast_sphere_stops_at_thin_box() {
+        for speed in [100.0, 400.0, 2000.0] {
+            let (mut world, bullet) = shooting_range(speed, true);
+            for _ in 0..60 {
+                world.step(DT);
+                let x = world.body(bullet).unwrap().position().x;
+                assert!(x < 0.0, "tunneled at {speed} m/s, x = {x}");
+            }
+
+            let body = world.body(bullet).unwrap();
+            assert!(body.position().x > -0.2, "stopped early at {:?}", body.position());
+            assert!(body.linear_velocity().x <= 1e-3, "still moving at {:?}", body.linear_velocity());
+        }
+    }
+
+    #[test]
+    fn fast_sphere_bounces_off_thin_box() {
+        let (mut world, bullet) = shooting_range(400.0, true);
+        if let Some(body) = world.body_mut(bullet) {
+            body.set_collider(Some(Collider::new(Shape::sphere(0.1)).with_restitution(1.0)));
+        }
+
+        for _ in 0..30 {
+            world.step(DT);
+        }
+
+        let body = world.body(bullet).unwrap();
+        assert!(body.position().x < -1.0);
+        assert!(body.linear_velocity().x < -300.0, "{:?}", body.linear_velocity());
+    }
+
+    #[test]
+    fn fast_sphere_stops_at_slanted_thin_box() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        world.add_body(
+            RigidBody::fixed()
+                .with_orientation(Quat::from_rotation_y(1.0))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(0.01, 2.0, 2.0)))),
+        );
+
+        let bullet = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::new(-5.0, 0.0, 0.0))
+                .with_linear_velocity(Vec3::X * 400.0)
+                .with_collider(Collider::new(Shape::sphere(0.1)))
+                .with_ccd(true),
+        );
+
+        let wall_normal = Quat::from_rotation_y(1.0) * Vec3::X;
+        for _ in 0..60 {
+            world.step(DT);
+            let side = world.body(bullet).unwrap().position().dot(wall_normal);
+            assert!(side < 0.0, "tunneled, {side}");
+        }
+    }
+
+    /// Sphere rolling along a floor made of the given material;
+    fn rolling_ball(rolling_friction: f32) -> f32 {
+        let mut materials = MaterialTable::new();
+        let felt = materials.add(Material::new("felt").with_rolling_friction(rolling_friction));
+
+        let mut world = WorldBuilder::new().with_materials(materials).build();
+        world.add_body(
+            RigidBody::fixed()
+                .with_position(Vec3::new(0.0, -0.5, 0.0))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(50.0, 0.5, 50.0))).with_material(felt)),
+        );
+
+        let ball = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::new(0.0, 0.5, 0.0))
+                .with_linear_velocity(Vec3::X * 2.0)
+                .with_angular_velocity(Vec3::Z * -4.0)
+                .with_collider(Collider::new(Shape::sphere(0.5)).with_material(felt))
+                .with_density(1000.0),
+        );
+
+        for _ in 0..180 {
+            world.step(DT);
+        }
+
+        world.body(ball).unwrap().linear_velocity().x
+    }
+
+    #[test]
+    fn rolling_friction_stops_ball() {
+        assert!(rolling_ball(0.0) > 1.8);
+        assert!(rolling_ball(0.1).abs() < 0.05);
+    }
+
+    #[test]
+    fn static_friction_holds_box_on_slope() {
+        let mut materials = MaterialTable::new();
+        let rough = materials.add(Material::new("rough").with_friction(0.5, 0.3));
+
+        let mut world = WorldBuilder::new().with_materials(materials).build();
+        let slope = Quat::from_rotation_z(0.4f32.atan());
+        world.add_body(
+            RigidBody::fixed()
+                .with_orientation(slope)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(10.0, 0.5, 10.0))).with_material(rough)),
+        );
+
+        // Resting a little inside the slope, like after settling;
+        let start = slope * Vec3::new(0.0, 0.998, 0.0);
+        let crate_ = world.add_body(
+            RigidBody::new()
+                .with_position(start)
+                .with_orientation(slope)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.5))).with_material(rough))
+                .with_density(100.0),
+        );
+
+        for _ in 0..120 {
+            world.step(DT);
+        }
+
+        let moved = world.body(crate_).unwrap().position() - start;
+        assert!(moved.length() < 0.05, "slid {moved:?}");
+
+        // Once pushed it slides on dynamic friction, which can't hold it;
+        world.body_mut(crate_).unwrap().set_linear_velocity(slope * Vec3::X * -1.0);
+        for _ in 0..60 {
+            world.step(DT);
+        }
+
+        let speed = world.body(crate_).unwrap().linear_velocity().length();
+        assert!(speed > 1.0, "stuck at {speed}");
+    }
+
+    #[test]
+    fn removed_handles_go_stale() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let old = world.add_body(RigidBody::new().with_position(Vec3::X * 3.0));
+        world.add_force_generator(Spring::local(old, None, Vec3::ZERO, Vec3::ZERO, 0.0).with_stiffness(50.0));
+        let old_joint = world.add_joint(Joint::ball(old, None, Vec3::X * 3.0)).unwrap();
+
+        assert!(world.remove_body(old).is_some());
+        let new = world.add_body(RigidBody::new().with_position(Vec3::X * 3.0));
+        assert_eq!(new.index(), old.index());
+        assert_ne!(new, old);
+
+        assert!(world.body(old).is_none() && world.body_mut(old).is_none());
+        assert!(world.remove_body(old).is_none());
+
+        // The joint went with its body, the next joint in its slot isn't reachable through the old handle;
+        let other = world.add_body(RigidBody::fixed());
+        let new_joint = world.add_joint(Joint::distance(other, None, Vec3::ZERO, Vec3::Y)).unwrap();
+        assert_eq!(new_joint.index(), old_joint.index());
+        assert!(world.joint(old_joint).is_none() && world.remove_joint(old_joint).is_none());
+        assert!(world.joint(new_joint).is_some());
+
+        // The spring held on to the old handle, so the new body stays put;
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        assert_eq!(world.body(new).unwrap().position(), Vec3::X * 3.0);
+        assert_eq!(world.bodies().map(|(handle, _)| handle).collect::<Vec<_>>(), vec![new, other]);
+    }
+}
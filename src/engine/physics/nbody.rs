@@ -0,0 +1,344 @@
+use glam::Vec3;
+
+use super::body::BodyHandle;
+use super::force::{ForceBodies, ForceGenerator};
+
+/// Octree depth past which coincident points share a leaf;
+const MAX_DEPTH: u32 = 24;
+
+/// How mutual attraction is summed;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NBodyMode {
+    /// Every pair, O(n²);
+    Exact,
+    /// Far groups act through their center of mass, O(n log n);
+    /// A node is grouped while its size over its distance is below the opening angle θ,
+    /// zero is exact && around 0.5 is the usual trade off;
+    BarnesHut { theta: f32 },
+}
+
+/// Mutual gravitation of dynamic bodies;
+#[derive(Debug, Clone)]
+pub struct NBodyGravity {
+    constant: f32,
+    /// Plummer softening, keeps close encounters finite;
+    softening: f32,
+    mode: NBodyMode,
+    bodies: Option<Vec<BodyHandle>>,
+}
+
+impl NBodyGravity {
+    /// Gravitational constant of the simulation, in world units;
+    pub fn new(constant: f32) -> Self {
+        Self { constant, softening: 0.01, mode: NBodyMode::BarnesHut { theta: 0.5 }, bodies: None }
+    }
+
+    pub fn with_mode(mut self, mode: NBodyMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_softening(mut self, softening: f32) -> Self {
+        self.softening = softening.max(0.0);
+        self
+    }
+
+    /// Only these bodies attract each other;
+    pub fn with_bodies(mut self, bodies: Vec<BodyHandle>) -> Self {
+        self.bodies = Some(bodies);
+        self
+    }
+
+    pub fn mode(&self) -> NBodyMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: NBodyMode) {
+        self.mode = mode;
+    }
+
+    /// Acceleration of every point mass `(position, mass)` caused by all the others;
+    pub fn accelerations(&self, points: &[(Vec3, f32)]) -> Vec<Vec3> {
+        let field = Field { constant: self.constant, softening_squared: self.softening * self.softening };
+        match self.mode {
+            NBodyMode::Exact => {
+                (0..points.len())
+                    .map(|i| {
+                        points.iter()
+                            .enumerate()
+                            .filter(|(j, _)| *j != i)
+                            .map(|(_, &(position, mass))| field.pull(points[i].0, position, mass))
+                            .sum()
+                    })
+                    .collect()
+            }
+            NBodyMode::BarnesHut { theta } => {
+                let tree = Octree::new(points);
+                (0..points.len())
+                    .map(|i| tree.acceleration(points, i, theta.max(0.0), &field))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl ForceGenerator for NBodyGravity {
+    fn apply(&mut self, bodies: &mut ForceBodies, _dt: f32) {
+        let handles: Vec<BodyHandle> = bodies.dynamic_mut()
+            .filter(|(handle, _)| self.bodies.as_ref().is_none_or(|bodies| bodies.contains(handle)))
+            .map(|(handle, _)| handle)
+            .collect();
+
+        let points: Vec<(Vec3, f32)> = handles.iter()
+            .filter_map(|&handle| bodies.get(handle))
+            .map(|body| (body.center_of_mass(), body.mass()))
+            .collect();
+
+        for (handle, acceleration) in handles.into_iter().zip(self.accelerations(&points)) {
+            if let Some(body) = bodies.get_mut(handle) {
+                if !body.is_sleeping() {
+                    let force = acceleration * body.mass();
+                    body.add_force(force);
+                }
+            }
+        }
+    }
+}
+
+struct Field {
+    constant: f32,
+    softening_squared: f32,
+}
+
+impl Field {
+    /// Acceleration at `at` toward a mass at `source`;
+    fn pull(&self, at: Vec3, source: Vec3, mass: f32) -> Vec3 {
+        let offset = source - at;
+        let distance_squared = offset.length_squared() + self.softening_squared;
+        match distance_squared > f32::EPSILON {
+            true => offset * (self.constant * mass / (distance_squared * distance_squared.sqrt())),
+            false => Vec3::ZERO
+        }
+    }
+}
+
+/// Cube of space, a leaf holds its points, an inner node eight children;
+struct Node {
+    center: Vec3,
+    half_size: f32,
+    mass: f32,
+    mass_center: Vec3,
+    /// Index of the first of eight children, zero for a leaf;
+    children: usize,
+    points: Vec<usize>,
+}
+
+impl Node {
+    fn new(center: Vec3, half_size: f32) -> Self {
+        Self { center, half_size, mass: 0.0, mass_center: Vec3::ZERO, children: 0, points: Vec::new() }
+    }
+
+    fn octant(&self, point: Vec3) -> usize {
+        (point.x >= self.center.x) as usize
+            | ((point.y >= self.center.y) as usize) << 1
+            | ((point.z >= self.center.z) as usize) << 2
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        (point - self.center).abs().max_element() <= self.half_size
+    }
+}
+
+/// Barnes–Hut tree over point masses;
+struct Octree {
+    nodes: Vec<Node>,
+}
+
+impl Octree {
+    fn new(points: &[(Vec3, f32)]) -> Self {
+        let (min, max) = points.iter().fold(
+            (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), &(p, _)| (min.min(p), max.max(p)),
+        );
+
+        let mut tree = Self { nodes: Vec::with_capacity(points.len() * 2 + 1) };
+        if points.is_empty() {
+            return tree;
+        }
+
+        let half_size = ((max - min).max_element() * 0.5).max(f32::EPSILON);
+        tree.nodes.push(Node::new((min + max) * 0.5, half_size));
+
+        for i in 0..points.len() {
+            tree.insert(points, i);
+        }
+        tree.summarize(0, points);
+
+        tree
+    }
+
+    fn insert(&mut self, points: &[(Vec3, f32)], index: usize) {
+        let position = points[index].0;
+        let mut node = 0;
+        let mut depth = 0;
+
+        loop {
+            if self.nodes[node].children != 0 {
+                node = self.nodes[node].children + self.nodes[node].octant(position);
+                depth += 1;
+                continue;
+            }
+
+            if self.nodes[node].points.is_empty() || depth >= MAX_DEPTH {
+                self.nodes[node].points.push(index);
+                return;
+            }
+
+            // Split the leaf && push its points one level down;
+            let (center, half_size) = (self.nodes[node].center, self.nodes[node].half_size * 0.5);
+            let first = self.nodes.len();
+            for octant in 0..8 {
+                let sign = |bit: usize| match octant & bit != 0 {
+                    true => 1.0,
+                    false => -1.0
+                };
+                let offset = Vec3::new(sign(1), sign(2), sign(4)) * half_size;
+                self.nodes.push(Node::new(center + offset, half_size));
+            }
+
+            self.nodes[node].children = first;
+            for moved in std::mem::take(&mut self.nodes[node].points) {
+                let child = first + self.nodes[node].octant(points[moved].0);
+                self.nodes[child].points.push(moved);
+            }
+        }
+    }
+
+    /// Fill in masses && centers of mass, bottom up;
+    fn summarize(&mut self, node: usize, points: &[(Vec3, f32)]) {
+        let (mut mass, mut moment) = (0.0, Vec3::ZERO);
+        match self.nodes[node].children {
+            0 => {
+                for &i in &self.nodes[node].points {
+                    mass += points[i].1;
+                    moment += points[i].0 * points[i].1;
+                }
+            }
+            first => {
+                for child in first..first + 8 {
+                    self.summarize(child, points);
+                    mass += self.nodes[child].mass;
+                    moment += self.nodes[child].mass_center * self.nodes[child].mass;
+                }
+            }
+        }
+
+        self.nodes[node].mass = mass;
+        self.nodes[node].mass_center = match mass > 0.0 {
+            true => moment / mass,
+            false => self.nodes[node].center
+        };
+    }
+
+    fn acceleration(&self, points: &[(Vec3, f32)], index: usize, theta: f32, field: &Field) -> Vec3 {
+        let position = points[index].0;
+        let mut acceleration = Vec3::ZERO;
+
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.mass <= 0.0 {
+                continue;
+            }
+
+            if node.children == 0 {
+                for &i in node.points.iter().filter(|&&i| i != index) {
+                    acceleration += field.pull(position, points[i].0, points[i].1);
+                }
+                continue;
+            }
+
+            // Never group a node around the point itself;
+            let distance = node.mass_center.distance(position);
+            match !node.contains(position) && node.half_size * 2.0 < theta * distance {
+                true => acceleration += field.pull(position, node.mass_center, node.mass),
+                false => stack.extend(node.children..node.children + 8)
+            }
+        }
+
+        acceleration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{RigidBody, WorldBuilder};
+    use super::*;
+
+    /// Deterministic cluster of point masses;
+    fn cluster(count: usize) -> Vec<(Vec3, f32)> {
+        let mut seed = 7u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+
+        (0..count)
+            .map(|_| (Vec3::new(random(), random(), random()) * 20.0 - 10.0, 1.0 + random() * 9.0))
+            .collect()
+    }
+
+    #[test]
+    fn zero_opening_angle_is_exact() {
+        let points = cluster(64);
+        let exact = NBodyGravity::new(1.0).with_mode(NBodyMode::Exact).accelerations(&points);
+        let tree = NBodyGravity::new(1.0).with_mode(NBodyMode::BarnesHut { theta: 0.0 }).accelerations(&points);
+
+        for (a, b) in exact.iter().zip(&tree) {
+            assert!((*a - *b).length() <= 1e-4 * a.length().max(1.0), "{a:?} vs {b:?}");
+        }
+    }
+
+    #[test]
+    fn barnes_hut_approximates_exact() {
+        let points = cluster(300);
+        let exact = NBodyGravity::new(1.0).with_mode(NBodyMode::Exact).accelerations(&points);
+
+        for (theta, tolerance) in [(0.3, 0.005), (0.5, 0.01), (1.0, 0.1)] {
+            let tree = NBodyGravity::new(1.0).with_mode(NBodyMode::BarnesHut { theta }).accelerations(&points);
+
+            // Mean relative error over the system;
+            let error = exact.iter().zip(&tree)
+                .map(|(a, b)| (*a - *b).length() / a.length())
+                .sum::<f32>() / points.len() as f32;
+            assert!(error < tolerance, "θ = {theta}: {error}");
+        }
+    }
+
+    #[test]
+    fn coincident_points_do_not_recurse_forever() {
+        let points = vec![(Vec3::ONE, 1.0); 8];
+        let tree = NBodyGravity::new(1.0).accelerations(&points);
+        assert!(tree.iter().all(|a| a.is_finite()));
+    }
+
+    #[test]
+    fn binary_orbits_its_center_of_mass() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+
+        // Two equal masses on a circular orbit, v² = G m / (4 r);
+        let (mass, radius) = (100.0f32, 1.0);
+        let speed = (mass / (4.0 * radius)).sqrt();
+        let a = world.add_body(RigidBody::new().with_mass(mass).with_position(Vec3::X * radius).with_linear_velocity(Vec3::Y * speed));
+        let b = world.add_body(RigidBody::new().with_mass(mass).with_position(Vec3::X * -radius).with_linear_velocity(Vec3::Y * -speed));
+        world.add_force_generator(NBodyGravity::new(1.0).with_softening(0.0));
+
+        for _ in 0..600 {
+            world.step(1.0 / 240.0);
+        }
+
+        let (pa, pb) = (world.body(a).unwrap().position(), world.body(b).unwrap().position());
+        assert!((pa + pb).length() < 1e-3, "center of mass drifted to {:?}", (pa + pb) * 0.5);
+        assert!((pa.distance(pb) - 2.0 * radius).abs() < 0.02, "separation {}", pa.distance(pb));
+    }
+}
@@ -0,0 +1,158 @@
+use glam::{IVec3, Vec3};
+
+/// Uniform grid of cubic cells hashed into a fixed table, no storage for empty space;
+/// Rebuilt from scratch every step, counting sort keeps each bucket contiguous;
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    /// Snapshot of the points the table was built from && their cells;
+    points: Vec<Vec3>,
+    cells: Vec<IVec3>,
+    /// Bucket `b` owns `entries[starts[b]..starts[b + 1]]`;
+    starts: Vec<usize>,
+    entries: Vec<usize>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self { cell_size: cell_size.max(f32::EPSILON), points: Vec::new(), cells: Vec::new(), starts: vec![0; 2], entries: Vec::new() }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Takes effect on the next rebuild;
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.max(f32::EPSILON);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Index every point by its cell;
+    pub fn rebuild(&mut self, points: &[Vec3]) {
+        let size = self.cell_size;
+        self.points.clear();
+        self.points.extend_from_slice(points);
+        self.cells.clear();
+        self.cells.extend(points.iter().map(|&point| cell(point, size)));
+
+        // Twice as many buckets as points keeps collisions rare;
+        let buckets = (points.len() * 2).next_power_of_two().max(1);
+        self.starts.clear();
+        self.starts.resize(buckets + 1, 0);
+
+        for &cell in &self.cells {
+            self.starts[bucket(cell, buckets)] += 1;
+        }
+
+        // Prefix sum gives each bucket's end, filling back to front leaves its start;
+        let mut sum = 0;
+        for start in self.starts.iter_mut() {
+            sum += *start;
+            *start = sum;
+        }
+
+        self.entries.clear();
+        self.entries.resize(points.len(), 0);
+        for (index, &cell) in self.cells.iter().enumerate() {
+            let bucket = bucket(cell, buckets);
+            self.starts[bucket] -= 1;
+            self.entries[self.starts[bucket]] = index;
+        }
+    }
+
+    /// Every indexed point within `radius` of `center`, each reported once;
+    pub fn query(&self, center: Vec3, radius: f32, callback: &mut dyn FnMut(usize)) {
+        if self.points.is_empty() {
+            return;
+        }
+
+        let (min, max) = (cell(center - radius, self.cell_size), cell(center + radius, self.cell_size));
+        let radius_squared = radius * radius;
+        let buckets = self.starts.len() - 1;
+
+        for z in min.z..=max.z {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    let cell = IVec3::new(x, y, z);
+                    let bucket = bucket(cell, buckets);
+
+                    // Buckets are shared between colliding cells, keep this cell's points only;
+                    for &index in &self.entries[self.starts[bucket]..self.starts[bucket + 1]] {
+                        if self.cells[index] == cell && self.points[index].distance_squared(center) <= radius_squared {
+                            callback(index);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn cell(point: Vec3, size: f32) -> IVec3 {
+    (point / size).floor().as_ivec3()
+}
+
+/// Slot of a cell in a power of two table, after Teschner et al.;
+fn bucket(cell: IVec3, buckets: usize) -> usize {
+    let hash = (cell.x as u32).wrapping_mul(73856093)
+        ^ (cell.y as u32).wrapping_mul(19349663)
+        ^ (cell.z as u32).wrapping_mul(83492791);
+
+    hash as usize & (buckets - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scatter(count: usize) -> Vec<Vec3> {
+        let mut seed = 11u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+
+        (0..count)
+            .map(|_| Vec3::new(random(), random(), random()) * 4.0 - 2.0)
+            .collect()
+    }
+
+    #[test]
+    fn query_matches_brute_force() {
+        let points = scatter(500);
+        let mut hash = SpatialHash::new(0.3);
+        hash.rebuild(&points);
+
+        for (center, radius) in [(Vec3::ZERO, 0.3), (Vec3::new(1.0, -0.5, 0.2), 0.45), (Vec3::splat(-1.9), 1.0)] {
+            let mut found = Vec::new();
+            hash.query(center, radius, &mut |i| found.push(i));
+            found.sort_unstable();
+
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| points[i].distance(center) <= radius)
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn empty_and_rebuilt() {
+        let mut hash = SpatialHash::new(1.0);
+        hash.query(Vec3::ZERO, 10.0, &mut |_| panic!("empty hash reported a point"));
+
+        hash.rebuild(&[Vec3::ZERO, Vec3::X * 0.5]);
+        hash.rebuild(&[Vec3::Y * 5.0]);
+
+        let mut found = Vec::new();
+        hash.query(Vec3::ZERO, 10.0, &mut |i| found.push(i));
+        assert_eq!(found, vec![0]);
+    }
+}
@@ -0,0 +1,170 @@
+use glam::Vec3;
+
+pub use hash::SpatialHash;
+pub use sph::{FluidHandle, SphFluid};
+
+use super::Boundary;
+use super::shape::Aabb;
+
+mod hash;
+mod sph;
+
+/// Point mass of a particle system;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+impl Particle {
+    pub fn new(position: Vec3, velocity: Vec3) -> Self {
+        Self { position, velocity }
+    }
+}
+
+/// Free points with a collision radius && a uniform grid for neighbor search;
+/// Particles don't collide with each other here, solvers built on top add interactions;
+#[derive(Debug, Clone)]
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    radius: f32,
+    restitution: f32,
+    friction: f32,
+    grid: SpatialHash,
+}
+
+impl ParticleSystem {
+    /// Particles are spheres of `radius` against colliders;
+    pub fn new(radius: f32) -> Self {
+        let radius = radius.max(f32::EPSILON);
+        Self { particles: Vec::new(), radius, restitution: 0.0, friction: 0.0, grid: SpatialHash::new(radius * 2.0) }
+    }
+
+    /// Share of the normal speed kept when bouncing off a collider;
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Share of the tangential speed lost when touching a collider;
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Insert a particle && return its index, indices shift when particles are removed;
+    pub fn add(&mut self, particle: Particle) -> usize {
+        self.particles.push(particle);
+        self.particles.len() - 1
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn particles_mut(&mut self) -> &mut [Particle] {
+        &mut self.particles
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Keep particles matching the predicate, like the ones still inside the level;
+    pub fn retain(&mut self, predicate: impl FnMut(&Particle) -> bool) {
+        self.particles.retain(predicate);
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Bounds of every particle sphere, None while empty;
+    pub fn aabb(&self) -> Option<Aabb> {
+        if self.particles.is_empty() {
+            return None;
+        }
+
+        Some(Aabb::from_points(self.particles.iter().map(|p| p.position)).expanded(self.radius))
+    }
+
+    /// Index current positions in cells of the given size;
+    pub fn update_grid(&mut self, cell_size: f32) {
+        let positions: Vec<Vec3> = self.particles.iter().map(|p| p.position).collect();
+        self.grid.set_cell_size(cell_size);
+        self.grid.rebuild(&positions);
+    }
+
+    /// Particles within `radius` of a point as of the last grid update;
+    pub fn neighbors(&self, point: Vec3, radius: f32, callback: &mut dyn FnMut(usize)) {
+        self.grid.query(point, radius, callback);
+    }
+
+    pub(super) fn integrate(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.position += particle.velocity * dt;
+        }
+    }
+
+    /// Push particles out of boundaries && drop their approaching speed;
+    pub(super) fn collide(&mut self, boundaries: &[Boundary]) {
+        for particle in &mut self.particles {
+            for boundary in boundaries {
+                let (distance, normal) = boundary.shape.distance(&boundary.support.iso, particle.position);
+                if distance >= self.radius {
+                    continue;
+                }
+
+                particle.position += normal * (self.radius - distance);
+
+                let normal_speed = particle.velocity.dot(normal);
+                if normal_speed < 0.0 {
+                    let tangent = particle.velocity - normal * normal_speed;
+                    particle.velocity = tangent * (1.0 - self.friction) - normal * (normal_speed * self.restitution);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::shape::{Isometry, Shape};
+    use super::*;
+
+    #[test]
+    fn particles_rest_on_a_plane() {
+        let floor = Shape::plane(Vec3::Y, 0.0);
+        let boundaries = [Boundary::fixed(&floor, Isometry::from_position(Vec3::ZERO))];
+
+        let mut system = ParticleSystem::new(0.1);
+        system.add(Particle::new(Vec3::Y * 0.05, Vec3::new(1.0, -2.0, 0.0)));
+        system.collide(&boundaries);
+
+        let particle = system.particles()[0];
+        assert!((particle.position.y - 0.1).abs() < 1e-6);
+        assert_eq!(particle.velocity, Vec3::X);
+    }
+
+    #[test]
+    fn neighbors_follow_grid_updates() {
+        let mut system = ParticleSystem::new(0.05);
+        for i in 0..10 {
+            system.add(Particle::new(Vec3::X * i as f32 * 0.1, Vec3::ZERO));
+        }
+        system.update_grid(0.2);
+
+        let mut found = Vec::new();
+        system.neighbors(Vec3::ZERO, 0.25, &mut |i| found.push(i));
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1, 2]);
+    }
+}
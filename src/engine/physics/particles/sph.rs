@@ -0,0 +1,359 @@
+use std::f32::consts::PI;
+
+use glam::Vec3;
+
+use super::{Particle, ParticleSystem};
+use super::super::Boundary;
+use super::super::shape::Aabb;
+use super::super::slot::SlotHandle;
+
+/// Largest share of the smoothing radius a particle may travel per substep;
+const COURANT: f32 = 0.4;
+
+/// Fluid slot inside the physics world && its generation;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FluidHandle(pub(in super::super) usize, pub(in super::super) u32);
+
+impl FluidHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+}
+
+impl SlotHandle for FluidHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+/// Smoothed particle hydrodynamics liquid, weakly compressible after Müller et al. 2003;
+/// Particles sample the fluid on a lattice of `spacing`, each carries the mass of its cube;
+/// The world steps fluids after rigid bodies, static colliders are their walls;
+#[derive(Debug, Clone)]
+pub struct SphFluid {
+    particles: ParticleSystem,
+    spacing: f32,
+    /// Kernel support, neighbors farther away don't interact;
+    smoothing_radius: f32,
+    particle_mass: f32,
+    rest_density: f32,
+    /// Pressure per unit of density above rest;
+    stiffness: f32,
+    viscosity: f32,
+    max_substeps: u32,
+
+    densities: Vec<f32>,
+    pressures: Vec<f32>,
+    /// Neighbors of particle `i` are `neighbors[starts[i]..starts[i + 1]]`, found once per substep;
+    neighbors: Vec<usize>,
+    starts: Vec<usize>,
+}
+
+impl SphFluid {
+    /// Water like liquid sampled every `spacing` meters;
+    pub fn new(spacing: f32) -> Self {
+        let spacing = spacing.max(f32::EPSILON);
+        let rest_density = 1000.0;
+        Self {
+            particles: ParticleSystem::new(spacing * 0.5).with_friction(0.05),
+            spacing,
+            smoothing_radius: spacing * 2.0,
+            particle_mass: rest_density * spacing.powi(3),
+            rest_density,
+            stiffness: 200.0,
+            viscosity: 30.0,
+            max_substeps: 16,
+            densities: Vec::new(),
+            pressures: Vec::new(),
+            neighbors: Vec::new(),
+            starts: Vec::new(),
+        }
+    }
+
+    /// Kilograms per cubic meter, particle masses follow;
+    pub fn with_rest_density(mut self, density: f32) -> Self {
+        self.rest_density = density.max(f32::EPSILON);
+        self.particle_mass = self.rest_density * self.spacing.powi(3);
+        self
+    }
+
+    /// Stiffer fluids compress less but need more substeps;
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness.max(0.0);
+        self
+    }
+
+    pub fn with_viscosity(mut self, viscosity: f32) -> Self {
+        self.viscosity = viscosity.max(0.0);
+        self
+    }
+
+    /// Upper bound of the substeps the time step is split into to stay stable;
+    pub fn with_max_substeps(mut self, substeps: u32) -> Self {
+        self.max_substeps = substeps.max(1);
+        self
+    }
+
+    /// Share of the tangential speed lost along walls;
+    pub fn with_wall_friction(mut self, friction: f32) -> Self {
+        self.particles = self.particles.with_friction(friction);
+        self
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    pub fn smoothing_radius(&self) -> f32 {
+        self.smoothing_radius
+    }
+
+    pub fn rest_density(&self) -> f32 {
+        self.rest_density
+    }
+
+    pub fn particles(&self) -> &ParticleSystem {
+        &self.particles
+    }
+
+    pub fn particles_mut(&mut self) -> &mut ParticleSystem {
+        &mut self.particles
+    }
+
+    /// Density at each particle as of the last substep;
+    pub fn densities(&self) -> &[f32] {
+        &self.densities
+    }
+
+    pub fn add_particle(&mut self, position: Vec3, velocity: Vec3) -> usize {
+        self.particles.add(Particle::new(position, velocity))
+    }
+
+    /// Fill a box with particles at rest spacing, returns how many were added;
+    pub fn add_block(&mut self, aabb: Aabb, velocity: Vec3) -> usize {
+        let counts = ((aabb.max - aabb.min) / self.spacing).floor().as_uvec3().max(glam::UVec3::ONE);
+        let first = aabb.min + Vec3::splat(self.spacing * 0.5);
+
+        for z in 0..counts.z {
+            for y in 0..counts.y {
+                for x in 0..counts.x {
+                    let offset = Vec3::new(x as f32, y as f32, z as f32) * self.spacing;
+                    self.add_particle(first + offset, velocity);
+                }
+            }
+        }
+
+        (counts.x * counts.y * counts.z) as usize
+    }
+
+    /// Farthest a particle may move in `dt`, used to gather walls ahead of stepping;
+    pub(in super::super) fn reach(&self, dt: f32) -> f32 {
+        let speed = self.particles.particles().iter()
+            .map(|p| p.velocity.length())
+            .fold(0.0, f32::max);
+
+        (speed + self.sound_speed()) * dt + self.smoothing_radius
+    }
+
+    pub(in super::super) fn step(&mut self, gravity: Vec3, dt: f32, boundaries: &[Boundary]) {
+        if self.particles.is_empty() {
+            return;
+        }
+
+        let substeps = self.substeps(dt);
+        let dt = dt / substeps as f32;
+        for _ in 0..substeps {
+            self.update_densities();
+
+            let accelerations = self.accelerations(gravity);
+            for (particle, acceleration) in self.particles.particles_mut().iter_mut().zip(accelerations) {
+                particle.velocity += acceleration * dt;
+            }
+
+            self.particles.integrate(dt);
+            self.particles.collide(boundaries);
+        }
+    }
+
+    fn sound_speed(&self) -> f32 {
+        self.stiffness.sqrt()
+    }
+
+    /// Enough substeps for sound && particles to cross a fraction of the kernel each;
+    fn substeps(&self, dt: f32) -> u32 {
+        let speed = self.particles.particles().iter()
+            .map(|p| p.velocity.length())
+            .fold(self.sound_speed(), f32::max);
+
+        ((speed * dt / (COURANT * self.smoothing_radius)).ceil() as u32).clamp(1, self.max_substeps)
+    }
+
+    fn update_densities(&mut self) {
+        let h = self.smoothing_radius;
+        self.particles.update_grid(h);
+
+        let poly6 = 315.0 / (64.0 * PI * h.powi(9));
+        let particles = self.particles.particles();
+
+        self.densities.clear();
+        self.pressures.clear();
+        self.neighbors.clear();
+        self.starts.clear();
+        for particle in particles {
+            let mut density = 0.0;
+            self.starts.push(self.neighbors.len());
+            self.particles.neighbors(particle.position, h, &mut |j| {
+                let r2 = particle.position.distance_squared(particles[j].position);
+                density += self.particle_mass * poly6 * (h * h - r2).powi(3);
+                self.neighbors.push(j);
+            });
+
+            // Only compression pushes, tension would clump the free surface;
+            self.densities.push(density);
+            self.pressures.push((self.stiffness * (density - self.rest_density)).max(0.0));
+        }
+    }
+
+    fn neighbors_of(&self, index: usize) -> &[usize] {
+        let end = self.starts.get(index + 1).copied().unwrap_or(self.neighbors.len());
+        &self.neighbors[self.starts[index]..end]
+    }
+
+    fn accelerations(&self, gravity: Vec3) -> Vec<Vec3> {
+        let h = self.smoothing_radius;
+        let spiky = 45.0 / (PI * h.powi(6));
+        let particles = self.particles.particles();
+
+        particles.iter()
+            .enumerate()
+            .map(|(i, particle)| {
+                let mut force = Vec3::ZERO;
+                for &j in self.neighbors_of(i) {
+                    let offset = particle.position - particles[j].position;
+                    let r = offset.length();
+                    if j == i || r <= f32::EPSILON {
+                        continue;
+                    }
+
+                    let shared = self.particle_mass / self.densities[j] * (h - r) * spiky;
+                    let pressure = (self.pressures[i] + self.pressures[j]) * 0.5 * (h - r);
+                    force += offset / r * (pressure * shared);
+                    force += (particles[j].velocity - particle.velocity) * (self.viscosity * shared);
+                }
+
+                force / self.densities[i] + gravity
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::shape::{Isometry, Shape};
+    use super::super::super::{Collider, RigidBody, World};
+    use super::*;
+
+    /// Open box of static walls with an inner floor at zero;
+    fn container(half_width: f32) -> Vec<(Shape, Isometry)> {
+        let wall = |half_extents: Vec3, center: Vec3| (Shape::cuboid(half_extents), Isometry::from_position(center));
+        vec![
+            wall(Vec3::new(half_width + 0.1, 0.1, half_width + 0.1), Vec3::Y * -0.1),
+            wall(Vec3::new(0.1, 1.0, half_width), Vec3::new(half_width + 0.1, 1.0, 0.0)),
+            wall(Vec3::new(0.1, 1.0, half_width), Vec3::new(-half_width - 0.1, 1.0, 0.0)),
+            wall(Vec3::new(half_width, 1.0, 0.1), Vec3::new(0.0, 1.0, half_width + 0.1)),
+            wall(Vec3::new(half_width, 1.0, 0.1), Vec3::new(0.0, 1.0, -half_width - 0.1)),
+        ]
+    }
+
+    fn boundaries(walls: &[(Shape, Isometry)]) -> Vec<Boundary<'_>> {
+        walls.iter().map(|(shape, iso)| Boundary::fixed(shape, *iso)).collect()
+    }
+
+    #[test]
+    fn lattice_is_near_rest_density() {
+        let mut fluid = SphFluid::new(0.1);
+        fluid.add_block(Aabb::new(Vec3::ZERO, Vec3::splat(1.0)), Vec3::ZERO);
+        fluid.update_densities();
+
+        // Interior particle, far from the free surface;
+        let center = fluid.particles().particles().iter()
+            .position(|p| p.position.distance(Vec3::splat(0.55)) < 1e-3)
+            .unwrap();
+        let density = fluid.densities()[center];
+        assert!((density - fluid.rest_density()).abs() < 0.1 * fluid.rest_density(), "{density}");
+    }
+
+    #[test]
+    fn dropped_block_settles_in_container() {
+        let walls = container(0.4);
+        let walls = boundaries(&walls);
+
+        let mut fluid = SphFluid::new(0.1);
+        let count = fluid.add_block(Aabb::new(Vec3::new(-0.3, 0.3, -0.3), Vec3::new(0.3, 0.9, 0.3)), Vec3::ZERO);
+
+        let gravity = Vec3::new(0.0, -9.81, 0.0);
+        for _ in 0..240 {
+            fluid.step(gravity, 1.0 / 60.0, &walls);
+        }
+
+        let particles = fluid.particles().particles();
+        assert_eq!(particles.len(), count);
+        for particle in particles {
+            assert!(particle.position.abs().max_element() < 0.45 && particle.position.y > 0.0, "escaped to {:?}", particle.position);
+            assert!(particle.velocity.length() < 0.5, "still moving at {:?}", particle.velocity);
+        }
+
+        // Spread over the floor, surface about as high as the volume allows;
+        let top = particles.iter().map(|p| p.position.y).fold(0.0, f32::max);
+        let expected = count as f32 * 0.001 / (0.8 * 0.8);
+        assert!(top < expected * 1.5 + 0.1, "surface at {top}, expected {expected}");
+    }
+
+    #[test]
+    fn pressure_separates_overlapping_particles() {
+        let mut fluid = SphFluid::new(0.1).with_viscosity(0.0);
+        fluid.add_particle(Vec3::ZERO, Vec3::ZERO);
+        fluid.add_particle(Vec3::X * 0.01, Vec3::ZERO);
+        for _ in 0..8 {
+            fluid.add_particle(Vec3::Y * 0.005, Vec3::ZERO);
+        }
+
+        fluid.step(Vec3::ZERO, 1.0 / 60.0, &[]);
+        let particles = fluid.particles().particles();
+        assert!(particles[1].position.x - particles[0].position.x > 0.01);
+    }
+
+    #[test]
+    fn fluid_rests_on_static_colliders() {
+        let mut world = World::new();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::cuboid(Vec3::new(2.0, 0.5, 2.0)))));
+
+        let mut fluid = SphFluid::new(0.1);
+        fluid.add_block(Aabb::new(Vec3::new(-0.2, 1.0, -0.2), Vec3::new(0.2, 1.4, 0.2)), Vec3::ZERO);
+        let handle = world.add_fluid(fluid);
+
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+
+        let fluid = world.fluid(handle).unwrap();
+        let lowest = fluid.particles().particles().iter().map(|p| p.position.y).fold(f32::INFINITY, f32::min);
+        assert!(lowest >= 0.5 + fluid.particles().radius() - 1e-3, "sank to {lowest}");
+
+        assert!(world.remove_fluid(handle).is_some());
+        assert_eq!(world.fluids().count(), 0);
+
+        // A fluid added in the freed slot isn't reachable through the old handle;
+        let next = world.add_fluid(SphFluid::new(0.1));
+        assert_eq!(next.index(), handle.index());
+        assert!(world.fluid(handle).is_none() && world.fluid(next).is_some());
+    }
+}
@@ -0,0 +1,205 @@
+use glam::Vec3;
+
+use super::body::{BodyHandle, RigidBody};
+use super::collider::CollisionGroups;
+use super::collision::{cast_ray, collide, distance, penetration, time_of_impact, Motion, Posed};
+use super::shape::{Aabb, Isometry, Shape};
+use super::World;
+
+/// Gap at which a shape cast counts as a hit;
+const CAST_TOLERANCE: f32 = 1e-3;
+
+/// User callback accepting or rejecting a body;
+pub type QueryPredicate<'a> = &'a dyn Fn(BodyHandle, &RigidBody) -> bool;
+
+/// Which bodies a query may report;
+#[derive(Clone, Copy, Default)]
+pub struct QueryFilter<'a> {
+    groups: Option<CollisionGroups>,
+    skip_sensors: bool,
+    exclude: Option<BodyHandle>,
+    predicate: Option<QueryPredicate<'a>>,
+}
+
+impl<'a> QueryFilter<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only report colliders these groups interact with, same rules as between colliders;
+    pub fn with_groups(mut self, groups: CollisionGroups) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    /// Report sensor colliders, true by default;
+    pub fn with_sensors(mut self, sensors: bool) -> Self {
+        self.skip_sensors = !sensors;
+        self
+    }
+
+    /// Skip a body, usually the one asking;
+    pub fn with_exclude(mut self, body: BodyHandle) -> Self {
+        self.exclude = Some(body);
+        self
+    }
+
+    /// Only report bodies the callback accepts;
+    pub fn with_predicate(mut self, predicate: QueryPredicate<'a>) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    fn accepts(&self, handle: BodyHandle, body: &RigidBody) -> bool {
+        let groups = match (self.groups, body.collider()) {
+            (Some(groups), Some(collider)) => groups.interacts_with(&collider.groups),
+            _ => true
+        };
+        let sensor = self.skip_sensors && body.collider().is_some_and(|c| c.sensor);
+
+        groups && !sensor && self.exclude != Some(handle) && self.predicate.is_none_or(|predicate| predicate(handle, body))
+    }
+}
+
+/// First contact of a ray or swept shape;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryHit {
+    pub body: BodyHandle,
+    pub point: Vec3,
+    /// Surface normal of the hit body;
+    pub normal: Vec3,
+    /// Fraction of the translation travelled before the hit;
+    pub fraction: f32,
+}
+
+/// Scene queries, bodies are found through the broad phase as of the last step;
+impl World {
+    /// Closest hit along the segment [origin; origin + translation];
+    pub fn cast_ray(&self, origin: Vec3, translation: Vec3, filter: &QueryFilter) -> Option<QueryHit> {
+        self.cast_ray_all(origin, translation, filter).into_iter().next()
+    }
+
+    /// Every hit along the segment, closest first;
+    pub fn cast_ray_all(&self, origin: Vec3, translation: Vec3, filter: &QueryFilter) -> Vec<QueryHit> {
+        let mut candidates = Vec::new();
+        self.broad_phase.query_ray(origin, translation, &mut |handle| candidates.push(handle));
+
+        let mut hits: Vec<QueryHit> = self.filtered(candidates, filter)
+            .filter_map(|(handle, body, shape)| {
+                let (fraction, normal) = cast_ray(shape, &body.isometry(), origin, translation)?;
+                Some(QueryHit { body: handle, point: origin + translation * fraction, normal, fraction })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| a.fraction.total_cmp(&b.fraction).then(a.body.cmp(&b.body)));
+        hits
+    }
+
+    /// First hit of a convex shape swept along the translation;
+    pub fn cast_shape(&self, shape: &Shape, iso: &Isometry, translation: Vec3, filter: &QueryFilter) -> Option<QueryHit> {
+        if !shape.is_convex() {
+            return None;
+        }
+
+        let motion = Motion::new(translation, Vec3::ZERO);
+        let swept = shape.aabb(iso).merged(&shape.aabb(&motion.at(iso, 1.0)));
+
+        let mut candidates = Vec::new();
+        self.broad_phase.query_aabb(&swept, &mut |handle| candidates.push(handle));
+
+        self.filtered(candidates, filter)
+            .filter_map(|(handle, body, other)| {
+                let other_iso = body.isometry();
+                let fraction = match collide(shape, iso, other, &other_iso) {
+                    Some(_) => 0.0,
+                    None => time_of_impact(
+                        &Posed::new(shape, iso), &motion,
+                        &Posed::new(other, &other_iso), &Motion::default(),
+                        1.0, CAST_TOLERANCE,
+                    )?
+                };
+
+                let (point, normal) = surface_contact(shape, &motion.at(iso, fraction), other, &other_iso, translation);
+                Some(QueryHit { body: handle, point, normal, fraction })
+            })
+            .min_by(|a, b| a.fraction.total_cmp(&b.fraction).then(a.body.cmp(&b.body)))
+    }
+
+    /// Bodies whose collider overlaps the posed shape;
+    pub fn overlap(&self, shape: &Shape, iso: &Isometry, filter: &QueryFilter) -> Vec<BodyHandle> {
+        let mut candidates = Vec::new();
+        self.broad_phase.query_aabb(&shape.aabb(iso), &mut |handle| candidates.push(handle));
+
+        self.filtered(candidates, filter)
+            .filter(|(_, body, other)| collide(shape, iso, other, &body.isometry()).is_some())
+            .map(|(handle, _, _)| handle)
+            .collect()
+    }
+
+    /// Bodies whose bounds overlap the box;
+    pub fn overlap_aabb(&self, aabb: &Aabb, filter: &QueryFilter) -> Vec<BodyHandle> {
+        let mut candidates = Vec::new();
+        self.broad_phase.query_aabb(aabb, &mut |handle| candidates.push(handle));
+
+        self.filtered(candidates, filter)
+            .map(|(handle, _, _)| handle)
+            .collect()
+    }
+
+    /// Sorted candidates with a collider that pass the filter;
+    fn filtered<'w>(
+        &'w self,
+        mut candidates: Vec<BodyHandle>,
+        filter: &'w QueryFilter,
+    ) -> impl Iterator<Item=(BodyHandle, &'w RigidBody, &'w Shape)> + 'w {
+        candidates.sort();
+        candidates.into_iter().filter_map(move |handle| {
+            let body = self.body(handle)?;
+            let collider = body.collider()?;
+            filter.accepts(handle, body).then_some((handle, body, &collider.shape))
+        })
+    }
+}
+
+/// Point on the hit surface && its normal, for shapes touching or barely apart;
+fn surface_contact(shape: &Shape, iso: &Isometry, other: &Shape, other_iso: &Isometry, translation: Vec3) -> (Vec3, Vec3) {
+    // Meshes: the closest triangle near the shape;
+    if let Some(mesh) = other.triangles() {
+        let local = other_iso.inverse() * *iso;
+        let mut candidates = Vec::new();
+        mesh.query_aabb(&shape.aabb(&local).expanded(2.0 * CAST_TOLERANCE), &mut |index| candidates.push(index));
+
+        let closest = candidates.into_iter()
+            .map(|index| mesh.triangle(index))
+            .filter(|t| !t.is_degenerate())
+            .map(|t| {
+                let hull = t.hull();
+                let gap = distance(&Posed::new(shape, &local), &hull).map_or(0.0, |(gap, _, _)| gap);
+                (gap, hull)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        if let Some((_, hull)) = closest {
+            return surface_contact(shape, iso, &hull, other_iso, translation);
+        }
+    }
+
+    if let Shape::Plane { normal, offset } = other {
+        let normal = other_iso.transform_vector(*normal);
+        let offset = offset + normal.dot(other_iso.position);
+        let deepest = iso.transform_point(shape.support(iso.inverse_transform_vector(-normal)));
+        return (deepest - normal * (normal.dot(deepest) - offset), normal);
+    }
+
+    let (a, b) = (Posed::new(shape, iso), Posed::new(other, other_iso));
+    if let Some((_, point_a, point_b)) = distance(&a, &b) {
+        if let Some(normal) = (point_a - point_b).try_normalize() {
+            return (point_b, normal);
+        }
+    }
+
+    match penetration(&a, &b) {
+        Some(pen) => (pen.point_b, -pen.normal),
+        None => (other_iso.position, -translation.normalize_or_zero())
+    }
+}
@@ -0,0 +1,589 @@
+use glam::Vec3;
+
+use super::{BodyHandle, Boundary, Pin, Reaction, SpatialHash, Support};
+use super::collision::closest_segment_segment;
+use super::shape::Aabb;
+use super::slot::SlotHandle;
+
+/// Rope slot inside the physics world && its generation;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RopeHandle(pub(super) usize, pub(super) u32);
+
+impl RopeHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+}
+
+impl SlotHandle for RopeHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+/// Chain of particles held at a fixed spacing, a capsule of `radius` around each segment, solved with XPBD small steps;
+/// The world steps ropes after soft bodies, contacts && body attachments push back on dynamic bodies;
+#[derive(Debug, Clone)]
+pub struct Rope {
+    positions: Vec<Vec3>,
+    previous: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    particle_mass: f32,
+    /// Rest distance between neighboring particles;
+    spacing: f32,
+    pins: Vec<Pin>,
+
+    /// Inverse stiffness along the rope, zero is inextensible;
+    compliance: f32,
+    /// Inverse stiffness against bending, None hangs freely like a chain;
+    bend_compliance: Option<f32>,
+    radius: f32,
+    friction: f32,
+    /// Share of the velocity lost per second;
+    damping: f32,
+    self_collision: bool,
+    /// Segment centers, for finding the ones that may touch;
+    grid: SpatialHash,
+    substeps: u32,
+}
+
+impl Rope {
+    /// Straight rope from `start` to `end` made of at least one segment;
+    pub fn new(start: Vec3, end: Vec3, segments: usize) -> Self {
+        let segments = segments.max(1);
+        let positions: Vec<Vec3> = (0..=segments)
+            .map(|i| start.lerp(end, i as f32 / segments as f32))
+            .collect();
+
+        let count = positions.len();
+        Self {
+            previous: positions.clone(),
+            positions,
+            velocities: vec![Vec3::ZERO; count],
+            particle_mass: 1.0 / count as f32,
+            spacing: start.distance(end) / segments as f32,
+            pins: Vec::new(),
+            compliance: 0.0,
+            bend_compliance: None,
+            radius: 0.03,
+            friction: 0.3,
+            damping: 0.1,
+            self_collision: false,
+            grid: SpatialHash::new(1.0),
+            substeps: 20,
+        }
+    }
+
+    /// Rest length of the whole rope, extra length over the span between the ends sags below it as a V;
+    pub fn with_length(mut self, length: f32) -> Self {
+        let segments = self.segments();
+        let (start, end) = (self.positions[0], self.positions[segments]);
+        self.spacing = length.max(0.0) / segments as f32;
+
+        let chord = end - start;
+        let half = chord.length() * 0.5;
+        let extra = length * 0.5;
+        if extra > half {
+            // Down as seen across the chord, sideways for a vertical one;
+            let down = Vec3::NEG_Y - chord.normalize_or_zero() * chord.normalize_or_zero().dot(Vec3::NEG_Y);
+            let down = down.try_normalize().unwrap_or(Vec3::X);
+            let bottom = start + chord * 0.5 + down * (extra * extra - half * half).sqrt();
+
+            for (i, position) in self.positions.iter_mut().enumerate() {
+                let t = i as f32 / segments as f32 * 2.0;
+                *position = match t <= 1.0 {
+                    true => start.lerp(bottom, t),
+                    false => bottom.lerp(end, t - 1.0)
+                };
+            }
+            self.previous.clone_from(&self.positions);
+        }
+
+        self
+    }
+
+    /// Total mass in kilograms, spread evenly over the particles;
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.particle_mass = mass.max(f32::EPSILON) / self.positions.len() as f32;
+        self
+    }
+
+    /// Higher is stretchier, zero keeps the length;
+    pub fn with_compliance(mut self, compliance: f32) -> Self {
+        self.compliance = compliance.max(0.0);
+        self
+    }
+
+    /// Resist bending toward straight, near zero gives a stiff cable;
+    pub fn with_bend_compliance(mut self, compliance: f32) -> Self {
+        self.bend_compliance = Some(compliance.max(0.0));
+        self
+    }
+
+    /// Thickness of the capsules around the segments, for contacts && self collision;
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(0.0);
+        self
+    }
+
+    /// Share of the sliding motion lost against colliders;
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.max(0.0);
+        self
+    }
+
+    /// Keep segments apart from each other, so the rope can coil && knot instead of passing through itself;
+    pub fn with_self_collision(mut self, enabled: bool) -> Self {
+        self.self_collision = enabled;
+        self
+    }
+
+    /// More substeps stiffen long ropes && heavy loads at a linear cost;
+    pub fn with_substeps(mut self, substeps: u32) -> Self {
+        self.substeps = substeps.max(1);
+        self
+    }
+
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.velocities
+    }
+
+    pub fn segments(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    /// Index of the particle at the far end, the first is zero;
+    pub fn last(&self) -> usize {
+        self.segments()
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.particle_mass * self.positions.len() as f32
+    }
+
+    pub fn rest_length(&self) -> f32 {
+        self.spacing * self.segments() as f32
+    }
+
+    /// Length along the current shape, longer than the rest length under tension;
+    pub fn length(&self) -> f32 {
+        self.positions.windows(2).map(|pair| pair[0].distance(pair[1])).sum()
+    }
+
+    /// Move a particle, like grabbing it with the mouse;
+    pub fn set_position(&mut self, index: usize, position: Vec3) {
+        self.positions[index] = position;
+        self.previous[index] = position;
+        self.velocities[index] = Vec3::ZERO;
+    }
+
+    pub fn pins(&self) -> &[Pin] {
+        &self.pins
+    }
+
+    /// Hold a particle where it is;
+    pub fn pin(&mut self, index: usize) {
+        self.pin_at(index, self.positions[index]);
+    }
+
+    /// Hold a particle at a world point, it is moved there on the next step;
+    pub fn pin_at(&mut self, index: usize, point: Vec3) {
+        self.unpin(index);
+        self.pins.push(Pin { index, body: None, point });
+    }
+
+    /// Tie a particle to a point in body space, dynamic bodies are pulled by the rope in turn;
+    /// The pin lets go once the body is removed;
+    pub fn attach(&mut self, index: usize, body: BodyHandle, local: Vec3) {
+        self.unpin(index);
+        self.pins.push(Pin { index, body: Some(body), point: local });
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        self.pins.retain(|pin| pin.index != index);
+    }
+
+    /// Bounds of every segment's capsule;
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.positions.iter().copied()).expanded(self.radius)
+    }
+
+    /// Farthest a particle may move in `dt`, used to gather colliders ahead of stepping;
+    pub(super) fn reach(&self, gravity: Vec3, dt: f32) -> f32 {
+        let speed = self.velocities.iter().map(|v| v.length()).fold(0.0, f32::max);
+        (speed + gravity.length() * dt) * dt + self.radius
+    }
+
+    pub(super) fn substeps(&self) -> u32 {
+        self.substeps
+    }
+
+    /// Advance by one `h` long substep ending `lag` seconds before the end of the step;
+    /// `anchors` holds the body of each pin, None for world pins && the ones letting go;
+    /// Returns what ties && contacts did to each body;
+    pub(super) fn substep(&mut self, gravity: Vec3, h: f32, lag: f32, anchors: &[Option<Support>], boundaries: &[Boundary]) -> Vec<(Support, Reaction)> {
+        let inv_mass = 1.0 / self.particle_mass;
+        let mut inv_masses = vec![inv_mass; self.positions.len()];
+        let mut attached = vec![false; self.positions.len()];
+
+        // World pins glide to their points over the rest of the step, body pins are tied in the solve;
+        // Ties && contacts on the same body share its reaction, so each sees what the others did;
+        let mut supports: Vec<Support> = boundaries.iter().map(|boundary| boundary.support).collect();
+        let mut pinned = Vec::new();
+        let mut ties = Vec::new();
+        for (pin, anchor) in self.pins.iter().zip(anchors) {
+            match (pin.body, anchor) {
+                (None, _) => pinned.push((pin.index, pin.point)),
+                (Some(_), Some(support)) => {
+                    let slot = supports.iter().position(|other| other.body == support.body).unwrap_or_else(|| {
+                        supports.push(*support);
+                        supports.len() - 1
+                    });
+                    ties.push((pin.index, pin.point, slot));
+                }
+                (Some(_), None) => continue
+            }
+            attached[pin.index] = true;
+        }
+        for &(index, _) in &pinned {
+            inv_masses[index] = 0.0;
+        }
+
+        let particles = self.positions.iter_mut().zip(&mut self.previous).zip(&mut self.velocities).zip(&inv_masses);
+        for (((position, previous), velocity), &inv_mass) in particles {
+            *previous = *position;
+            if inv_mass > 0.0 {
+                *velocity += gravity * h;
+                *position += *velocity * h;
+            }
+        }
+
+        let glide = h / (h + lag);
+        for &(index, target) in &pinned {
+            self.positions[index] = self.positions[index].lerp(target, glide);
+        }
+
+        let mut reactions = vec![Reaction::default(); supports.len()];
+        self.solve_links(&inv_masses, h);
+        self.solve_ties(&ties, &supports, &mut reactions, lag, h);
+        if self.self_collision {
+            self.solve_self_collision(&inv_masses);
+        }
+        self.collide(&attached, boundaries, lag, h, &mut reactions);
+
+        let keep = (1.0 - self.damping * h).max(0.0);
+        for ((velocity, position), previous) in self.velocities.iter_mut().zip(&self.positions).zip(&self.previous) {
+            *velocity = (*position - *previous) / h * keep;
+        }
+
+        supports.into_iter().zip(reactions).collect()
+    }
+
+    /// One XPBD pass over neighbors, then over every other particle when bending is resisted;
+    fn solve_links(&mut self, inv_masses: &[f32], h: f32) {
+        let (segments, spacing, compliance) = (self.segments(), self.spacing, self.compliance);
+        let links = (0..segments).map(|i| (i, i + 1, spacing, compliance));
+        let bends = self.bend_compliance.into_iter()
+            .flat_map(|compliance| (0..segments.saturating_sub(1)).map(move |i| (i, i + 2, spacing * 2.0, compliance)));
+
+        for (a, b, rest, compliance) in links.chain(bends) {
+            let (wa, wb) = (inv_masses[a], inv_masses[b]);
+            let offset = self.positions[a] - self.positions[b];
+            let length = offset.length();
+            if wa + wb == 0.0 || length <= f32::EPSILON {
+                continue;
+            }
+
+            let alpha = compliance / (h * h);
+            let lambda = -(length - rest) / (wa + wb + alpha);
+            let correction = offset / length * lambda;
+            self.positions[a] += correction * wa;
+            self.positions[b] -= correction * wb;
+        }
+    }
+
+    /// Pull tied particles onto their body points, dynamic bodies take their share by effective mass;
+    fn solve_ties(&mut self, ties: &[(usize, Vec3, usize)], supports: &[Support], reactions: &mut [Reaction], lag: f32, h: f32) {
+        let w = 1.0 / self.particle_mass;
+        for &(index, local, slot) in ties {
+            let (support, pull) = (&supports[slot], &mut reactions[slot]);
+            let target = pull.pose(support, lag).transform_point(local);
+            let offset = target - self.positions[index];
+            self.positions[index] += pull.exchange(support, lag, target, w, offset, h);
+        }
+    }
+
+    /// Push apart segments farther apart along the rope than its thickness, split by how close each contact is to the segment ends;
+    fn solve_self_collision(&mut self, inv_masses: &[f32]) {
+        let centers: Vec<Vec3> = self.positions.windows(2).map(|pair| (pair[0] + pair[1]) * 0.5).collect();
+        self.grid.set_cell_size(self.spacing + self.radius * 2.0);
+        self.grid.rebuild(&centers);
+
+        // Segments closer than the gap along the rope touch at rest, they're left to the links;
+        let gap = self.radius * 2.0;
+        let apart = 1 + (gap / self.spacing) as usize;
+        let mut pairs = Vec::new();
+        for (i, center) in centers.iter().enumerate() {
+            self.grid.query(*center, self.spacing + gap, &mut |j| {
+                if j > i + apart {
+                    pairs.push((i, j));
+                }
+            });
+        }
+
+        for (i, j) in pairs {
+            let [a, b, c, d] = [i, i + 1, j, j + 1];
+            let [pa, pb, pc, pd] = [a, b, c, d].map(|k| self.positions[k]);
+            let (s, t) = closest_segment_segment(pa, pb, pc, pd);
+            let offset = pa.lerp(pb, s) - pc.lerp(pd, t);
+            let distance = offset.length();
+            if distance >= gap || distance <= f32::EPSILON {
+                continue;
+            }
+
+            let shares = [1.0 - s, s, -(1.0 - t), -t];
+            let weight: f32 = [a, b, c, d].iter().zip(shares).map(|(&k, share)| share * share * inv_masses[k]).sum();
+            if weight <= f32::EPSILON {
+                continue;
+            }
+
+            let normal = offset / distance;
+            let lambda = (gap - distance) / weight;
+            for (&k, share) in [a, b, c, d].iter().zip(shares) {
+                self.positions[k] += normal * (lambda * share * inv_masses[k]);
+            }
+        }
+    }
+
+    /// Push particles out of colliders posed `lag` seconds before the step's end && cancel part of their sliding;
+    /// Tied && pinned particles are left to their pins, dynamic bodies take their share of the rest;
+    fn collide(&mut self, attached: &[bool], boundaries: &[Boundary], lag: f32, h: f32, reactions: &mut [Reaction]) {
+        let w = 1.0 / self.particle_mass;
+
+        let particles = self.positions.iter_mut().zip(&self.previous).zip(attached);
+        for ((position, previous), _) in particles.filter(|(_, attached)| !**attached) {
+            for (boundary, reaction) in boundaries.iter().zip(reactions.iter_mut()) {
+                let support = &boundary.support;
+                let iso = reaction.pose(support, lag);
+                let (distance, normal) = boundary.shape.distance(&iso, *position);
+                if distance >= self.radius {
+                    continue;
+                }
+
+                *position += reaction.exchange(support, lag, *position, w, normal * (self.radius - distance), h);
+
+                // Sliding relative to the body's surface, ropes ride along moving bodies;
+                let moved = *position - *previous - reaction.velocity_at(support, lag, *position) * h;
+                let sliding = moved - normal * moved.dot(normal);
+                *position += reaction.exchange(support, lag, *position, w, -sliding * self.friction, h);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::super::shape::{Isometry, Shape};
+    use super::super::{Collider, RigidBody, World};
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+    const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+
+    fn run(rope: &mut Rope, steps: usize, boundaries: &[Boundary]) {
+        let anchors = vec![None; rope.pins().len()];
+        let h = DT / rope.substeps() as f32;
+        for _ in 0..steps {
+            for substep in (0..rope.substeps()).rev() {
+                rope.substep(GRAVITY, h, h * substep as f32, &anchors, boundaries);
+            }
+        }
+    }
+
+    #[test]
+    fn hanging_rope_keeps_its_length() {
+        let mut rope = Rope::new(Vec3::Y * 2.0, Vec3::new(2.0, 2.0, 0.0), 20);
+        rope.pin(0);
+        run(&mut rope, 180, &[]);
+
+        // Swings down from horizontal, the pin holds && the links barely stretch;
+        assert_eq!(rope.positions()[0], Vec3::Y * 2.0);
+        assert!((rope.length() - 2.0).abs() < 0.02, "{}", rope.length());
+        assert!(rope.positions()[rope.last()].y < 1.0);
+
+        // Segments thinner than they are apart still leave their neighbors alone;
+        let mut fine = Rope::new(Vec3::Y * 2.0, Vec3::new(2.0, 2.0, 0.0), 40)
+            .with_radius(0.04)
+            .with_self_collision(true);
+        fine.pin(0);
+        run(&mut fine, 180, &[]);
+        assert!((fine.length() - 2.0).abs() < 0.02, "{}", fine.length());
+    }
+
+    #[test]
+    fn slack_bridge_sags_between_its_ends() {
+        let mut rope = Rope::new(Vec3::ZERO, Vec3::X * 4.0, 16).with_length(5.0).with_damping(2.0);
+        assert!((rope.length() - 5.0).abs() < 1e-3, "{}", rope.length());
+        rope.pin(0);
+        rope.pin(rope.last());
+        run(&mut rope, 240, &[]);
+
+        // Settles from the V into a catenary, its lowest point sagging about 1.32 below the middle of the span;
+        let lowest = rope.positions().iter().map(|p| p.y).fold(0.0, f32::min);
+        let middle = rope.positions()[8];
+        assert!((middle.x - 2.0).abs() < 0.01 && middle.y < lowest + 0.05, "{middle}");
+        assert!((lowest + 1.32).abs() < 0.03, "{lowest}");
+        assert!((rope.length() - 5.0).abs() < 0.01, "{}", rope.length());
+    }
+
+    #[test]
+    fn self_collision_keeps_a_coil_apart() {
+        let floor = Shape::plane(Vec3::Y, 0.0);
+        let boundaries = [Boundary::fixed(&floor, Isometry::default())];
+
+        // Dropped as a loose helix, its turns stack up instead of passing through each other;
+        let pile = |self_collision: bool| {
+            let mut rope = Rope::new(Vec3::ZERO, Vec3::Y * 3.0, 60)
+                .with_radius(0.05)
+                .with_self_collision(self_collision);
+            for i in 0..=60 {
+                let angle = i as f32 * 0.2474;
+                rope.set_position(i, Vec3::new(angle.cos() * 0.2, 0.3 + i as f32 * 0.008, angle.sin() * 0.2));
+            }
+            run(&mut rope, 240, &boundaries);
+            rope.positions().iter().map(|p| p.y).fold(0.0, f32::max)
+        };
+
+        // Flat on the floor when passing through, a second layer on top of the first when not;
+        let (apart, through) = (pile(true), pile(false));
+        assert!(through < 0.06, "{through}");
+        assert!(apart > 0.14, "{apart}");
+    }
+
+    #[test]
+    fn rope_drapes_over_a_fixed_bar() {
+        let bar = Shape::capsule(1.0, 0.1);
+        let iso = Isometry::new(Vec3::ZERO, Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+        let boundaries = [Boundary::fixed(&bar, iso)];
+
+        let mut rope = Rope::new(Vec3::new(-1.0, 0.2, 0.0), Vec3::new(1.0, 0.2, 0.0), 20).with_damping(3.0);
+        run(&mut rope, 240, &boundaries);
+
+        // Hangs evenly down both sides of the bar;
+        for position in rope.positions() {
+            let from_axis = Vec3::new(position.x, position.y, 0.0).length();
+            assert!(from_axis > 0.1 + rope.radius() - 0.01, "inside the bar at {position}");
+        }
+        assert!(rope.positions()[10].y > 0.1);
+        assert!(rope.positions()[0].y < -0.7 && rope.positions()[rope.last()].y < -0.7);
+    }
+
+    #[test]
+    fn rope_carries_a_dynamic_load() {
+        let mut world = World::new();
+        let hook = world.add_body(RigidBody::kinematic().with_position(Vec3::Y * 4.0));
+        let load = world.add_body(RigidBody::new()
+            .with_position(Vec3::Y * 1.8)
+            .with_mass(5.0)
+            .with_collider(Collider::new(Shape::sphere(0.2))));
+
+        // Five times heavier than the rope, tied to the top of the load;
+        let mut rope = Rope::new(Vec3::Y * 4.0, Vec3::Y * 2.0, 12);
+        rope.attach(0, hook, Vec3::ZERO);
+        rope.attach(rope.last(), load, Vec3::Y * 0.2);
+        let handle = world.add_rope(rope);
+
+        // Hangs at the rope's length, barely stretched by the weight;
+        for step in 0..120 {
+            world.step(DT);
+            let height = world.body(load).unwrap().position().y;
+            assert!(step < 60 || (height - 1.8).abs() < 0.015, "{height}");
+        }
+
+        let load_body = world.body(load).unwrap();
+        let rope = world.rope(handle).unwrap();
+        assert!(rope.positions()[0].distance(Vec3::Y * 4.0) < 1e-4);
+        assert!(rope.positions()[rope.last()].distance(load_body.isometry().transform_point(Vec3::Y * 0.2)) < 0.01);
+
+        // Hauling the hook up lifts the load with it;
+        world.body_mut(hook).unwrap().set_linear_velocity(Vec3::Y);
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        let height = world.body(load).unwrap().position().y;
+        assert!((height - 2.8).abs() < 0.03, "{height}");
+
+        // A rope added in the freed slot isn't reachable through the old handle;
+        assert!(world.remove_rope(handle).is_some());
+        let next = world.add_rope(Rope::new(Vec3::ZERO, Vec3::X, 2));
+        assert_eq!(next.index(), handle.index());
+        assert!(world.rope(handle).is_none() && world.rope(next).is_some());
+    }
+
+    #[test]
+    fn planks_tied_along_two_ropes_settle() {
+        let mut world = World::new();
+        let mut ropes: Vec<Rope> = [-0.6, 0.6].map(|z| {
+            let mut rope = Rope::new(Vec3::new(-0.5, 3.0, z), Vec3::new(0.5, 3.0, z), 5).with_length(1.05);
+            rope.pin(0);
+            rope.pin(rope.last());
+            rope
+        }).into();
+
+        // Each plank spans one link of both ropes && is tied at its four corners;
+        let half = ropes[0].rest_length() / 10.0;
+        let mut planks = Vec::new();
+        for index in [1, 3] {
+            let [a, b] = [index, index + 1].map(|k| (ropes[0].positions()[k] + ropes[1].positions()[k]) * 0.5);
+            let plank = world.add_body(RigidBody::new()
+                .with_position((a + b) * 0.5)
+                .with_orientation(Quat::from_rotation_arc(Vec3::X, (b - a).normalize()))
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(half, 0.03, 0.5))))
+                .with_density(100.0));
+            for (rope, z) in ropes.iter_mut().zip([-0.6, 0.6]) {
+                rope.attach(index, plank, Vec3::new(-half, 0.0, z));
+                rope.attach(index + 1, plank, Vec3::new(half, 0.0, z));
+            }
+            planks.push((index, plank));
+        }
+        let handles: Vec<RopeHandle> = ropes.into_iter().map(|rope| world.add_rope(rope)).collect();
+
+        for _ in 0..240 {
+            world.step(DT);
+        }
+
+        // Ties on one body share its reaction, the planks come to rest where the ropes hold them;
+        for (index, plank) in planks {
+            let body = world.body(plank).unwrap();
+            assert!(body.linear_velocity().length() < 0.05, "{}", body.linear_velocity());
+            assert!(body.angular_velocity().length() < 0.3, "{}", body.angular_velocity());
+            for (handle, z) in handles.iter().zip([-0.6, 0.6]) {
+                let tied = world.rope(*handle).unwrap().positions()[index];
+                assert!(tied.distance(body.isometry().transform_point(Vec3::new(-half, 0.0, z))) < 0.01);
+            }
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use glam::{Quat, Vec3};
+
+use super::collision::{distance, penetration};
+use super::mesh::{HeightField, TriMesh, Triangles};
+
+/// Rigid transform: rotation, then translation;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Isometry {
+    pub position: Vec3,
+    pub rotation: Quat,
+}
+
+impl Default for Isometry {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Isometry {
+    pub const IDENTITY: Self = Self { position: Vec3::ZERO, rotation: Quat::IDENTITY };
+
+    pub fn new(position: Vec3, rotation: Quat) -> Self {
+        Self { position, rotation }
+    }
+
+    pub fn from_position(position: Vec3) -> Self {
+        Self { position, rotation: Quat::IDENTITY }
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.rotation * point + self.position
+    }
+
+    pub fn inverse_transform_point(&self, point: Vec3) -> Vec3 {
+        self.rotation.inverse() * (point - self.position)
+    }
+
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.rotation * vector
+    }
+
+    pub fn inverse_transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.rotation.inverse() * vector
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        Self { position: rotation * -self.position, rotation }
+    }
+}
+
+/// Composition, `a * b` applies b first;
+impl std::ops::Mul for Isometry {
+    type Output = Isometry;
+
+    fn mul(self, other: Isometry) -> Isometry {
+        Isometry::new(self.transform_point(other.position), (self.rotation * other.rotation).normalize())
+    }
+}
+
+/// Axis aligned bounding box;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+/// Half size of unbounded boxes (planes), small enough for surface areas to stay finite;
+const UNBOUNDED: f32 = 1e15;
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Vec3, half_extents: Vec3) -> Self {
+        Self { min: center - half_extents, max: center + half_extents }
+    }
+
+    pub fn unbounded() -> Self {
+        Self::from_center(Vec3::ZERO, Vec3::splat(UNBOUNDED))
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item=Vec3>) -> Self {
+        let mut aabb = Self::new(Vec3::splat(f32::MAX), Vec3::splat(f32::MIN));
+        for point in points {
+            aabb.min = aabb.min.min(point);
+            aabb.max = aabb.max.max(point);
+        }
+
+        aabb
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.min).all() && other.max.cmple(self.max).all()
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.min.cmple(point).all() && point.cmple(self.max).all()
+    }
+
+    pub fn merged(&self, other: &Aabb) -> Aabb {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    pub fn expanded(&self, margin: f32) -> Aabb {
+        Self::new(self.min - Vec3::splat(margin), self.max + Vec3::splat(margin))
+    }
+
+    /// Bounds of the box moved by the transform;
+    pub fn transformed(&self, iso: &Isometry) -> Aabb {
+        Self::from_points((0..8).map(|i| iso.transform_point(Vec3::new(
+            match i & 1 { 0 => self.min.x, _ => self.max.x },
+            match i & 2 { 0 => self.min.y, _ => self.max.y },
+            match i & 4 { 0 => self.min.z, _ => self.max.z },
+        ))))
+    }
+
+    /// Entry fraction of the segment [origin; origin + translation], zero when starting inside;
+    pub fn ray_fraction(&self, origin: Vec3, translation: Vec3) -> Option<f32> {
+        let mut enter = 0.0f32;
+        let mut exit = 1.0f32;
+
+        for axis in 0..3 {
+            let (o, d) = (origin[axis], translation[axis]);
+            if d.abs() <= f32::EPSILON {
+                if o < self.min[axis] || o > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let (t1, t2) = ((self.min[axis] - o) / d, (self.max[axis] - o) / d);
+            enter = enter.max(t1.min(t2));
+            exit = exit.min(t1.max(t2));
+
+            if enter > exit {
+                return None;
+            }
+        }
+
+        Some(enter)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+}
+
+/// Collision shape in body local space;
+/// Capsules && cylinders are aligned with the local Y axis;
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Sphere { radius: f32 },
+    /// Oriented box;
+    Box { half_extents: Vec3 },
+    Capsule { half_height: f32, radius: f32 },
+    Cylinder { half_height: f32, radius: f32 },
+    /// Infinite plane, points with `normal.dot(p) <= offset` are inside;
+    Plane { normal: Vec3, offset: f32 },
+    /// Convex hull of a point cloud;
+    ConvexHull(ConvexHull),
+    /// Static triangle mesh for level geometry;
+    TriMesh(TriMesh),
+    /// Static regular grid terrain;
+    HeightField(HeightField),
+}
+
+/// Convex hull given by its points, interior points are allowed;
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvexHull {
+    points: Vec<Vec3>,
+}
+
+impl ConvexHull {
+    pub fn new(points: impl IntoIterator<Item=Vec3>) -> Self {
+        let points: Vec<Vec3> = points.into_iter().collect();
+        assert!(!points.is_empty(), "Convex hull needs at least one point");
+
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    pub fn support(&self, dir: Vec3) -> Vec3 {
+        self.points.iter()
+            .copied()
+            .max_by(|a, b| a.dot(dir).total_cmp(&b.dot(dir)))
+            .unwrap()
+    }
+
+    /// Signed distance from a local point, found with GJK && EPA;
+    pub fn local_distance(&self, point: Vec3) -> (f32, Vec3) {
+        if let Some((distance, _, closest)) = distance(&point, self) {
+            return (distance, (point - closest) / distance);
+        }
+
+        match penetration(&point, self) {
+            Some(pen) => (-pen.depth, -pen.normal),
+            None => (0.0, Vec3::Y)
+        }
+    }
+}
+
+impl Shape {
+    pub fn sphere(radius: f32) -> Self {
+        Self::Sphere { radius }
+    }
+
+    pub fn cuboid(half_extents: Vec3) -> Self {
+        Self::Box { half_extents }
+    }
+
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        Self::Capsule { half_height, radius }
+    }
+
+    pub fn cylinder(half_height: f32, radius: f32) -> Self {
+        Self::Cylinder { half_height, radius }
+    }
+
+    pub fn plane(normal: Vec3, offset: f32) -> Self {
+        Self::Plane { normal: normal.normalize(), offset }
+    }
+
+    pub fn convex_hull(points: impl IntoIterator<Item=Vec3>) -> Self {
+        Self::ConvexHull(ConvexHull::new(points))
+    }
+
+    pub fn tri_mesh(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> Self {
+        Self::TriMesh(TriMesh::new(vertices, indices))
+    }
+
+    pub fn height_field(heights: Vec<f32>, columns: usize, rows: usize, scale: Vec3) -> Self {
+        Self::HeightField(HeightField::new(heights, columns, rows, scale))
+    }
+
+    /// Triangle source of mesh shapes;
+    pub(super) fn triangles(&self) -> Option<&dyn Triangles> {
+        match self {
+            Shape::TriMesh(mesh) => Some(mesh),
+            Shape::HeightField(field) => Some(field),
+            _ => None
+        }
+    }
+
+    /// Farthest local point in the local direction;
+    /// Planes are unbounded, so the plane point closest to the origin is returned;
+    pub fn support(&self, dir: Vec3) -> Vec3 {
+        match self {
+            Shape::Sphere { radius } => dir.normalize_or_zero() * *radius,
+            Shape::Box { half_extents } => Vec3::new(
+                half_extents.x.copysign(dir.x),
+                half_extents.y.copysign(dir.y),
+                half_extents.z.copysign(dir.z),
+            ),
+            Shape::Capsule { half_height, radius } => {
+                Vec3::Y * half_height.copysign(dir.y) + dir.normalize_or_zero() * *radius
+            }
+            Shape::Cylinder { half_height, radius } => {
+                let radial = Vec3::new(dir.x, 0.0, dir.z).normalize_or_zero();
+                Vec3::Y * half_height.copysign(dir.y) + radial * *radius
+            }
+            Shape::Plane { normal, offset } => *normal * *offset,
+            Shape::ConvexHull(hull) => hull.support(dir),
+            Shape::TriMesh(mesh) => mesh.support(dir),
+            Shape::HeightField(field) => field.support(dir),
+        }
+    }
+
+    /// World space bounds;
+    pub fn aabb(&self, iso: &Isometry) -> Aabb {
+        match self {
+            Shape::Sphere { radius } => Aabb::from_center(iso.position, Vec3::splat(*radius)),
+            Shape::Box { half_extents } => {
+                let rotation = glam::Mat3::from_quat(iso.rotation);
+                let extents = Vec3::new(
+                    rotation.row(0).abs().dot(*half_extents),
+                    rotation.row(1).abs().dot(*half_extents),
+                    rotation.row(2).abs().dot(*half_extents),
+                );
+
+                Aabb::from_center(iso.position, extents)
+            }
+            Shape::Capsule { half_height, radius } => {
+                let axis = iso.transform_vector(Vec3::Y) * *half_height;
+                Aabb::from_center(iso.position, axis.abs() + Vec3::splat(*radius))
+            }
+            Shape::Cylinder { half_height, radius } => {
+                let axis = iso.transform_vector(Vec3::Y);
+                let disk = (Vec3::ONE - axis * axis).max(Vec3::ZERO);
+                let extents = axis.abs() * *half_height + Vec3::new(disk.x.sqrt(), disk.y.sqrt(), disk.z.sqrt()) * *radius;
+
+                Aabb::from_center(iso.position, extents)
+            }
+            Shape::Plane { .. } => Aabb::unbounded(),
+            Shape::ConvexHull(hull) => Aabb::from_points(hull.points().iter().map(|p| iso.transform_point(*p))),
+            Shape::TriMesh(mesh) => mesh.local_aabb().transformed(iso),
+            Shape::HeightField(field) => field.local_aabb().transformed(iso),
+        }
+    }
+
+    /// Signed distance from a local point to the surface && outward surface normal;
+    pub fn local_distance(&self, point: Vec3) -> (f32, Vec3) {
+        match self {
+            Shape::Sphere { radius } => {
+                let len = point.length();
+                let normal = match len > f32::EPSILON {
+                    true => point / len,
+                    false => Vec3::Y
+                };
+
+                (len - radius, normal)
+            }
+            Shape::Box { half_extents } => {
+                let q = point.abs() - *half_extents;
+                if q.max_element() > 0.0 {
+                    let closest = point.clamp(-*half_extents, *half_extents);
+                    let delta = point - closest;
+                    let len = delta.length();
+                    return (len, delta / len);
+                }
+
+                // Inside: push out through the nearest face;
+                let axis = match (q.x >= q.y, q.x >= q.z, q.y >= q.z) {
+                    (true, true, _) => 0,
+                    (false, _, true) => 1,
+                    _ => 2
+                };
+
+                let mut normal = Vec3::ZERO;
+                normal[axis] = 1.0f32.copysign(point[axis]);
+                (q[axis], normal)
+            }
+            Shape::Capsule { half_height, radius } => {
+                let on_axis = Vec3::Y * point.y.clamp(-half_height, *half_height);
+                let delta = point - on_axis;
+                let len = delta.length();
+                let normal = match len > f32::EPSILON {
+                    true => delta / len,
+                    false => Vec3::X
+                };
+
+                (len - radius, normal)
+            }
+            Shape::Cylinder { half_height, radius } => {
+                let radial = Vec3::new(point.x, 0.0, point.z);
+                let radial_len = radial.length();
+                let radial_dir = match radial_len > f32::EPSILON {
+                    true => radial / radial_len,
+                    false => Vec3::X
+                };
+
+                let dr = radial_len - radius;
+                let dy = point.y.abs() - half_height;
+
+                if dr > 0.0 || dy > 0.0 {
+                    let closest = radial_dir * radial_len.min(*radius)
+                        + Vec3::Y * point.y.clamp(-half_height, *half_height);
+                    let delta = point - closest;
+                    let len = delta.length();
+                    return (len, delta / len);
+                }
+
+                match dr > dy {
+                    true => (dr, radial_dir),
+                    false => (dy, Vec3::Y * 1.0f32.copysign(point.y))
+                }
+            }
+            Shape::Plane { normal, offset } => (normal.dot(point) - offset, *normal),
+            Shape::ConvexHull(hull) => hull.local_distance(point),
+            Shape::TriMesh(mesh) => mesh.local_distance(point),
+            Shape::HeightField(field) => field.local_distance(point),
+        }
+    }
+
+    /// Signed distance from a world point && outward world normal;
+    pub fn distance(&self, iso: &Isometry, point: Vec3) -> (f32, Vec3) {
+        let (distance, normal) = self.local_distance(iso.inverse_transform_point(point));
+        (distance, iso.transform_vector(normal))
+    }
+
+    /// Farthest distance of the surface from the local origin;
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            Shape::Sphere { radius } => *radius,
+            Shape::Box { half_extents } => half_extents.length(),
+            Shape::Capsule { half_height, radius } => half_height + radius,
+            Shape::Cylinder { half_height, radius } => half_height.hypot(*radius),
+            Shape::Plane { .. } => f32::INFINITY,
+            Shape::ConvexHull(hull) => hull.points().iter().map(|p| p.length()).fold(0.0, f32::max),
+            Shape::TriMesh(mesh) => farthest_corner(&mesh.local_aabb()),
+            Shape::HeightField(field) => farthest_corner(&field.local_aabb()),
+        }
+    }
+
+    pub fn is_convex(&self) -> bool {
+        !matches!(self, Shape::Plane { .. } | Shape::TriMesh(_) | Shape::HeightField(_))
+    }
+}
+
+fn farthest_corner(aabb: &Aabb) -> f32 {
+    aabb.min.abs().max(aabb.max.abs()).length()
+}
@@ -0,0 +1,165 @@
+use std::marker::PhantomData;
+
+/// Handle into `Slots`, a slot index && the generation of the value it was given for;
+pub(super) trait SlotHandle: Copy {
+    fn from_slot(index: usize, generation: u32) -> Self;
+
+    fn slot(&self) -> (usize, u32);
+}
+
+/// Values owned by the world behind generational handles;
+/// Emptied slots are reused one generation on, so old handles find nothing instead of the new value;
+pub(super) struct Slots<H, T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    handle: PhantomData<H>,
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+impl<H: SlotHandle, T> Slots<H, T> {
+    pub(super) fn new() -> Self {
+        Self { slots: Vec::new(), free: Vec::new(), handle: PhantomData }
+    }
+
+    pub(super) fn insert(&mut self, value: T) -> H {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.value = Some(value);
+                H::from_slot(index, slot.generation)
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, value: Some(value) });
+                H::from_slot(self.slots.len() - 1, 0)
+            }
+        }
+    }
+
+    pub(super) fn remove(&mut self, handle: H) -> Option<T> {
+        let (index, generation) = handle.slot();
+        let slot = self.slots.get_mut(index).filter(|slot| slot.generation == generation)?;
+        let value = slot.value.take();
+        if value.is_some() {
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free.push(index);
+        }
+
+        value
+    }
+
+    pub(super) fn get(&self, handle: H) -> Option<&T> {
+        let (index, generation) = handle.slot();
+        self.slots.get(index).filter(|slot| slot.generation == generation)?.value.as_ref()
+    }
+
+    pub(super) fn get_mut(&mut self, handle: H) -> Option<&mut T> {
+        let (index, generation) = handle.slot();
+        self.slots.get_mut(index).filter(|slot| slot.generation == generation)?.value.as_mut()
+    }
+
+    /// Value in a slot whatever its generation, for indices taken from current handles;
+    pub(super) fn at(&self, index: usize) -> Option<&T> {
+        self.slots.get(index)?.value.as_ref()
+    }
+
+    pub(super) fn at_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots.get_mut(index)?.value.as_mut()
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item=(H, &T)> {
+        self.slots.iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.value.as_ref().map(|value| (H::from_slot(i, slot.generation), value)))
+    }
+
+    pub(super) fn iter_mut(&mut self) -> impl Iterator<Item=(H, &mut T)> {
+        self.slots.iter_mut()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.value.as_mut().map(|value| (H::from_slot(i, slot.generation), value)))
+    }
+
+    pub(super) fn values(&self) -> impl Iterator<Item=&T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub(super) fn values_mut(&mut self) -> impl Iterator<Item=&mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    /// Empty the slots whose value the predicate rejects;
+    pub(super) fn retain(&mut self, mut keep: impl FnMut(H, &mut T) -> bool) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let Some(value) = &mut slot.value else { continue };
+            if !keep(H::from_slot(index, slot.generation), value) {
+                slot.value = None;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index);
+            }
+        }
+    }
+
+    /// Values alive;
+    pub(super) fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Slots ever used, empty or not, every handle index is below it;
+    pub(super) fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+impl<H: SlotHandle, T> Default for Slots<H, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Handle(usize, u32);
+
+    impl SlotHandle for Handle {
+        fn from_slot(index: usize, generation: u32) -> Self {
+            Self(index, generation)
+        }
+
+        fn slot(&self) -> (usize, u32) {
+            (self.0, self.1)
+        }
+    }
+
+    #[test]
+    fn reused_slots_leave_old_handles_stale() {
+        let mut slots: Slots<Handle, &str> = Slots::new();
+        let old = slots.insert("old");
+        assert_eq!(slots.remove(old), Some("old"));
+
+        let new = slots.insert("new");
+        assert_eq!((new.0, new.1), (old.0, old.1 + 1));
+        assert_eq!(slots.get(old), None);
+        assert_eq!(slots.remove(old), None);
+        assert_eq!(slots.get(new), Some(&"new"));
+        assert_eq!(slots.at(old.0), Some(&"new"));
+    }
+
+    #[test]
+    fn iteration_skips_empty_slots() {
+        let mut slots: Slots<Handle, usize> = Slots::new();
+        let handles: Vec<Handle> = (0..5).map(|i| slots.insert(i)).collect();
+        slots.remove(handles[1]);
+        slots.retain(|_, value| *value != 3);
+
+        assert_eq!(slots.iter().map(|(h, v)| (h, *v)).collect::<Vec<_>>(), vec![(handles[0], 0), (handles[2], 2), (handles[4], 4)]);
+        assert_eq!((slots.len(), slots.capacity()), (3, 5));
+        assert_eq!(slots.get(handles[3]), None);
+        assert_eq!(slots.get(Handle(9, 0)), None);
+    }
+}
@@ -0,0 +1,577 @@
+use std::collections::{BTreeMap, HashMap};
+
+use glam::{Mat3, Quat, Vec3};
+
+use super::shape::Aabb;
+use super::slot::SlotHandle;
+use super::{Boundary, Reaction, Support};
+
+/// Soft body slot inside the physics world && its generation;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SoftBodyHandle(pub(super) usize, pub(super) u32);
+
+impl SoftBodyHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.1
+    }
+}
+
+impl SlotHandle for SoftBodyHandle {
+    fn from_slot(index: usize, generation: u32) -> Self {
+        Self(index, generation)
+    }
+
+    fn slot(&self) -> (usize, u32) {
+        (self.0, self.1)
+    }
+}
+
+/// Distance constraint along a mesh edge;
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    a: usize,
+    b: usize,
+    rest: f32,
+}
+
+/// Volume constraint of a surface triangle && the centroid of the surface;
+#[derive(Debug, Clone, Copy)]
+struct Tetrahedron {
+    corners: [usize; 3],
+    rest: f32,
+}
+
+impl Tetrahedron {
+    fn volume(center: Vec3, [a, b, c]: [Vec3; 3]) -> f32 {
+        (a - center).cross(b - center).dot(c - center) / 6.0
+    }
+}
+
+/// Closed surface of particles, each triangle && the surface's centroid make a tetrahedron;
+/// Edges keep their length, tetrahedra keep their volume && shape matching pulls toward the rest shape;
+/// The centroid follows the particles instead of being one, moving it leaves the total volume unchanged;
+/// The mesh must be star shaped around its vertex centroid, like balls && boxes;
+/// The world steps soft bodies after rigid bodies, they collide with every solid collider && push dynamic bodies back;
+#[derive(Debug, Clone)]
+pub struct SoftBody {
+    positions: Vec<Vec3>,
+    previous: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    particle_mass: f32,
+    edges: Vec<Edge>,
+    tetrahedra: Vec<Tetrahedron>,
+    triangles: Vec<[u32; 3]>,
+    /// Rest positions around the rest centroid && the rotation shape matching found last;
+    rest: Vec<Vec3>,
+    rotation: Quat,
+
+    /// Inverse stiffness of each constraint, zero is rigid;
+    edge_compliance: f32,
+    volume_compliance: f32,
+    /// None leaves the rest shape out;
+    shape_compliance: Option<f32>,
+    /// Collision distance from colliders;
+    thickness: f32,
+    friction: f32,
+    /// Share of the velocity lost per second;
+    damping: f32,
+    substeps: u32,
+}
+
+impl SoftBody {
+    /// Closed triangle mesh in world space, counter clockwise seen from outside;
+    pub fn new(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> Self {
+        let positions = vertices;
+        let center = centroid(&positions);
+
+        let mut pairs: Vec<(usize, usize)> = indices.iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .map(|(a, b)| (a.min(b) as usize, a.max(b) as usize))
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        let edges = pairs.into_iter()
+            .map(|(a, b)| Edge { a, b, rest: positions[a].distance(positions[b]) })
+            .collect();
+
+        let tetrahedra = indices.iter()
+            .map(|&[a, b, c]| {
+                let corners = [a, b, c].map(|i| i as usize);
+                Tetrahedron { corners, rest: Tetrahedron::volume(center, corners.map(|i| positions[i])) }
+            })
+            .collect();
+
+        let count = positions.len();
+        Self {
+            rest: positions.iter().map(|p| *p - center).collect(),
+            previous: positions.clone(),
+            positions,
+            velocities: vec![Vec3::ZERO; count],
+            particle_mass: 1.0 / count as f32,
+            edges,
+            tetrahedra,
+            triangles: indices,
+            rotation: Quat::IDENTITY,
+            edge_compliance: 1e-3,
+            volume_compliance: 0.0,
+            shape_compliance: None,
+            thickness: 0.02,
+            friction: 0.4,
+            damping: 0.5,
+            substeps: 10,
+        }
+    }
+
+    /// Icosphere, each subdivision splits every triangle in four;
+    pub fn ball(center: Vec3, radius: f32, subdivisions: u32) -> Self {
+        let t = (1.0 + 5f32.sqrt()) * 0.5;
+        let mut vertices: Vec<Vec3> = [
+            (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+            (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+            (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+        ].map(|(x, y, z)| Vec3::new(x, y, z).normalize()).to_vec();
+
+        let mut indices: Vec<[u32; 3]> = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut middles = HashMap::new();
+            let mut middle = |a: u32, b: u32| *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                vertices.push((vertices[a as usize] + vertices[b as usize]).normalize());
+                vertices.len() as u32 - 1
+            });
+
+            indices = indices.into_iter()
+                .flat_map(|[a, b, c]| {
+                    let (ab, bc, ca) = (middle(a, b), middle(b, c), middle(c, a));
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        Self::new(vertices.into_iter().map(|v| center + v * radius).collect(), indices)
+    }
+
+    /// Box with every face split into `segments` by `segments` quads;
+    pub fn cuboid(center: Vec3, half_extents: Vec3, segments: u32) -> Self {
+        let n = segments.max(1);
+        let mut lattice = BTreeMap::new();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // Faces are grids over the two axes following their normal axis, so u cross v points out on the positive side;
+        for axis in 0..3 {
+            for positive in [false, true] {
+                let mut vertex = |u: u32, v: u32| {
+                    let mut key = [0; 3];
+                    key[axis] = match positive {
+                        true => n,
+                        false => 0
+                    };
+                    key[(axis + 1) % 3] = u;
+                    key[(axis + 2) % 3] = v;
+
+                    *lattice.entry(key).or_insert_with(|| {
+                        let unit = Vec3::from_array(key.map(|k| k as f32 * 2.0 / n as f32 - 1.0));
+                        vertices.push(center + half_extents * unit);
+                        vertices.len() as u32 - 1
+                    })
+                };
+
+                for u in 0..n {
+                    for v in 0..n {
+                        let [a, b, c, d] = [vertex(u, v), vertex(u + 1, v), vertex(u + 1, v + 1), vertex(u, v + 1)];
+                        match positive {
+                            true => indices.extend([[a, b, c], [a, c, d]]),
+                            false => indices.extend([[a, c, b], [a, d, c]])
+                        }
+                    }
+                }
+            }
+        }
+
+        Self::new(vertices, indices)
+    }
+
+    /// Total mass in kilograms, spread evenly over the particles;
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.particle_mass = mass.max(f32::EPSILON) / self.positions.len() as f32;
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec3) -> Self {
+        self.velocities.fill(velocity);
+        self
+    }
+
+    /// Higher lets the surface stretch && wobble, zero keeps every edge rigid;
+    pub fn with_edge_compliance(mut self, compliance: f32) -> Self {
+        self.edge_compliance = compliance.max(0.0);
+        self
+    }
+
+    /// Higher lets the body lose volume as it squashes, zero keeps it;
+    pub fn with_volume_compliance(mut self, compliance: f32) -> Self {
+        self.volume_compliance = compliance.max(0.0);
+        self
+    }
+
+    /// Pull every particle toward the best rotated rest shape, lower recovers faster;
+    pub fn with_shape_matching(mut self, compliance: f32) -> Self {
+        self.shape_compliance = Some(compliance.max(0.0));
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness.max(0.0);
+        self
+    }
+
+    /// Share of the sliding motion lost against colliders;
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.max(0.0);
+        self
+    }
+
+    /// More substeps stiffen the constraints at a linear cost;
+    pub fn with_substeps(mut self, substeps: u32) -> Self {
+        self.substeps = substeps.max(1);
+        self
+    }
+
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.velocities
+    }
+
+    /// Surface triangles, indices into the positions;
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    /// Area weighted vertex normals of the current surface;
+    pub fn normals(&self) -> Vec<Vec3> {
+        let mut normals = vec![Vec3::ZERO; self.positions.len()];
+        for &[a, b, c] in &self.triangles {
+            let [a, b, c] = [a, b, c].map(|i| i as usize);
+            let normal = (self.positions[b] - self.positions[a]).cross(self.positions[c] - self.positions[a]);
+            for i in [a, b, c] {
+                normals[i] += normal;
+            }
+        }
+
+        normals.into_iter().map(|n| n.normalize_or_zero()).collect()
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.particle_mass * self.positions.len() as f32
+    }
+
+    pub fn center_of_mass(&self) -> Vec3 {
+        centroid(&self.positions)
+    }
+
+    pub fn linear_velocity(&self) -> Vec3 {
+        self.velocities.iter().sum::<Vec3>() / self.velocities.len() as f32
+    }
+
+    /// Enclosed volume of the current surface;
+    pub fn volume(&self) -> f32 {
+        let center = self.center_of_mass();
+        self.tetrahedra.iter().map(|tet| Tetrahedron::volume(center, tet.corners.map(|i| self.positions[i]))).sum()
+    }
+
+    pub fn rest_volume(&self) -> f32 {
+        self.tetrahedra.iter().map(|tet| tet.rest).sum()
+    }
+
+    /// Move a particle, like grabbing it with the mouse;
+    pub fn set_position(&mut self, index: usize, position: Vec3) {
+        self.positions[index] = position;
+        self.previous[index] = position;
+        self.velocities[index] = Vec3::ZERO;
+    }
+
+    /// Shift every particle, keeping the shape && velocities;
+    pub fn translate(&mut self, offset: Vec3) {
+        for (position, previous) in self.positions.iter_mut().zip(&mut self.previous) {
+            *position += offset;
+            *previous += offset;
+        }
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vec3) {
+        self.velocities.fill(velocity);
+    }
+
+    /// Bounds of every particle;
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.positions.iter().copied()).expanded(self.thickness)
+    }
+
+    /// Farthest a particle may move in `dt`, used to gather colliders ahead of stepping;
+    pub(super) fn reach(&self, gravity: Vec3, dt: f32) -> f32 {
+        let speed = self.velocities.iter().map(|v| v.length()).fold(0.0, f32::max);
+        (speed + gravity.length() * dt) * dt + self.thickness
+    }
+
+    /// Advance by `dt`, returns what the contacts did to each boundary's body;
+    pub(super) fn step(&mut self, gravity: Vec3, dt: f32, boundaries: &[Boundary]) -> Vec<(Support, Reaction)> {
+        let mut reactions = vec![Reaction::default(); boundaries.len()];
+
+        let h = dt / self.substeps as f32;
+        for substep in 1..=self.substeps {
+            for ((position, previous), velocity) in self.positions.iter_mut().zip(&mut self.previous).zip(&mut self.velocities) {
+                *previous = *position;
+                *velocity += gravity * h;
+                *position += *velocity * h;
+            }
+
+            // Pushed bodies keep moving with the velocity they were given;
+            for reaction in &mut reactions {
+                reaction.advance(h);
+            }
+
+            self.solve_edges(h);
+            self.solve_volumes(h);
+            if let Some(compliance) = self.shape_compliance {
+                self.match_shape(compliance, h);
+            }
+
+            let lag = dt * (1.0 - substep as f32 / self.substeps as f32);
+            self.collide(boundaries, lag, h, &mut reactions);
+
+            let keep = (1.0 - self.damping * h).max(0.0);
+            for ((velocity, position), previous) in self.velocities.iter_mut().zip(&self.positions).zip(&self.previous) {
+                *velocity = (*position - *previous) / h * keep;
+            }
+        }
+
+        boundaries.iter().map(|boundary| boundary.support).zip(reactions).collect()
+    }
+
+    /// One XPBD pass, every particle weighs the same so the correction splits evenly;
+    fn solve_edges(&mut self, h: f32) {
+        let w = 1.0 / self.particle_mass;
+        let alpha = self.edge_compliance / (h * h);
+
+        for edge in &self.edges {
+            let offset = self.positions[edge.a] - self.positions[edge.b];
+            let length = offset.length();
+            if length <= f32::EPSILON {
+                continue;
+            }
+
+            let lambda = -(length - edge.rest) / (2.0 * w + alpha);
+            let correction = offset / length * (lambda * w);
+            self.positions[edge.a] += correction;
+            self.positions[edge.b] -= correction;
+        }
+    }
+
+    /// One XPBD pass over the signed tetrahedron volumes, the centroid is held for the pass;
+    /// The centroid's share of each correction is spread over every particle by shifting them back at the end;
+    fn solve_volumes(&mut self, h: f32) {
+        let w = 1.0 / self.particle_mass;
+        let alpha = self.volume_compliance / (h * h);
+        let o = self.center_of_mass();
+
+        for tet in &self.tetrahedra {
+            let [a, b, c] = tet.corners.map(|i| self.positions[i]);
+            let gradients = [(b - o).cross(c - o) / 6.0, (c - o).cross(a - o) / 6.0, (a - o).cross(b - o) / 6.0];
+
+            let weight: f32 = gradients.iter().map(|g| g.length_squared() * w).sum();
+            if weight + alpha <= f32::EPSILON {
+                continue;
+            }
+
+            let lambda = -(Tetrahedron::volume(o, [a, b, c]) - tet.rest) / (weight + alpha);
+            for (corner, gradient) in tet.corners.iter().zip(gradients) {
+                self.positions[*corner] += gradient * (lambda * w);
+            }
+        }
+
+        let drift = self.center_of_mass() - o;
+        for position in &mut self.positions {
+            *position -= drift;
+        }
+    }
+
+    /// Pull particles toward the rest shape turned by the rotation that fits the current one best;
+    fn match_shape(&mut self, compliance: f32, h: f32) {
+        let center = self.center_of_mass();
+        let moments = self.positions.iter()
+            .zip(&self.rest)
+            .fold(Mat3::ZERO, |sum, (position, rest)| {
+                let d = *position - center;
+                sum + Mat3::from_cols(d * rest.x, d * rest.y, d * rest.z)
+            });
+        self.rotation = extract_rotation(&moments, self.rotation);
+
+        let w = 1.0 / self.particle_mass;
+        let share = w / (w + compliance / (h * h));
+        for (position, rest) in self.positions.iter_mut().zip(&self.rest) {
+            let goal = center + self.rotation * *rest;
+            *position += (goal - *position) * share;
+        }
+    }
+
+    /// Push particles out of colliders posed `lag` seconds before the step's end && cancel part of their sliding;
+    /// Dynamic bodies take their share of both corrections by their effective mass at the contact;
+    fn collide(&mut self, boundaries: &[Boundary], lag: f32, h: f32, reactions: &mut [Reaction]) {
+        let w = 1.0 / self.particle_mass;
+
+        for (position, previous) in self.positions.iter_mut().zip(&self.previous) {
+            for (boundary, reaction) in boundaries.iter().zip(reactions.iter_mut()) {
+                let support = &boundary.support;
+                let iso = reaction.pose(support, lag);
+                let (distance, normal) = boundary.shape.distance(&iso, *position);
+                if distance >= self.thickness {
+                    continue;
+                }
+
+                *position += reaction.exchange(support, lag, *position, w, normal * (self.thickness - distance), h);
+
+                // Sliding relative to the body's surface, including what this step's contacts gave it;
+                let moved = *position - *previous - reaction.velocity_at(support, lag, *position) * h;
+                let sliding = moved - normal * moved.dot(normal);
+                *position += reaction.exchange(support, lag, *position, w, -sliding * self.friction, h);
+            }
+        }
+    }
+}
+
+/// Mean of the points;
+fn centroid(points: &[Vec3]) -> Vec3 {
+    points.iter().sum::<Vec3>() / points.len().max(1) as f32
+}
+
+/// Rotation part of a deformation matrix, refined from a guess;
+/// Iterative method of Müller et al., "A Robust Method to Extract the Rotational Part of Deformations";
+fn extract_rotation(matrix: &Mat3, guess: Quat) -> Quat {
+    let mut rotation = guess;
+    for _ in 0..8 {
+        let r = Mat3::from_quat(rotation);
+        let torque = r.x_axis.cross(matrix.x_axis) + r.y_axis.cross(matrix.y_axis) + r.z_axis.cross(matrix.z_axis);
+        let alignment = r.x_axis.dot(matrix.x_axis) + r.y_axis.dot(matrix.y_axis) + r.z_axis.dot(matrix.z_axis);
+
+        let omega = torque / (alignment.abs() + 1e-9);
+        let angle = omega.length();
+        if angle < 1e-6 {
+            break;
+        }
+
+        rotation = (Quat::from_axis_angle(omega / angle, angle) * rotation).normalize();
+    }
+
+    rotation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::shape::{Isometry, Shape};
+    use super::super::{Collider, RigidBody, WorldBuilder};
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+    const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+
+    #[test]
+    fn meshes_enclose_their_volume() {
+        let cube = SoftBody::cuboid(Vec3::ONE, Vec3::new(0.5, 1.0, 1.5), 3);
+        assert!((cube.volume() - 6.0).abs() < 1e-4, "{}", cube.volume());
+        // Lattice points on the surface of a 4 by 4 by 4 grid;
+        assert_eq!(cube.positions().len(), 4 * 4 * 4 - 2 * 2 * 2);
+
+        // Flat faces lose a little to the sphere;
+        let ball = SoftBody::ball(Vec3::ZERO, 1.0, 3);
+        let sphere = 4.0 / 3.0 * std::f32::consts::PI;
+        assert!(ball.volume() < sphere && ball.volume() > sphere * 0.97, "{}", ball.volume());
+    }
+
+    #[test]
+    fn ball_squashes_on_impact_and_keeps_volume() {
+        let floor = Shape::plane(Vec3::Y, 0.0);
+        let boundaries = [Boundary::fixed(&floor, Isometry::default())];
+
+        let mut ball = SoftBody::ball(Vec3::Y * 1.5, 0.5, 2).with_damping(2.0);
+        let mut flattest: f32 = 1.0;
+        for _ in 0..240 {
+            ball.step(GRAVITY, DT, &boundaries);
+            let aabb = ball.aabb();
+            flattest = flattest.min(aabb.half_extents().y / aabb.half_extents().x);
+            assert!((ball.volume() - ball.rest_volume()).abs() < ball.rest_volume() * 0.05);
+        }
+
+        assert!(flattest < 0.9, "never squashed, {flattest}");
+        assert!(ball.positions().iter().all(|p| p.y > 0.0));
+        assert!(ball.linear_velocity().length() < 0.1);
+    }
+
+    #[test]
+    fn shape_matching_recovers_a_crushed_cube() {
+        let mut cube = SoftBody::cuboid(Vec3::ZERO, Vec3::splat(0.5), 2)
+            .with_edge_compliance(1.0)
+            .with_volume_compliance(1.0)
+            .with_shape_matching(1e-5);
+        for i in 0..cube.positions().len() {
+            let p = cube.positions()[i];
+            cube.set_position(i, Vec3::new(p.x * 1.5, p.y * 0.3, p.z));
+        }
+
+        for _ in 0..60 {
+            cube.step(Vec3::ZERO, DT, &[]);
+        }
+
+        assert!((cube.volume() - 1.0).abs() < 0.05, "{}", cube.volume());
+        assert!((cube.aabb().half_extents().y - 0.52).abs() < 0.03);
+    }
+
+    #[test]
+    fn soft_body_pushes_dynamic_bodies() {
+        let mut world = WorldBuilder::new().with_gravity(Vec3::ZERO).build();
+        let crate_body = world.add_body(RigidBody::new()
+            .with_position(Vec3::X * 1.5)
+            .with_mass(1.0)
+            .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.3)))));
+        let ball = world.add_soft_body(SoftBody::ball(Vec3::ZERO, 0.3, 2)
+            .with_mass(1.0)
+            .with_damping(0.0)
+            .with_velocity(Vec3::X * 3.0));
+
+        for _ in 0..60 {
+            world.step(DT);
+        }
+
+        // The crate takes momentum from the ball && nothing is created along the way;
+        let soft = world.soft_body(ball).unwrap();
+        let crate_velocity = world.body(crate_body).unwrap().linear_velocity();
+        let momentum = soft.linear_velocity() * soft.mass() + crate_velocity;
+        assert!(crate_velocity.x > 1.0, "{crate_velocity}");
+        assert!((momentum.x - 3.0).abs() < 0.3, "{momentum}");
+        assert!(soft.positions().iter().all(|p| p.x < world.body(crate_body).unwrap().position().x));
+
+        // A soft body added in the freed slot isn't reachable through the old handle;
+        assert!(world.remove_soft_body(ball).is_some());
+        let next = world.add_soft_body(SoftBody::ball(Vec3::ZERO, 0.3, 1));
+        assert_eq!(next.index(), ball.index());
+        assert!(world.soft_body(ball).is_none() && world.soft_body(next).is_some());
+    }
+}
@@ -0,0 +1,253 @@
+use glam::{Mat3, Vec2, Vec3};
+
+use super::{effective_mass, pair_mut, FrictionModel, PositionCorrection, SolverConfig, Velocity};
+use super::super::body::RigidBody;
+use super::super::collision::any_perpendicular;
+use super::super::material::ContactMaterial;
+use super::super::{BodyPair, CachedImpulse, Contact};
+
+/// Max drift of a cached point, in body space, to still warm start from it;
+const WARM_START_DISTANCE: f32 = 0.05;
+
+/// Tangential speed below which a contact is held by static friction;
+const STATIC_FRICTION_SPEED: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy)]
+struct ConstraintPoint {
+    /// Offsets from each center of mass;
+    r_a: Vec3,
+    r_b: Vec3,
+    local_a: Vec3,
+    depth: f32,
+    normal: Vec3,
+    tangents: [Vec3; 2],
+
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    /// Target separating speed from restitution;
+    velocity_bias: f32,
+
+    normal_impulse: f32,
+    tangent_impulse: Vec2,
+    pseudo_impulse: f32,
+    /// Slipping faster than `STATIC_FRICTION_SPEED`, held by dynamic friction;
+    sliding: bool,
+}
+
+/// Non-penetration && friction constraints of one manifold;
+pub(super) struct ContactConstraint {
+    pub(super) pair: BodyPair,
+    a: usize,
+    b: usize,
+    material: ContactMaterial,
+    points: Vec<ConstraintPoint>,
+
+    normal: Vec3,
+    rolling_mass: Mat3,
+    /// Angular impulse resisting relative rolling, bounded by the total normal impulse;
+    rolling_impulse: Vec3,
+}
+
+impl ContactConstraint {
+    pub(super) fn new(
+        contact: &Contact,
+        body_a: &RigidBody,
+        body_b: &RigidBody,
+        material: ContactMaterial,
+        velocities: &[Velocity],
+        config: &SolverConfig,
+    ) -> Self {
+        let (a, b) = (contact.body_a.0, contact.body_b.0);
+        let (va, vb) = (&velocities[a], &velocities[b]);
+        let restitution = material.restitution;
+
+        let iso_a = body_a.isometry();
+        let points = contact.manifold.points.iter()
+            .map(|point| {
+                let normal = point.normal;
+                let tangent = any_perpendicular(normal);
+                let tangents = [tangent, normal.cross(tangent)];
+
+                let r_a = point.point - body_a.center_of_mass();
+                let r_b = point.point - body_b.center_of_mass();
+                let local_a = iso_a.inverse_transform_point(point.point);
+
+                let dv = vb.at(r_b) - va.at(r_a);
+                let approach = dv.dot(normal);
+                let sliding = (dv - normal * approach).length() > STATIC_FRICTION_SPEED;
+                let velocity_bias = match approach < -config.restitution_threshold {
+                    true => -restitution * approach,
+                    false => 0.0
+                };
+
+                // Reuse the impulse of the closest cached point;
+                let cached = contact.impulses.iter()
+                    .filter(|c| c.local_a.distance_squared(local_a) < WARM_START_DISTANCE * WARM_START_DISTANCE)
+                    .min_by(|x, y| x.local_a.distance_squared(local_a).total_cmp(&y.local_a.distance_squared(local_a)));
+
+                let (normal_impulse, tangent_impulse) = match (config.warm_starting, cached) {
+                    (true, Some(c)) => (c.normal, Vec2::new(c.tangent.dot(tangents[0]), c.tangent.dot(tangents[1]))),
+                    _ => (0.0, Vec2::ZERO)
+                };
+
+                ConstraintPoint {
+                    r_a,
+                    r_b,
+                    local_a,
+                    depth: point.depth,
+                    normal,
+                    tangents,
+
+                    normal_mass: effective_mass(va, r_a, vb, r_b, normal),
+                    tangent_mass: [
+                        effective_mass(va, r_a, vb, r_b, tangents[0]),
+                        effective_mass(va, r_a, vb, r_b, tangents[1]),
+                    ],
+                    velocity_bias,
+
+                    normal_impulse,
+                    tangent_impulse,
+                    pseudo_impulse: 0.0,
+                    sliding,
+                }
+            })
+            .collect();
+
+        let inv_inertia = va.inv_inertia + vb.inv_inertia;
+        let rolling_mass = match material.rolling_friction > 0.0 && inv_inertia.determinant().abs() > f32::EPSILON {
+            true => inv_inertia.inverse(),
+            false => Mat3::ZERO
+        };
+
+        Self {
+            pair: (contact.body_a, contact.body_b),
+            a,
+            b,
+            material,
+            points,
+
+            normal: contact.manifold.normal,
+            rolling_mass,
+            rolling_impulse: Vec3::ZERO,
+        }
+    }
+
+    /// Apply last step's impulses before iterating;
+    pub(super) fn warm_start(&self, velocities: &mut [Velocity]) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+        for p in &self.points {
+            let impulse = p.normal * p.normal_impulse
+                + p.tangents[0] * p.tangent_impulse.x
+                + p.tangents[1] * p.tangent_impulse.y;
+
+            va.apply(-impulse, p.r_a);
+            vb.apply(impulse, p.r_b);
+        }
+    }
+
+    pub(super) fn solve_velocity(&mut self, velocities: &mut [Velocity], config: &SolverConfig, dt: f32) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+
+        // Friction first, bounded by the current normal impulse;
+        // Sticking points are held by static friction, sliding ones by dynamic friction;
+        for p in &mut self.points {
+            let friction = match p.sliding {
+                true => self.material.dynamic_friction,
+                false => self.material.static_friction
+            };
+            let max = friction * p.normal_impulse;
+            let old = p.tangent_impulse;
+
+            match config.friction_model {
+                FrictionModel::Pyramid => {
+                    for i in 0..2 {
+                        let dv = vb.at(p.r_b) - va.at(p.r_a);
+                        let lambda = -dv.dot(p.tangents[i]) * p.tangent_mass[i];
+                        let new = (p.tangent_impulse[i] + lambda).clamp(-max, max);
+                        let impulse = p.tangents[i] * (new - p.tangent_impulse[i]);
+                        p.tangent_impulse[i] = new;
+
+                        va.apply(-impulse, p.r_a);
+                        vb.apply(impulse, p.r_b);
+                    }
+                }
+                FrictionModel::Cone => {
+                    let dv = vb.at(p.r_b) - va.at(p.r_a);
+                    let lambda = Vec2::new(
+                        -dv.dot(p.tangents[0]) * p.tangent_mass[0],
+                        -dv.dot(p.tangents[1]) * p.tangent_mass[1],
+                    );
+                    p.tangent_impulse = (old + lambda).clamp_length_max(max);
+
+                    let delta = p.tangent_impulse - old;
+                    let impulse = p.tangents[0] * delta.x + p.tangents[1] * delta.y;
+                    va.apply(-impulse, p.r_a);
+                    vb.apply(impulse, p.r_b);
+                }
+            }
+        }
+
+        for p in &mut self.points {
+            let dv = vb.at(p.r_b) - va.at(p.r_a);
+            let vn = dv.dot(p.normal);
+
+            let bias = match config.position_correction {
+                PositionCorrection::Baumgarte => {
+                    let correction = config.correction_factor / dt * (p.depth - config.slop).max(0.0);
+                    p.velocity_bias.max(correction)
+                }
+                PositionCorrection::SplitImpulse => p.velocity_bias
+            };
+
+            let lambda = p.normal_mass * (bias - vn);
+            let new = (p.normal_impulse + lambda).max(0.0);
+            let impulse = p.normal * (new - p.normal_impulse);
+            p.normal_impulse = new;
+
+            va.apply(-impulse, p.r_a);
+            vb.apply(impulse, p.r_b);
+        }
+
+        if self.material.rolling_friction > 0.0 {
+            let max = self.material.rolling_friction * self.points.iter().map(|p| p.normal_impulse).sum::<f32>();
+            let dw = vb.angular - va.angular;
+            let rolling = dw - self.normal * dw.dot(self.normal);
+
+            let lambda = -(self.rolling_mass * rolling);
+            let old = self.rolling_impulse;
+            self.rolling_impulse = (old + lambda - self.normal * lambda.dot(self.normal)).clamp_length_max(max);
+
+            let impulse = self.rolling_impulse - old;
+            va.apply_angular(-impulse);
+            vb.apply_angular(impulse);
+        }
+    }
+
+    /// Split impulse: push apart with pseudo velocities only;
+    pub(super) fn solve_position(&mut self, velocities: &mut [Velocity], config: &SolverConfig, dt: f32) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+
+        for p in &mut self.points {
+            let vn = (vb.pseudo_at(p.r_b) - va.pseudo_at(p.r_a)).dot(p.normal);
+            let target = config.correction_factor / dt * (p.depth - config.slop).max(0.0);
+
+            let lambda = p.normal_mass * (target - vn);
+            let new = (p.pseudo_impulse + lambda).max(0.0);
+            let impulse = p.normal * (new - p.pseudo_impulse);
+            p.pseudo_impulse = new;
+
+            va.apply_pseudo(-impulse, p.r_a);
+            vb.apply_pseudo(impulse, p.r_b);
+        }
+    }
+
+    pub(super) fn impulses(&self) -> Vec<CachedImpulse> {
+        self.points.iter()
+            .map(|p| CachedImpulse {
+                local_a: p.local_a,
+                normal: p.normal_impulse,
+                tangent: p.tangents[0] * p.tangent_impulse.x + p.tangents[1] * p.tangent_impulse.y,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,261 @@
+use glam::Vec3;
+
+use super::{pair_mut, SolverConfig, Velocity};
+use super::super::collision::any_perpendicular;
+use super::super::joint::{Joint, JointHandle, JointKind};
+use super::super::shape::Isometry;
+
+/// Row slots, fixed per meaning so cached impulses line up between steps;
+const LINEAR: usize = 0;
+const ANGULAR: usize = 3;
+const LIMIT: usize = 6;
+const MOTOR: usize = 7;
+
+/// Single scalar constraint `J v + bias = 0` with a bounded impulse;
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    slot: usize,
+    linear_a: Vec3,
+    angular_a: Vec3,
+    linear_b: Vec3,
+    angular_b: Vec3,
+
+    mass: f32,
+    bias: f32,
+    min: f32,
+    max: f32,
+    impulse: f32,
+}
+
+impl Row {
+    /// Relative velocity of two points along dir;
+    fn linear(slot: usize, dir: Vec3, r_a: Vec3, r_b: Vec3) -> Self {
+        Self::new(slot, -dir, -r_a.cross(dir), dir, r_b.cross(dir))
+    }
+
+    /// Relative angular velocity about axis;
+    fn angular(slot: usize, axis: Vec3) -> Self {
+        Self::new(slot, Vec3::ZERO, -axis, Vec3::ZERO, axis)
+    }
+
+    fn new(slot: usize, linear_a: Vec3, angular_a: Vec3, linear_b: Vec3, angular_b: Vec3) -> Self {
+        Self {
+            slot,
+            linear_a,
+            angular_a,
+            linear_b,
+            angular_b,
+
+            mass: 0.0,
+            bias: 0.0,
+            min: f32::NEG_INFINITY,
+            max: f32::INFINITY,
+            impulse: 0.0,
+        }
+    }
+
+    /// Same constraint measured from the other body;
+    fn flipped(mut self) -> Self {
+        self.linear_a = -self.linear_a;
+        self.angular_a = -self.angular_a;
+        self.linear_b = -self.linear_b;
+        self.angular_b = -self.angular_b;
+        self
+    }
+
+    /// Drive the position error to zero;
+    fn with_error(mut self, error: f32, rate: f32) -> Self {
+        self.bias = error * rate;
+        self
+    }
+
+    fn with_bounds(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// One sided limit row, or none while the value is inside the range;
+    fn limit(self, value: f32, (min, max): (f32, f32), rate: f32) -> Option<Self> {
+        match (value <= min, value >= max) {
+            (true, true) => Some(self.with_error(value - min, rate)),
+            (true, false) => Some(self.with_error(value - min, rate).with_bounds(0.0, f32::INFINITY)),
+            (false, true) => Some(self.with_error(value - max, rate).with_bounds(f32::NEG_INFINITY, 0.0)),
+            (false, false) => None
+        }
+    }
+
+    fn velocity(&self, a: &Velocity, b: &Velocity) -> f32 {
+        self.linear_a.dot(a.linear) + self.angular_a.dot(a.angular)
+            + self.linear_b.dot(b.linear) + self.angular_b.dot(b.angular)
+    }
+
+    fn apply(&self, a: &mut Velocity, b: &mut Velocity, impulse: f32) {
+        a.linear += self.linear_a * (a.inv_mass * impulse);
+        a.angular += a.inv_inertia * self.angular_a * impulse;
+        b.linear += self.linear_b * (b.inv_mass * impulse);
+        b.angular += b.inv_inertia * self.angular_b * impulse;
+    }
+
+    fn prepare(&mut self, a: &Velocity, b: &Velocity) {
+        let k = a.inv_mass * self.linear_a.length_squared() + self.angular_a.dot(a.inv_inertia * self.angular_a)
+            + b.inv_mass * self.linear_b.length_squared() + self.angular_b.dot(b.inv_inertia * self.angular_b);
+
+        self.mass = match k > f32::EPSILON {
+            true => 1.0 / k,
+            false => 0.0
+        };
+    }
+}
+
+/// Rows of one joint for the current step;
+pub(super) struct JointConstraint {
+    pub(super) handle: JointHandle,
+    a: usize,
+    b: usize,
+    rows: Vec<Row>,
+}
+
+impl JointConstraint {
+    /// Build rows from body poses && world centers of mass, `b` may be the solver's world slot;
+    pub(super) fn new(
+        handle: JointHandle,
+        joint: &Joint,
+        (a, iso_a, center_a): (usize, Isometry, Vec3),
+        (b, iso_b, center_b): (usize, Isometry, Vec3),
+        velocities: &[Velocity],
+        config: &SolverConfig,
+        dt: f32,
+    ) -> Self {
+        let rate = config.correction_factor / dt;
+        let frame_a = iso_a * joint.frame_a;
+        let frame_b = iso_b * joint.frame_b;
+
+        let r_a = frame_a.position - center_a;
+        let r_b = frame_b.position - center_b;
+        let delta = frame_b.position - frame_a.position;
+        let axis = frame_a.transform_vector(Vec3::X);
+
+        let mut rows = Vec::new();
+        match joint.kind {
+            JointKind::Ball | JointKind::Hinge | JointKind::Fixed => {
+                for (i, dir) in [Vec3::X, Vec3::Y, Vec3::Z].into_iter().enumerate() {
+                    rows.push(Row::linear(LINEAR + i, dir, r_a, r_b).with_error(delta.dot(dir), rate));
+                }
+            }
+            JointKind::Prismatic => {
+                // Lever arm up to B's anchor, so the rows stay valid at any slide;
+                let u = any_perpendicular(axis);
+                let v = axis.cross(u);
+                for (i, dir) in [u, v].into_iter().enumerate() {
+                    rows.push(Row::linear(LINEAR + i, dir, r_a + delta, r_b).with_error(delta.dot(dir), rate));
+                }
+            }
+            JointKind::Distance => {
+                let length = delta.length();
+                let dir = match length > f32::EPSILON {
+                    true => delta / length,
+                    false => Vec3::X
+                };
+
+                let limits = joint.limits.unwrap_or((0.0, f32::INFINITY));
+                rows.extend(Row::linear(LIMIT, dir, r_a, r_b).limit(length, limits, rate));
+            }
+        }
+
+        match joint.kind {
+            JointKind::Fixed | JointKind::Prismatic => {
+                let error = rotation_error(&frame_a, &frame_b);
+                for (i, dir) in [Vec3::X, Vec3::Y, Vec3::Z].into_iter().enumerate() {
+                    rows.push(Row::angular(ANGULAR + i, dir).with_error(error.dot(dir), rate));
+                }
+            }
+            JointKind::Hinge => {
+                // Keep both hinge axes aligned, rotation about them is free;
+                let axis_b = frame_b.transform_vector(Vec3::X);
+                let error = axis.cross(axis_b);
+                let u = any_perpendicular(axis);
+                let v = axis.cross(u);
+                for (i, dir) in [u, v].into_iter().enumerate() {
+                    rows.push(Row::angular(ANGULAR + i, dir).with_error(error.dot(dir), rate));
+                }
+            }
+            JointKind::Ball | JointKind::Distance => ()
+        }
+
+        // Limits && motor act on the free axis, measuring A relative to B;
+        let free = match joint.kind {
+            JointKind::Hinge => Some((Row::angular(LIMIT, axis).flipped(), -hinge_angle(&frame_a, &frame_b))),
+            JointKind::Prismatic => Some((Row::linear(LIMIT, axis, r_a + delta, r_b).flipped(), -delta.dot(axis))),
+            _ => None
+        };
+
+        if let Some((row, value)) = free {
+            if let Some(limits) = joint.limits {
+                rows.extend(row.limit(value, limits, rate));
+            }
+
+            if let Some(motor) = joint.motor {
+                let bound = motor.max_force * dt;
+                rows.push(Row { slot: MOTOR, ..row }.with_error(-motor.velocity, 1.0).with_bounds(-bound, bound));
+            }
+        }
+
+        let (va, vb) = (&velocities[a], &velocities[b]);
+        for row in &mut rows {
+            row.prepare(va, vb);
+            if config.warm_starting {
+                row.impulse = joint.impulses[row.slot].clamp(row.min, row.max);
+            }
+        }
+
+        Self { handle, a, b, rows }
+    }
+
+    pub(super) fn warm_start(&self, velocities: &mut [Velocity]) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+        for row in &self.rows {
+            row.apply(va, vb, row.impulse);
+        }
+    }
+
+    pub(super) fn solve_velocity(&mut self, velocities: &mut [Velocity]) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+        for row in &mut self.rows {
+            let lambda = -row.mass * (row.velocity(va, vb) + row.bias);
+            let new = (row.impulse + lambda).clamp(row.min, row.max);
+            row.apply(va, vb, new - row.impulse);
+            row.impulse = new;
+        }
+    }
+
+    /// Accumulated impulse per row slot;
+    pub(super) fn impulses(&self) -> [f32; Joint::MAX_ROWS] {
+        let mut impulses = [0.0; Joint::MAX_ROWS];
+        for row in &self.rows {
+            impulses[row.slot] = row.impulse;
+        }
+
+        impulses
+    }
+}
+
+/// Small angle rotation vector taking frame A onto frame B;
+fn rotation_error(frame_a: &Isometry, frame_b: &Isometry) -> Vec3 {
+    let relative = frame_b.rotation * frame_a.rotation.inverse();
+    let relative = match relative.w < 0.0 {
+        true => -relative,
+        false => relative
+    };
+
+    2.0 * Vec3::new(relative.x, relative.y, relative.z)
+}
+
+/// Angle of B's reference axis around the hinge axis, measured from A's;
+fn hinge_angle(frame_a: &Isometry, frame_b: &Isometry) -> f32 {
+    let axis = frame_a.transform_vector(Vec3::X);
+    let reference_a = frame_a.transform_vector(Vec3::Y);
+    let reference_b = frame_b.transform_vector(Vec3::Y);
+
+    reference_a.cross(reference_b).dot(axis).atan2(reference_a.dot(reference_b))
+}
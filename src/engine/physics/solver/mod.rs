@@ -0,0 +1,395 @@
+use std::collections::BTreeMap;
+
+use glam::{Mat3, Vec3};
+
+use super::body::{BodyHandle, RigidBody};
+use super::joint::{Joint, JointHandle};
+use super::material::{ContactMaterial, MaterialTable};
+use super::shape::Isometry;
+use super::slot::Slots;
+use super::{BodyPair, Contact};
+
+use contact::ContactConstraint;
+use joint::JointConstraint;
+
+mod contact;
+mod joint;
+
+/// How the tangent impulse is bounded by the normal one;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrictionModel {
+    /// Each tangent direction clamped on its own, cheaper but anisotropic;
+    Pyramid,
+    /// Tangent impulse length clamped;
+    Cone,
+}
+
+/// How penetration is removed;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionCorrection {
+    /// Bias the velocity constraint, adds energy to the system;
+    Baumgarte,
+    /// Separate pseudo velocities that move bodies without keeping momentum;
+    SplitImpulse,
+}
+
+/// Iterative constraint solver settings;
+#[derive(Debug, Clone, Copy)]
+pub struct SolverConfig {
+    pub(super) velocity_iterations: u32,
+    pub(super) position_iterations: u32,
+    pub(super) friction_model: FrictionModel,
+    pub(super) position_correction: PositionCorrection,
+    pub(super) warm_starting: bool,
+    /// Approach speed below which contacts don't bounce;
+    pub(super) restitution_threshold: f32,
+    /// Fraction of the penetration removed per step;
+    pub(super) correction_factor: f32,
+    /// Penetration allowed without correction, keeps resting contacts stable;
+    pub(super) slop: f32,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            velocity_iterations: 8,
+            position_iterations: 3,
+            friction_model: FrictionModel::Cone,
+            position_correction: PositionCorrection::SplitImpulse,
+            warm_starting: true,
+            restitution_threshold: 1.0,
+            correction_factor: 0.2,
+            slop: 0.005,
+        }
+    }
+}
+
+impl SolverConfig {
+    pub fn velocity_iterations(&self) -> u32 {
+        self.velocity_iterations
+    }
+
+    pub fn position_iterations(&self) -> u32 {
+        self.position_iterations
+    }
+
+    pub fn friction_model(&self) -> FrictionModel {
+        self.friction_model
+    }
+
+    pub fn position_correction(&self) -> PositionCorrection {
+        self.position_correction
+    }
+}
+
+/// Solver copy of a body's velocities && inverse mass;
+#[derive(Debug, Clone, Copy)]
+struct Velocity {
+    linear: Vec3,
+    angular: Vec3,
+    pseudo_linear: Vec3,
+    pseudo_angular: Vec3,
+    inv_mass: f32,
+    inv_inertia: Mat3,
+}
+
+impl Velocity {
+    /// Immovable, stands for the world && empty body slots;
+    const FIXED: Self = Self {
+        linear: Vec3::ZERO,
+        angular: Vec3::ZERO,
+        pseudo_linear: Vec3::ZERO,
+        pseudo_angular: Vec3::ZERO,
+        inv_mass: 0.0,
+        inv_inertia: Mat3::ZERO,
+    };
+
+    fn new(body: &RigidBody) -> Self {
+        Self {
+            linear: body.linear_velocity,
+            angular: body.angular_velocity,
+            pseudo_linear: Vec3::ZERO,
+            pseudo_angular: Vec3::ZERO,
+            inv_mass: body.inv_mass,
+            inv_inertia: body.world_inv_inertia(),
+        }
+    }
+
+    /// Velocity of a point at offset r from the center of mass;
+    fn at(&self, r: Vec3) -> Vec3 {
+        self.linear + self.angular.cross(r)
+    }
+
+    fn pseudo_at(&self, r: Vec3) -> Vec3 {
+        self.pseudo_linear + self.pseudo_angular.cross(r)
+    }
+
+    fn apply(&mut self, impulse: Vec3, r: Vec3) {
+        self.linear += impulse * self.inv_mass;
+        self.angular += self.inv_inertia * r.cross(impulse);
+    }
+
+    fn apply_angular(&mut self, impulse: Vec3) {
+        self.angular += self.inv_inertia * impulse;
+    }
+
+    fn apply_pseudo(&mut self, impulse: Vec3, r: Vec3) {
+        self.pseudo_linear += impulse * self.inv_mass;
+        self.pseudo_angular += self.inv_inertia * r.cross(impulse);
+    }
+
+    /// Inverse of the mass seen along dir at offset r;
+    fn inv_mass_along(&self, r: Vec3, dir: Vec3) -> f32 {
+        let rn = r.cross(dir);
+        self.inv_mass + rn.dot(self.inv_inertia * rn)
+    }
+}
+
+/// Effective mass of a two body constraint along dir;
+fn effective_mass(a: &Velocity, r_a: Vec3, b: &Velocity, r_b: Vec3, dir: Vec3) -> f32 {
+    let k = a.inv_mass_along(r_a, dir) + b.inv_mass_along(r_b, dir);
+    match k > f32::EPSILON {
+        true => 1.0 / k,
+        false => 0.0
+    }
+}
+
+/// Two distinct mutable velocities;
+fn pair_mut(velocities: &mut [Velocity], a: usize, b: usize) -> (&mut Velocity, &mut Velocity) {
+    assert_ne!(a, b);
+    match a < b {
+        true => {
+            let (low, high) = velocities.split_at_mut(b);
+            (&mut low[a], &mut high[0])
+        }
+        false => {
+            let (low, high) = velocities.split_at_mut(a);
+            (&mut high[0], &mut low[b])
+        }
+    }
+}
+
+/// Sequential impulse solver for a single step;
+/// Works on copies of body velocities, written back by `apply`;
+/// Joints && contacts are solved in the same iterations;
+pub(super) struct Solver<'a> {
+    config: &'a SolverConfig,
+    dt: f32,
+    /// One per body slot, then the world;
+    velocities: Vec<Velocity>,
+    contacts: Vec<ContactConstraint>,
+    joints: Vec<JointConstraint>,
+}
+
+impl<'a> Solver<'a> {
+    pub(super) fn new(config: &'a SolverConfig, bodies: &Slots<BodyHandle, RigidBody>, dt: f32) -> Self {
+        let velocities = (0..bodies.capacity())
+            .map(|index| bodies.at(index).map_or(Velocity::FIXED, Velocity::new))
+            .chain([Velocity::FIXED])
+            .collect();
+
+        Self { config, dt, velocities, contacts: Vec::new(), joints: Vec::new() }
+    }
+
+    pub(super) fn add_joint(&mut self, handle: JointHandle, joint: &Joint, bodies: &Slots<BodyHandle, RigidBody>) {
+        let Some(body_a) = bodies.get(joint.body_a) else { return };
+        let (b, active_b) = match joint.body_b {
+            Some(handle) => match bodies.get(handle) {
+                Some(body_b) => ((handle.0, body_b.isometry(), body_b.center_of_mass()), body_b.is_active()),
+                None => return
+            },
+            None => ((self.velocities.len() - 1, Isometry::IDENTITY, Vec3::ZERO), false)
+        };
+
+        if !body_a.is_active() && !active_b {
+            return;
+        }
+
+        let a = (joint.body_a.0, body_a.isometry(), body_a.center_of_mass());
+        self.joints.push(JointConstraint::new(handle, joint, a, b, &self.velocities, self.config, self.dt));
+    }
+
+    pub(super) fn add_contact(&mut self, contact: &Contact, bodies: &Slots<BodyHandle, RigidBody>, materials: &MaterialTable) {
+        if !contact.is_touching() {
+            return;
+        }
+
+        let (Some(body_a), Some(body_b)) = (bodies.get(contact.body_a), bodies.get(contact.body_b)) else { return };
+        if !body_a.is_active() && !body_b.is_active() {
+            return;
+        }
+
+        let material = match (body_a.collider(), body_b.collider()) {
+            (Some(ca), Some(cb)) => materials.resolve(ca, cb),
+            _ => ContactMaterial::default()
+        };
+
+        self.contacts.push(ContactConstraint::new(contact, body_a, body_b, material, &self.velocities, self.config));
+    }
+
+    pub(super) fn solve(&mut self) {
+        if self.config.warm_starting {
+            for joint in &self.joints {
+                joint.warm_start(&mut self.velocities);
+            }
+            for contact in &self.contacts {
+                contact.warm_start(&mut self.velocities);
+            }
+        }
+
+        for _ in 0..self.config.velocity_iterations {
+            for joint in &mut self.joints {
+                joint.solve_velocity(&mut self.velocities);
+            }
+            for contact in &mut self.contacts {
+                contact.solve_velocity(&mut self.velocities, self.config, self.dt);
+            }
+        }
+
+        if self.config.position_correction == PositionCorrection::SplitImpulse {
+            for _ in 0..self.config.position_iterations {
+                for contact in &mut self.contacts {
+                    contact.solve_position(&mut self.velocities, self.config, self.dt);
+                }
+            }
+        }
+    }
+
+    /// Cache accumulated impulses on their contacts for the next step;
+    pub(super) fn store_impulses(&self, pairs: &mut BTreeMap<BodyPair, Contact>) {
+        for constraint in &self.contacts {
+            if let Some(contact) = pairs.get_mut(&constraint.pair) {
+                contact.impulses = constraint.impulses();
+            }
+        }
+    }
+
+    pub(super) fn store_joint_impulses(&self, joints: &mut Slots<JointHandle, Joint>) {
+        for constraint in &self.joints {
+            if let Some(joint) = joints.get_mut(constraint.handle) {
+                joint.impulses = constraint.impulses();
+            }
+        }
+    }
+
+    /// Write velocities back && move bodies by their pseudo velocities;
+    pub(super) fn apply(&self, bodies: &mut Slots<BodyHandle, RigidBody>) {
+        for (handle, body) in bodies.iter_mut() {
+            let velocity = &self.velocities[handle.0];
+            if !body.is_active() {
+                continue;
+            }
+
+            body.linear_velocity = velocity.linear;
+            body.angular_velocity = velocity.angular;
+            body.displace(velocity.pseudo_linear, velocity.pseudo_angular, self.dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use super::super::{BodyHandle, Collider, Shape, World, WorldBuilder};
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Awake unit box resting on the ground, sleeping would hide any drift;
+    fn resting_box(correction: PositionCorrection) -> (World, BodyHandle) {
+        let mut world = WorldBuilder::new().with_position_correction(correction).with_sleeping(false).build();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let box_ = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::Y * 0.5)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.5))))
+                .with_density(100.0),
+        );
+
+        (world, box_)
+    }
+
+    #[test]
+    fn resting_box_does_not_drift() {
+        for correction in [PositionCorrection::SplitImpulse, PositionCorrection::Baumgarte] {
+            let (mut world, box_) = resting_box(correction);
+            for _ in 0..60 {
+                world.step(DT);
+            }
+
+            let settled = world.body(box_).unwrap().position();
+            for _ in 0..600 {
+                world.step(DT);
+                let body = world.body(box_).unwrap();
+                let depth = 0.5 - body.position().y;
+                assert!(depth < 0.01, "{correction:?} sank {depth}");
+                assert!(body.position().distance(settled) < 1e-3, "{correction:?} drifted to {:?}", body.position());
+            }
+        }
+    }
+
+    /// Distance a box slid down a 0.3 rad slope in two seconds;
+    fn slide_down_slope(friction: f32) -> f32 {
+        let mut world = WorldBuilder::new().with_sleeping(false).build();
+        let slope = Quat::from_rotation_z(0.3);
+        world.add_body(
+            RigidBody::fixed()
+                .with_orientation(slope)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::new(20.0, 0.5, 5.0))).with_friction(friction)),
+        );
+
+        let start = slope * Vec3::Y;
+        let box_ = world.add_body(
+            RigidBody::new()
+                .with_position(start)
+                .with_orientation(slope)
+                .with_collider(Collider::new(Shape::cuboid(Vec3::splat(0.5))).with_friction(friction))
+                .with_density(100.0),
+        );
+
+        for _ in 0..120 {
+            world.step(DT);
+        }
+
+        world.body(box_).unwrap().position().distance(start)
+    }
+
+    #[test]
+    fn friction_holds_box_on_slope_or_lets_it_slide() {
+        // tan 0.3 is about 0.31;
+        let held = slide_down_slope(0.5);
+        assert!(held < 0.01, "slid {held}");
+
+        // Frictionless, it slides g sin θ t² / 2;
+        let slid = slide_down_slope(0.0);
+        let expected = 0.5 * 9.81 * 0.3f32.sin() * 2.0 * 2.0;
+        assert!((slid - expected).abs() < 0.1 * expected, "slid {slid} vs {expected}");
+    }
+
+    #[test]
+    fn restitution_bounce_reaches_expected_height() {
+        let mut world = World::new();
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::plane(Vec3::Y, 0.0))));
+        let ball = world.add_body(
+            RigidBody::new()
+                .with_position(Vec3::Y * 2.5)
+                .with_collider(Collider::new(Shape::sphere(0.5)).with_restitution(0.5)),
+        );
+
+        let mut bounced = false;
+        let mut peak = 0.0f32;
+        for _ in 0..180 {
+            world.step(DT);
+            let body = world.body(ball).unwrap();
+            bounced |= body.linear_velocity().y > 0.0;
+            if bounced {
+                peak = peak.max(body.position().y);
+            }
+        }
+
+        // A drop of 2 m comes back e² as high;
+        let height = peak - 0.5;
+        assert!(bounced && (height - 0.5).abs() < 0.05, "bounced {height}");
+    }
+}
@@ -0,0 +1,289 @@
+use glam::Vec2;
+
+use super::collider::Collider;
+use super::mass::MassProperties;
+use super::shape::Isometry;
+
+/// Stable body index inside the 2D physics world;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyHandle(pub(super) usize);
+
+impl BodyHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyType {
+    /// Moved by forces and contacts;
+    Dynamic,
+    /// Never moves;
+    Static,
+    /// Moved only by its velocity, ignores forces;
+    Kinematic,
+}
+
+/// Planar rigid body state;
+/// Position, angle,
+/// Velocities, mass, inertia;
+#[derive(Debug, Clone)]
+pub struct RigidBody {
+    pub(super) body_type: BodyType,
+
+    pub(super) position: Vec2,
+    /// Counter clockwise, in radians;
+    pub(super) angle: f32,
+    pub(super) linear_velocity: Vec2,
+    pub(super) angular_velocity: f32,
+
+    pub(super) mass: f32,
+    pub(super) inv_mass: f32,
+    pub(super) inertia: f32,
+    pub(super) inv_inertia: f32,
+
+    pub(super) force: Vec2,
+    pub(super) torque: f32,
+
+    pub(super) collider: Option<Collider>,
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self {
+            body_type: BodyType::Dynamic,
+
+            position: Vec2::ZERO,
+            angle: 0.0,
+            linear_velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+
+            mass: 1.0,
+            inv_mass: 1.0,
+            inertia: 1.0,
+            inv_inertia: 1.0,
+
+            force: Vec2::ZERO,
+            torque: 0.0,
+
+            collider: None,
+        }
+    }
+}
+
+impl RigidBody {
+    /// Dynamic body with unit mass && inertia;
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Body that never moves;
+    pub fn fixed() -> Self {
+        Self::default().with_type(BodyType::Static)
+    }
+
+    /// Body moved only by its velocity;
+    pub fn kinematic() -> Self {
+        Self::default().with_type(BodyType::Kinematic)
+    }
+
+    pub fn with_type(mut self, body_type: BodyType) -> Self {
+        self.body_type = body_type;
+        self.update_mass();
+        self
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    pub fn with_linear_velocity(mut self, velocity: Vec2) -> Self {
+        self.linear_velocity = velocity;
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, velocity: f32) -> Self {
+        self.angular_velocity = velocity;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self.update_mass();
+        self
+    }
+
+    pub fn with_inertia(mut self, inertia: f32) -> Self {
+        self.inertia = inertia;
+        self.update_mass();
+        self
+    }
+
+    /// Mass && inertia, the center is ignored as bodies rotate about their position;
+    pub fn with_mass_properties(mut self, properties: MassProperties) -> Self {
+        self.mass = properties.mass;
+        self.inertia = properties.inertia;
+        self.update_mass();
+        self
+    }
+
+    /// Mass && inertia of the collider shape filled with the density, set the collider first;
+    pub fn with_density(self, density: f32) -> Self {
+        match self.collider.as_ref().map(|c| c.shape.mass_properties(density)) {
+            Some(properties) => self.with_mass_properties(properties),
+            None => self
+        }
+    }
+
+    pub fn with_collider(mut self, collider: Collider) -> Self {
+        self.collider = Some(collider);
+        self
+    }
+
+    /// Recompute inverse mass properties;
+    fn update_mass(&mut self) {
+        match self.body_type {
+            BodyType::Dynamic if self.mass > 0.0 => {
+                self.inv_mass = 1.0 / self.mass;
+                self.inv_inertia = match self.inertia > f32::EPSILON {
+                    true => 1.0 / self.inertia,
+                    false => 0.0
+                };
+            }
+            _ => {
+                self.inv_mass = 0.0;
+                self.inv_inertia = 0.0;
+            }
+        }
+    }
+
+    pub fn body_type(&self) -> BodyType {
+        self.body_type
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    pub fn set_angle(&mut self, angle: f32) {
+        self.angle = angle;
+    }
+
+    /// Current position && angle;
+    pub fn isometry(&self) -> Isometry {
+        Isometry::new(self.position, self.angle)
+    }
+
+    pub fn collider(&self) -> Option<&Collider> {
+        self.collider.as_ref()
+    }
+
+    pub fn set_collider(&mut self, collider: Option<Collider>) {
+        self.collider = collider;
+    }
+
+    pub fn linear_velocity(&self) -> Vec2 {
+        self.linear_velocity
+    }
+
+    pub fn set_linear_velocity(&mut self, velocity: Vec2) {
+        self.linear_velocity = velocity;
+    }
+
+    pub fn angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
+
+    pub fn set_angular_velocity(&mut self, velocity: f32) {
+        self.angular_velocity = velocity;
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub fn inv_mass(&self) -> f32 {
+        self.inv_mass
+    }
+
+    pub fn inertia(&self) -> f32 {
+        self.inertia
+    }
+
+    pub fn inv_inertia(&self) -> f32 {
+        self.inv_inertia
+    }
+
+    /// Velocity of a world space point attached to the body;
+    pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+        self.linear_velocity + (point - self.position).perp() * self.angular_velocity
+    }
+
+    /// Apply force at the center of mass until the next step;
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    /// Apply force at a world space point until the next step;
+    pub fn apply_force_at(&mut self, force: Vec2, point: Vec2) {
+        self.force += force;
+        self.torque += (point - self.position).perp_dot(force);
+    }
+
+    pub fn apply_torque(&mut self, torque: f32) {
+        self.torque += torque;
+    }
+
+    /// Instant velocity change at the center of mass;
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.linear_velocity += impulse * self.inv_mass;
+    }
+
+    /// Instant velocity change at a world space point;
+    pub fn apply_impulse_at(&mut self, impulse: Vec2, point: Vec2) {
+        self.linear_velocity += impulse * self.inv_mass;
+        self.angular_velocity += self.inv_inertia * (point - self.position).perp_dot(impulse);
+    }
+
+    /// Semi-implicit euler velocity update;
+    pub(super) fn integrate_velocity(&mut self, gravity: Vec2, dt: f32) {
+        if self.is_dynamic() {
+            let acceleration = gravity + self.force * self.inv_mass;
+            self.linear_velocity += acceleration * dt;
+            self.angular_velocity += self.inv_inertia * self.torque * dt;
+        }
+
+        self.force = Vec2::ZERO;
+        self.torque = 0.0;
+    }
+
+    pub(super) fn integrate_position(&mut self, dt: f32) {
+        if self.body_type != BodyType::Static {
+            self.displace(self.linear_velocity, self.angular_velocity, dt);
+        }
+    }
+
+    /// Move by the given velocities without keeping them;
+    pub(super) fn displace(&mut self, linear: Vec2, angular: f32, dt: f32) {
+        self.position += linear * dt;
+        self.angle += angular * dt;
+    }
+}
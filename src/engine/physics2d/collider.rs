@@ -0,0 +1,40 @@
+use super::shape::Shape;
+
+/// Collision geometry attached to a body;
+/// Friction && restitution of both colliders are combined per contact;
+#[derive(Debug, Clone)]
+pub struct Collider {
+    pub(super) shape: Shape,
+    pub(super) friction: f32,
+    pub(super) restitution: f32,
+}
+
+impl Collider {
+    pub fn new(shape: Shape) -> Self {
+        Self { shape, friction: 0.5, restitution: 0.0 }
+    }
+
+    /// Coulomb friction coefficient;
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.max(0.0);
+        self
+    }
+
+    /// Bounciness, zero for no bounce && one for a perfectly elastic one;
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn friction(&self) -> f32 {
+        self.friction
+    }
+
+    pub fn restitution(&self) -> f32 {
+        self.restitution
+    }
+}
@@ -0,0 +1,345 @@
+use glam::Vec2;
+
+use super::shape::{Isometry, Shape};
+
+/// Cores closer than this count as overlapping;
+const TOUCHING: f32 = 1e-5;
+/// Faces this parallel to the contact normal rest on each other with two points;
+const PARALLEL: f32 = 0.999;
+/// Extra separation B's face needs to be picked as reference, keeps the choice stable between steps;
+const REFERENCE_BIAS: f32 = 1e-3;
+
+/// Single contact point, `point` lies midway between both surfaces;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint {
+    pub point: Vec2,
+    pub depth: f32,
+}
+
+/// Contact manifold between two shapes, at most two points;
+/// Normal points from the first shape to the second;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifold {
+    pub normal: Vec2,
+    pub points: Vec<ContactPoint>,
+}
+
+impl Manifold {
+    pub fn new(normal: Vec2) -> Self {
+        Self { normal, points: Vec::new() }
+    }
+
+    pub fn max_depth(&self) -> f32 {
+        self.points.iter()
+            .map(|p| p.depth)
+            .fold(f32::MIN, f32::max)
+    }
+}
+
+/// Shape core in world space;
+/// One point for circles, a segment for capsules, the ring of vertices for polygons;
+struct Core {
+    points: Vec<Vec2>,
+    radius: f32,
+}
+
+impl Core {
+    fn new(shape: &Shape, iso: &Isometry) -> Self {
+        let (points, radius) = shape.core();
+        Self { points: points.into_iter().map(|p| iso.transform_point(p)).collect(), radius }
+    }
+
+    /// Segments run both ways, a lone point has no edges;
+    fn edge_count(&self) -> usize {
+        match self.points.len() {
+            1 => 0,
+            n => n
+        }
+    }
+
+    fn edge(&self, i: usize) -> (Vec2, Vec2) {
+        (self.points[i], self.points[(i + 1) % self.points.len()])
+    }
+
+    /// Outward normal of edge i, counter clockwise winding;
+    fn normal(&self, i: usize) -> Vec2 {
+        let (a, b) = self.edge(i);
+        Vec2::new(b.y - a.y, a.x - b.x).normalize()
+    }
+
+    /// Edges as segments, a lone point as a zero length one;
+    fn segments(&self) -> impl Iterator<Item=(Vec2, Vec2)> + '_ {
+        (0..self.edge_count().max(1)).map(|i| self.edge(i))
+    }
+
+    /// Edge facing dir the most;
+    fn facing_edge(&self, dir: Vec2) -> Option<usize> {
+        (0..self.edge_count()).max_by(|i, j| self.normal(*i).dot(dir).total_cmp(&self.normal(*j).dot(dir)))
+    }
+
+    /// Largest gap between this core's edges && the other core's points, with its edge;
+    fn max_separation(&self, other: &Core) -> Option<(f32, usize)> {
+        (0..self.edge_count())
+            .map(|i| {
+                let (normal, origin) = (self.normal(i), self.points[i]);
+                let gap = other.points.iter().map(|p| normal.dot(*p - origin)).fold(f32::INFINITY, f32::min);
+                (gap, i)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+    }
+}
+
+/// Narrow phase test of two posed shapes;
+/// Separate cores touch through their rounding along the closest points,
+/// Overlapping cores are pushed apart along the face of least penetration;
+pub fn collide(a: &Shape, iso_a: &Isometry, b: &Shape, iso_b: &Isometry) -> Option<Manifold> {
+    let (core_a, core_b) = (Core::new(a, iso_a), Core::new(b, iso_b));
+    let radius = core_a.radius + core_b.radius;
+
+    let (closest_a, closest_b) = core_a.segments()
+        .flat_map(|(a0, a1)| core_b.segments().map(move |(b0, b1)| closest_points(a0, a1, b0, b1)))
+        .min_by(|x, y| x.0.distance_squared(x.1).total_cmp(&y.0.distance_squared(y.1)))?;
+
+    let distance = closest_a.distance(closest_b);
+    if distance > radius {
+        return None;
+    }
+
+    // Polygons may hold the other core without any edges crossing;
+    let separation = [core_a.max_separation(&core_b), core_b.max_separation(&core_a)]
+        .into_iter()
+        .flatten()
+        .map(|(gap, _)| gap)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let polygonal = core_a.points.len() > 2 || core_b.points.len() > 2;
+
+    let manifold = match distance <= TOUCHING || (polygonal && separation < 0.0) {
+        true => overlapping(&core_a, &core_b),
+        false => separated(&core_a, &core_b, closest_a, closest_b, distance)
+    };
+
+    (!manifold.points.is_empty()).then_some(manifold)
+}
+
+fn separated(a: &Core, b: &Core, closest_a: Vec2, closest_b: Vec2, distance: f32) -> Manifold {
+    let normal = (closest_b - closest_a) / distance;
+
+    if let (Some(i), Some(j)) = (a.facing_edge(normal), b.facing_edge(-normal)) {
+        if a.normal(i).dot(normal) > PARALLEL && b.normal(j).dot(-normal) > PARALLEL {
+            let manifold = clip(a, i, b, false);
+            if !manifold.points.is_empty() {
+                return manifold;
+            }
+        }
+    }
+
+    let point = (closest_a + normal * a.radius + closest_b - normal * b.radius) * 0.5;
+    Manifold { normal, points: vec![ContactPoint { point, depth: a.radius + b.radius - distance }] }
+}
+
+fn overlapping(a: &Core, b: &Core) -> Manifold {
+    match (a.max_separation(b), b.max_separation(a)) {
+        (Some((gap_a, i)), Some((gap_b, j))) => match gap_b > gap_a + REFERENCE_BIAS {
+            true => clip(b, j, a, true),
+            false => clip(a, i, b, false)
+        },
+        (Some((_, i)), None) => clip(a, i, b, false),
+        (None, Some((_, j))) => clip(b, j, a, true),
+        // Two circles on the same center;
+        (None, None) => Manifold {
+            normal: Vec2::Y,
+            points: vec![ContactPoint { point: (a.points[0] + b.points[0]) * 0.5, depth: a.radius + b.radius }],
+        }
+    }
+}
+
+/// Incident edge clipped to the side planes of the reference edge;
+/// Flipped when the reference edge belongs to B, the normal still points from A to B;
+fn clip(reference: &Core, edge: usize, incident: &Core, flipped: bool) -> Manifold {
+    let (v1, v2) = reference.edge(edge);
+    let normal = reference.normal(edge);
+    let tangent = (v2 - v1).normalize();
+
+    let mut segment = match incident.facing_edge(-normal) {
+        Some(j) => {
+            let (w1, w2) = incident.edge(j);
+            [w1, w2]
+        }
+        None => [incident.points[0]; 2]
+    };
+
+    if incident.edge_count() > 0 {
+        for (origin, dir) in [(v1, tangent), (v2, -tangent)] {
+            let d = segment.map(|p| dir.dot(p - origin));
+            let (start, end) = (segment[0], segment[1]);
+            let cut = |d: [f32; 2]| start + (end - start) * (d[0] / (d[0] - d[1]));
+            match (d[0] < 0.0, d[1] < 0.0) {
+                (true, true) => return Manifold::new(normal),
+                (true, false) => segment[0] = cut(d),
+                (false, true) => segment[1] = cut(d),
+                (false, false) => ()
+            }
+        }
+    }
+
+    let count = match segment[0].distance(segment[1]) > TOUCHING {
+        true => 2,
+        false => 1
+    };
+
+    let radius = reference.radius + incident.radius;
+    let points = segment[..count].iter()
+        .filter_map(|p| {
+            let gap = normal.dot(*p - v1);
+            (gap <= radius).then(|| ContactPoint {
+                point: *p + normal * (reference.radius - gap - incident.radius) * 0.5,
+                depth: radius - gap,
+            })
+        })
+        .collect();
+
+    let normal = match flipped {
+        true => -normal,
+        false => normal
+    };
+
+    Manifold { normal, points }
+}
+
+fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length = ab.length_squared();
+    match length > f32::EPSILON {
+        true => a + ab * ((point - a).dot(ab) / length).clamp(0.0, 1.0),
+        false => a
+    }
+}
+
+/// Closest points of two segments, their crossing when they intersect;
+fn closest_points(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> (Vec2, Vec2) {
+    let (da, db) = (a1 - a0, b1 - b0);
+    let denominator = da.perp_dot(db);
+    if denominator.abs() > f32::EPSILON {
+        let t = (b0 - a0).perp_dot(db) / denominator;
+        let u = (b0 - a0).perp_dot(da) / denominator;
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            let crossing = a0 + da * t;
+            return (crossing, crossing);
+        }
+    }
+
+    [
+        (a0, closest_on_segment(a0, b0, b1)),
+        (a1, closest_on_segment(a1, b0, b1)),
+        (closest_on_segment(b0, a0, a1), b0),
+        (closest_on_segment(b1, a0, a1), b1),
+    ]
+        .into_iter()
+        .min_by(|x, y| x.0.distance_squared(x.1).total_cmp(&y.0.distance_squared(y.1)))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_vec(actual: Vec2, expected: Vec2) {
+        assert!(actual.abs_diff_eq(expected, 1e-3), "expected {expected:?}, got {actual:?}");
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "expected {expected}, got {actual}");
+    }
+
+    fn at(x: f32, y: f32) -> Isometry {
+        Isometry::from_position(Vec2::new(x, y))
+    }
+
+    #[test]
+    fn circle_circle() {
+        let m = collide(&Shape::circle(1.0), &at(0.0, 0.0), &Shape::circle(1.0), &at(1.5, 0.0)).unwrap();
+        assert_vec(m.normal, Vec2::X);
+        assert_near(m.points[0].depth, 0.5);
+        assert_vec(m.points[0].point, Vec2::new(0.75, 0.0));
+
+        assert!(collide(&Shape::circle(1.0), &at(0.0, 0.0), &Shape::circle(1.0), &at(2.1, 0.0)).is_none());
+    }
+
+    #[test]
+    fn circle_polygon() {
+        let square = Shape::rectangle(Vec2::ONE);
+
+        // Face from above;
+        let m = collide(&Shape::circle(0.5), &at(0.2, 1.4), &square, &at(0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec2::NEG_Y);
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 0.1);
+        assert_vec(m.points[0].point, Vec2::new(0.2, 0.95));
+
+        // Corner, closest point is (1, 1);
+        let m = collide(&square, &at(0.0, 0.0), &Shape::circle(0.5), &at(1.24, 1.32)).unwrap();
+        assert_vec(m.normal, Vec2::new(0.6, 0.8));
+        assert_near(m.points[0].depth, 0.1);
+
+        // Center inside the square;
+        let m = collide(&Shape::circle(0.5), &at(0.0, 0.8), &square, &at(0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec2::NEG_Y);
+        assert_near(m.points[0].depth, 0.7);
+
+        assert!(collide(&Shape::circle(0.5), &at(1.4, 1.4), &square, &at(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn polygon_polygon() {
+        let square = Shape::rectangle(Vec2::ONE);
+
+        // Resting face on face, the upper square is narrower;
+        let small = Shape::rectangle(Vec2::new(0.5, 0.5));
+        let m = collide(&square, &at(0.0, 0.0), &small, &at(0.8, 1.4)).unwrap();
+        assert_vec(m.normal, Vec2::Y);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.1);
+            assert_near(p.point.y, 0.95);
+        }
+        let xs: Vec<f32> = m.points.iter().map(|p| p.point.x).collect();
+        assert!(xs.iter().any(|x| (x - 0.3).abs() < 1e-3) && xs.iter().any(|x| (x - 1.0).abs() < 1e-3), "{xs:?}");
+
+        // Corner of a rotated square into the face of the other one;
+        let diamond = Isometry::new(Vec2::new(0.0, 2.3), std::f32::consts::FRAC_PI_4);
+        let m = collide(&square, &at(0.0, 0.0), &square, &diamond).unwrap();
+        assert_vec(m.normal, Vec2::Y);
+        assert_eq!(m.points.len(), 1);
+        assert_near(m.points[0].depth, 2.0f32.sqrt() + 1.0 - 2.3);
+
+        // Reference face on B, the normal still points from A to B;
+        let m = collide(&square, &diamond, &square, &at(0.0, 0.0)).unwrap();
+        assert_vec(m.normal, Vec2::NEG_Y);
+
+        assert!(collide(&square, &at(0.0, 0.0), &square, &at(2.1, 0.5)).is_none());
+    }
+
+    #[test]
+    fn capsules() {
+        let capsule = Shape::capsule(1.0, 0.25);
+        let lying = Isometry::new(Vec2::new(0.2, 1.2), std::f32::consts::FRAC_PI_2);
+
+        // Lying on a square: two points along the flat side;
+        let m = collide(&Shape::rectangle(Vec2::ONE), &at(0.0, 0.0), &capsule, &lying).unwrap();
+        assert_vec(m.normal, Vec2::Y);
+        assert_eq!(m.points.len(), 2);
+        for p in &m.points {
+            assert_near(p.depth, 0.05);
+        }
+
+        // End to end along their axis;
+        let m = collide(&capsule, &at(0.0, 0.0), &capsule, &at(0.0, 2.4)).unwrap();
+        assert_vec(m.normal, Vec2::Y);
+        assert_near(m.max_depth(), 0.1);
+
+        // Crossing cores;
+        let crossing = Isometry::new(Vec2::new(0.2, 0.5), std::f32::consts::FRAC_PI_2);
+        let m = collide(&capsule, &at(0.0, 0.0), &capsule, &crossing).unwrap();
+        assert!(m.max_depth() > 0.25);
+    }
+}
@@ -0,0 +1,156 @@
+use glam::Vec2;
+
+use super::body::BodyHandle;
+use super::shape::Isometry;
+
+/// Stable joint index inside the 2D physics world;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JointHandle(pub(super) usize);
+
+impl JointHandle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointKind {
+    /// Shared anchor point, free rotation;
+    Revolute,
+    /// Fixed relative angle, translation along the frame X axis only;
+    Prismatic,
+    /// No relative motion;
+    Weld,
+    /// Anchors kept within a distance range;
+    Distance,
+}
+
+/// Velocity motor driving the free axis of revolute && prismatic joints;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motor {
+    /// Target angular (rad/s) or linear (m/s) speed;
+    pub velocity: f32,
+    /// Max torque or force the motor may apply;
+    pub max_force: f32,
+}
+
+/// Constraint between two bodies, or a body && the world;
+/// Frames are given in world space && stored in body space once added to the world;
+/// Limits && motors measure body A relative to body B (or the world);
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub(super) kind: JointKind,
+    pub(super) body_a: BodyHandle,
+    /// None attaches to the world;
+    pub(super) body_b: Option<BodyHandle>,
+    pub(super) frame_a: Isometry,
+    pub(super) frame_b: Isometry,
+
+    /// Angle (revolute), translation (prismatic) or length (distance) range;
+    pub(super) limits: Option<(f32, f32)>,
+    pub(super) motor: Option<Motor>,
+    /// Let the connected bodies collide with each other;
+    pub(super) collisions: bool,
+
+    /// Accumulated impulse per constraint row, kept for warm starting;
+    pub(super) impulses: [f32; Joint::MAX_ROWS],
+}
+
+impl Joint {
+    pub(super) const MAX_ROWS: usize = 5;
+
+    fn new(kind: JointKind, body_a: BodyHandle, body_b: Option<BodyHandle>, frame_a: Isometry, frame_b: Isometry) -> Self {
+        Self {
+            kind,
+            body_a,
+            body_b,
+            frame_a,
+            frame_b,
+
+            limits: None,
+            motor: None,
+            collisions: false,
+
+            impulses: [0.0; Joint::MAX_ROWS],
+        }
+    }
+
+    /// Pin joint rotating about the anchor;
+    pub fn revolute(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec2) -> Self {
+        let frame = Isometry::from_position(anchor);
+        Self::new(JointKind::Revolute, body_a, body_b, frame, frame)
+    }
+
+    /// Piston like joint sliding along the world axis;
+    pub fn prismatic(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec2, axis: Vec2) -> Self {
+        let frame = Isometry::new(anchor, axis.to_angle());
+        Self::new(JointKind::Prismatic, body_a, body_b, frame, frame)
+    }
+
+    pub fn weld(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor: Vec2) -> Self {
+        let frame = Isometry::from_position(anchor);
+        Self::new(JointKind::Weld, body_a, body_b, frame, frame)
+    }
+
+    /// Rod between two world anchors, keeps their current distance unless limits are set;
+    pub fn distance(body_a: BodyHandle, body_b: Option<BodyHandle>, anchor_a: Vec2, anchor_b: Vec2) -> Self {
+        let length = anchor_a.distance(anchor_b);
+        Self::new(
+            JointKind::Distance, body_a, body_b,
+            Isometry::from_position(anchor_a), Isometry::from_position(anchor_b),
+        ).with_limits(length, length)
+    }
+
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some((min.min(max), max.max(min)));
+        self
+    }
+
+    pub fn with_motor(mut self, velocity: f32, max_force: f32) -> Self {
+        self.motor = Some(Motor { velocity, max_force: max_force.max(0.0) });
+        self
+    }
+
+    pub fn with_collisions(mut self, collisions: bool) -> Self {
+        self.collisions = collisions;
+        self
+    }
+
+    pub fn kind(&self) -> JointKind {
+        self.kind
+    }
+
+    pub fn bodies(&self) -> (BodyHandle, Option<BodyHandle>) {
+        (self.body_a, self.body_b)
+    }
+
+    /// Anchors in body space of A && B (or world space when B is the world);
+    pub fn local_anchors(&self) -> (Vec2, Vec2) {
+        (self.frame_a.position, self.frame_b.position)
+    }
+
+    pub fn limits(&self) -> Option<(f32, f32)> {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Option<(f32, f32)>) {
+        self.limits = limits;
+    }
+
+    pub fn motor(&self) -> Option<Motor> {
+        self.motor
+    }
+
+    pub fn set_motor(&mut self, motor: Option<Motor>) {
+        self.motor = motor;
+    }
+
+    pub fn collisions(&self) -> bool {
+        self.collisions
+    }
+
+    /// Connects the body to something;
+    pub(super) fn involves(&self, body: BodyHandle) -> bool {
+        self.body_a == body || self.body_b == Some(body)
+    }
+}
@@ -0,0 +1,72 @@
+use std::f32::consts::PI;
+
+use glam::Vec2;
+
+use super::shape::{Polygon, Shape};
+
+/// Mass, center of mass && moment of inertia about that center, in shape local space;
+/// Bodies rotate about their position, so off center shapes should be moved by `center`;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub mass: f32,
+    pub center: Vec2,
+    pub inertia: f32,
+}
+
+impl MassProperties {
+    pub const ZERO: Self = Self { mass: 0.0, center: Vec2::ZERO, inertia: 0.0 };
+
+    pub fn new(mass: f32, center: Vec2, inertia: f32) -> Self {
+        Self { mass, center, inertia }
+    }
+
+    pub fn circle(radius: f32, density: f32) -> Self {
+        let mass = density * PI * radius * radius;
+        Self::new(mass, Vec2::ZERO, 0.5 * mass * radius * radius)
+    }
+
+    /// Triangle fan around the first vertex;
+    pub fn polygon(polygon: &Polygon, density: f32) -> Self {
+        let points = polygon.points();
+        let origin = points[0];
+
+        let mut area = 0.0;
+        let mut center = Vec2::ZERO;
+        let mut inertia = 0.0;
+        for pair in points[1..].windows(2) {
+            let (e1, e2) = (pair[0] - origin, pair[1] - origin);
+            let cross = e1.perp_dot(e2);
+
+            area += 0.5 * cross;
+            center += 0.5 * cross * (e1 + e2) / 3.0;
+            inertia += cross / 12.0 * (e1.length_squared() + e1.dot(e2) + e2.length_squared());
+        }
+
+        let mass = density * area;
+        let center = center / area;
+        Self::new(mass, origin + center, density * inertia - mass * center.length_squared())
+    }
+
+    /// Y axis rectangle && two half disk caps;
+    pub fn capsule(half_height: f32, radius: f32, density: f32) -> Self {
+        let rectangle = density * 4.0 * radius * half_height;
+        let disk = density * PI * radius * radius;
+
+        // Caps sit at the rectangle ends, their centroids a further 4r / 3π out;
+        let offset = 4.0 * radius / (3.0 * PI);
+        let caps = disk * (0.5 * radius * radius + half_height * half_height + 2.0 * half_height * offset);
+        let inertia = rectangle * (radius * radius + half_height * half_height) / 3.0 + caps;
+
+        Self::new(rectangle + disk, Vec2::ZERO, inertia)
+    }
+}
+
+impl Shape {
+    pub fn mass_properties(&self, density: f32) -> MassProperties {
+        match self {
+            Shape::Circle { radius } => MassProperties::circle(*radius, density),
+            Shape::Polygon(polygon) => MassProperties::polygon(polygon, density),
+            Shape::Capsule { half_height, radius } => MassProperties::capsule(*half_height, *radius, density),
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::Vec2;
+
+pub use body::{BodyHandle, BodyType, RigidBody};
+pub use collider::Collider;
+pub use collision::{collide, ContactPoint, Manifold};
+pub use joint::{Joint, JointHandle, JointKind, Motor};
+pub use mass::MassProperties;
+pub use shape::{Aabb, Isometry, Polygon, Shape};
+pub use solver::SolverConfig;
+
+use solver::Solver;
+
+mod body;
+mod collider;
+mod collision;
+mod joint;
+mod mass;
+mod shape;
+mod solver;
+
+/// Two bodies, lower handle first;
+pub type BodyPair = (BodyHandle, BodyHandle);
+
+fn ordered(a: BodyHandle, b: BodyHandle) -> BodyPair {
+    match a < b {
+        true => (a, b),
+        false => (b, a)
+    }
+}
+
+/// Body pair with overlapping bounds && their contact manifold;
+/// The manifold is empty while shapes don't touch;
+#[derive(Debug, Clone)]
+pub struct Contact {
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    pub manifold: Manifold,
+
+    impulses: Vec<CachedImpulse>,
+}
+
+/// Accumulated impulse of a manifold point, reused to warm start the next step;
+#[derive(Debug, Clone, Copy)]
+struct CachedImpulse {
+    /// Point in body A space;
+    local_a: Vec2,
+    normal: f32,
+    tangent: f32,
+}
+
+impl Contact {
+    fn new((body_a, body_b): BodyPair) -> Self {
+        Self { body_a, body_b, manifold: Manifold::new(Vec2::ZERO), impulses: Vec::new() }
+    }
+
+    pub fn is_touching(&self) -> bool {
+        !self.manifold.points.is_empty()
+    }
+
+    /// Total normal impulse applied by the last step;
+    pub fn normal_impulse(&self) -> f32 {
+        self.impulses.iter().map(|i| i.normal).sum()
+    }
+}
+
+/// Planar physics world;
+/// Owns every 2D rigid body && steps them, bodies move in the XY plane && rotate about Z;
+pub struct World {
+    gravity: Vec2,
+    bodies: Vec<Option<RigidBody>>,
+    free: Vec<usize>,
+    joints: Vec<Option<Joint>>,
+    free_joints: Vec<usize>,
+    solver: SolverConfig,
+
+    pairs: BTreeMap<BodyPair, Contact>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        WorldBuilder::default().build()
+    }
+
+    /// Insert body && return its handle;
+    pub fn add_body(&mut self, body: RigidBody) -> BodyHandle {
+        match self.free.pop() {
+            Some(index) => {
+                self.bodies[index] = Some(body);
+                BodyHandle(index)
+            }
+            None => {
+                self.bodies.push(Some(body));
+                BodyHandle(self.bodies.len() - 1)
+            }
+        }
+    }
+
+    pub fn remove_body(&mut self, handle: BodyHandle) -> Option<RigidBody> {
+        let body = self.bodies.get_mut(handle.0)?.take();
+        if body.is_some() {
+            self.free.push(handle.0);
+            self.pairs.retain(|(a, b), _| *a != handle && *b != handle);
+
+            let attached: Vec<JointHandle> = self.joints()
+                .filter(|(_, joint)| joint.involves(handle))
+                .map(|(joint, _)| joint)
+                .collect();
+
+            for joint in attached {
+                self.remove_joint(joint);
+            }
+        }
+
+        body
+    }
+
+    /// Insert joint, its world frames are stored relative to the bodies' current poses;
+    pub fn add_joint(&mut self, mut joint: Joint) -> JointHandle {
+        assert_ne!(Some(joint.body_a), joint.body_b, "Joint needs two different bodies");
+
+        if let Some(body) = self.body(joint.body_a) {
+            joint.frame_a = body.isometry().inverse() * joint.frame_a;
+        }
+        if let Some(body) = joint.body_b.and_then(|b| self.body(b)) {
+            joint.frame_b = body.isometry().inverse() * joint.frame_b;
+        }
+
+        match self.free_joints.pop() {
+            Some(index) => {
+                self.joints[index] = Some(joint);
+                JointHandle(index)
+            }
+            None => {
+                self.joints.push(Some(joint));
+                JointHandle(self.joints.len() - 1)
+            }
+        }
+    }
+
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint> {
+        let joint = self.joints.get_mut(handle.0)?.take();
+        if joint.is_some() {
+            self.free_joints.push(handle.0);
+        }
+
+        joint
+    }
+
+    pub fn joint(&self, handle: JointHandle) -> Option<&Joint> {
+        self.joints.get(handle.0)?.as_ref()
+    }
+
+    pub fn joint_mut(&mut self, handle: JointHandle) -> Option<&mut Joint> {
+        self.joints.get_mut(handle.0)?.as_mut()
+    }
+
+    pub fn joints(&self) -> impl Iterator<Item=(JointHandle, &Joint)> {
+        self.joints.iter()
+            .enumerate()
+            .filter_map(|(i, j)| j.as_ref().map(|j| (JointHandle(i), j)))
+    }
+
+    /// World space anchors of a joint;
+    pub fn joint_anchors(&self, handle: JointHandle) -> Option<(Vec2, Vec2)> {
+        let joint = self.joint(handle)?;
+        let iso_a = self.body(joint.body_a)?.isometry();
+        let iso_b = match joint.body_b {
+            Some(b) => self.body(b)?.isometry(),
+            None => Isometry::IDENTITY
+        };
+
+        Some((iso_a.transform_point(joint.frame_a.position), iso_b.transform_point(joint.frame_b.position)))
+    }
+
+    pub fn body(&self, handle: BodyHandle) -> Option<&RigidBody> {
+        self.bodies.get(handle.0)?.as_ref()
+    }
+
+    pub fn body_mut(&mut self, handle: BodyHandle) -> Option<&mut RigidBody> {
+        self.bodies.get_mut(handle.0)?.as_mut()
+    }
+
+    /// Iterate over every alive body;
+    pub fn bodies(&self) -> impl Iterator<Item=(BodyHandle, &RigidBody)> {
+        self.bodies.iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.as_ref().map(|b| (BodyHandle(i), b)))
+    }
+
+    pub fn bodies_mut(&mut self) -> impl Iterator<Item=(BodyHandle, &mut RigidBody)> {
+        self.bodies.iter_mut()
+            .enumerate()
+            .filter_map(|(i, b)| b.as_mut().map(|b| (BodyHandle(i), b)))
+    }
+
+    pub fn body_count(&self) -> usize {
+        self.bodies.len() - self.free.len()
+    }
+
+    pub fn gravity(&self) -> Vec2 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vec2) {
+        self.gravity = gravity;
+    }
+
+    pub fn solver(&self) -> &SolverConfig {
+        &self.solver
+    }
+
+    /// Touching pairs found by the last step;
+    pub fn contacts(&self) -> impl Iterator<Item=&Contact> {
+        self.pairs.values().filter(|c| c.is_touching())
+    }
+
+    /// Advance simulation by dt seconds;
+    pub fn step(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        let gravity = self.gravity;
+        for (_, body) in self.bodies_mut() {
+            body.integrate_velocity(gravity, dt);
+        }
+
+        self.find_contacts();
+
+        let mut solver = Solver::new(&self.solver, &self.bodies, dt);
+        for (index, joint) in self.joints.iter().enumerate() {
+            if let Some(joint) = joint {
+                solver.add_joint(index, joint, &self.bodies);
+            }
+        }
+        for contact in self.pairs.values() {
+            solver.add_contact(contact, &self.bodies);
+        }
+
+        solver.solve();
+        solver.store_joint_impulses(&mut self.joints);
+        solver.store_impulses(&mut self.pairs);
+        solver.apply(&mut self.bodies);
+
+        for (_, body) in self.bodies_mut() {
+            body.integrate_position(dt);
+        }
+    }
+
+    /// Sort && sweep over the X axis, pairs with at least one dynamic body;
+    fn broad_phase(&self) -> BTreeSet<BodyPair> {
+        let mut boxes: Vec<(BodyHandle, &RigidBody, Aabb)> = self.bodies()
+            .filter_map(|(handle, body)| body.collider().map(|c| (handle, body, c.shape.aabb(&body.isometry()))))
+            .collect();
+        boxes.sort_by(|x, y| x.2.min.x.total_cmp(&y.2.min.x));
+
+        let mut pairs = BTreeSet::new();
+        for (i, (a, body_a, aabb_a)) in boxes.iter().enumerate() {
+            for (b, body_b, aabb_b) in boxes[i + 1..].iter().take_while(|other| other.2.min.x <= aabb_a.max.x) {
+                if (body_a.is_dynamic() || body_b.is_dynamic()) && aabb_a.overlaps(aabb_b) {
+                    pairs.insert(ordered(*a, *b));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Narrow phase over broad phase pairs, cached impulses survive while bounds overlap;
+    fn find_contacts(&mut self) {
+        let overlapping = self.broad_phase();
+        self.pairs.retain(|pair, _| overlapping.contains(pair));
+        for pair in overlapping {
+            self.pairs.entry(pair).or_insert_with(|| Contact::new(pair));
+        }
+
+        // Jointed bodies don't collide unless asked to;
+        let jointed: BTreeSet<BodyPair> = self.joints.iter()
+            .flatten()
+            .filter(|joint| !joint.collisions)
+            .filter_map(|joint| joint.body_b.map(|b| ordered(joint.body_a, b)))
+            .collect();
+
+        for (pair @ (handle_a, handle_b), contact) in self.pairs.iter_mut() {
+            let (Some(body_a), Some(body_b)) = (&self.bodies[handle_a.0], &self.bodies[handle_b.0]) else { continue };
+            let (Some(collider_a), Some(collider_b)) = (body_a.collider(), body_b.collider()) else { continue };
+
+            let manifold = match jointed.contains(pair) {
+                true => None,
+                false => collide(&collider_a.shape, &body_a.isometry(), &collider_b.shape, &body_b.isometry())
+            };
+
+            match manifold {
+                Some(manifold) => contact.manifold = manifold,
+                None => {
+                    contact.manifold.points.clear();
+                    contact.impulses.clear();
+                }
+            }
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 2D physics world settings;
+pub struct WorldBuilder {
+    gravity: Vec2,
+    solver: SolverConfig,
+}
+
+impl Default for WorldBuilder {
+    fn default() -> Self {
+        Self {
+            gravity: Vec2::new(0.0, -9.81),
+            solver: SolverConfig::default(),
+        }
+    }
+}
+
+impl WorldBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec2) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Solver passes over every contact per step, more is stiffer && slower;
+    pub fn with_velocity_iterations(mut self, iterations: u32) -> Self {
+        self.solver.velocity_iterations = iterations;
+        self
+    }
+
+    /// Split impulse passes per step;
+    pub fn with_position_iterations(mut self, iterations: u32) -> Self {
+        self.solver.position_iterations = iterations;
+        self
+    }
+
+    pub fn with_warm_starting(mut self, warm_starting: bool) -> Self {
+        self.solver.warm_starting = warm_starting;
+        self
+    }
+
+    /// Approach speed below which contacts don't bounce;
+    pub fn with_restitution_threshold(mut self, threshold: f32) -> Self {
+        self.solver.restitution_threshold = threshold.max(0.0);
+        self
+    }
+
+    pub fn build(self) -> World {
+        World {
+            gravity: self.gravity,
+            bodies: Vec::new(),
+            free: Vec::new(),
+            joints: Vec::new(),
+            free_joints: Vec::new(),
+            solver: self.solver,
+
+            pairs: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn ground(world: &mut World) -> BodyHandle {
+        world.add_body(RigidBody::fixed().with_collider(Collider::new(Shape::rectangle(Vec2::new(10.0, 0.5)))))
+    }
+
+    #[test]
+    fn boxes_stack_and_rest() {
+        let mut world = World::new();
+        ground(&mut world);
+
+        let boxes: Vec<BodyHandle> = (0..5)
+            .map(|i| world.add_body(
+                RigidBody::new()
+                    .with_position(Vec2::new(0.0, 1.0 + i as f32 * 1.0))
+                    .with_collider(Collider::new(Shape::rectangle(Vec2::splat(0.5))))
+                    .with_density(1.0),
+            ))
+            .collect();
+
+        for _ in 0..300 {
+            world.step(DT);
+        }
+
+        for (i, handle) in boxes.iter().enumerate() {
+            let body = world.body(*handle).unwrap();
+            assert!((body.position().y - (1.0 + i as f32)).abs() < 0.05, "box {i} at {:?}", body.position());
+            assert!(body.position().x.abs() < 0.01 && body.angle().abs() < 0.01, "box {i} at {:?}", body.position());
+            assert!(body.linear_velocity().length() < 0.05);
+        }
+    }
+
+    #[test]
+    fn circle_rolls_down_a_ramp() {
+        let mut world = World::new();
+        let slope = 0.3f32;
+        world.add_body(
+            RigidBody::fixed()
+                .with_angle(-slope)
+                .with_collider(Collider::new(Shape::rectangle(Vec2::new(20.0, 0.5)))),
+        );
+
+        let ball = world.add_body(
+            RigidBody::new()
+                .with_position(Vec2::new(-5.0 * slope.cos(), 5.0 * slope.sin() + 1.0 / slope.cos()))
+                .with_collider(Collider::new(Shape::circle(0.5)))
+                .with_density(1.0),
+        );
+
+        for _ in 0..60 {
+            world.step(DT);
+        }
+
+        // Rolling without slipping accelerates at g sin(θ) / (1 + I / m r²);
+        let body = world.body(ball).unwrap();
+        let expected = 9.81 * slope.sin() / 1.5;
+        assert!((body.linear_velocity().length() - expected).abs() < 0.1, "{:?}", body.linear_velocity());
+        assert!((body.angular_velocity() + expected / 0.5).abs() < 0.2, "{}", body.angular_velocity());
+    }
+
+    #[test]
+    fn pendulum_keeps_its_length() {
+        let mut world = World::new();
+        let bob = world.add_body(
+            RigidBody::new()
+                .with_position(Vec2::new(2.0, 0.0))
+                .with_collider(Collider::new(Shape::capsule(0.2, 0.1))),
+        );
+        world.add_joint(Joint::revolute(bob, None, Vec2::ZERO));
+
+        let mut lowest = 0.0f32;
+        for _ in 0..120 {
+            world.step(DT);
+            let position = world.body(bob).unwrap().position();
+            assert!((position.length() - 2.0).abs() < 0.02, "{position:?}");
+            lowest = lowest.min(position.y);
+        }
+
+        assert!(lowest < -1.95);
+    }
+
+    #[test]
+    fn motor_spins_wheel_within_limits() {
+        let mut world = WorldBuilder::new().with_gravity(Vec2::ZERO).build();
+        let wheel = world.add_body(RigidBody::new().with_collider(Collider::new(Shape::circle(1.0))).with_density(1.0));
+        let joint = world.add_joint(Joint::revolute(wheel, None, Vec2::ZERO).with_motor(2.0, 100.0));
+
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        assert!((world.body(wheel).unwrap().angular_velocity() - 2.0).abs() < 1e-3);
+
+        if let Some(joint) = world.joint_mut(joint) {
+            joint.set_limits(Some((-1.0, 1.5)));
+        }
+        for _ in 0..120 {
+            world.step(DT);
+        }
+        assert!((world.body(wheel).unwrap().angle() - 1.5).abs() < 0.05, "{}", world.body(wheel).unwrap().angle());
+    }
+}
@@ -0,0 +1,205 @@
+use glam::Vec2;
+
+/// Rigid transform in the plane: rotation, then translation;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Isometry {
+    pub position: Vec2,
+    /// Unit complex number, cos && sin of the angle;
+    pub rotation: Vec2,
+}
+
+impl Default for Isometry {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Isometry {
+    pub const IDENTITY: Self = Self { position: Vec2::ZERO, rotation: Vec2::X };
+
+    /// Counter clockwise angle in radians;
+    pub fn new(position: Vec2, angle: f32) -> Self {
+        Self { position, rotation: Vec2::from_angle(angle) }
+    }
+
+    pub fn from_position(position: Vec2) -> Self {
+        Self { position, rotation: Vec2::X }
+    }
+
+    /// Angle in (-π, π];
+    pub fn angle(&self) -> f32 {
+        self.rotation.to_angle()
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.transform_vector(point) + self.position
+    }
+
+    pub fn inverse_transform_point(&self, point: Vec2) -> Vec2 {
+        self.inverse_transform_vector(point - self.position)
+    }
+
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        self.rotation.rotate(vector)
+    }
+
+    pub fn inverse_transform_vector(&self, vector: Vec2) -> Vec2 {
+        self.inverse_rotation().rotate(vector)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.inverse_rotation();
+        Self { position: rotation.rotate(-self.position), rotation }
+    }
+
+    fn inverse_rotation(&self) -> Vec2 {
+        Vec2::new(self.rotation.x, -self.rotation.y)
+    }
+}
+
+/// Composition, `a * b` applies b first;
+impl std::ops::Mul for Isometry {
+    type Output = Isometry;
+
+    fn mul(self, other: Isometry) -> Isometry {
+        Isometry {
+            position: self.transform_point(other.position),
+            rotation: self.rotation.rotate(other.rotation).normalize(),
+        }
+    }
+}
+
+/// Axis aligned bounding box;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item=Vec2>) -> Self {
+        let mut min = Vec2::splat(f32::INFINITY);
+        let mut max = Vec2::splat(f32::NEG_INFINITY);
+        for point in points {
+            min = min.min(point);
+            max = max.max(point);
+        }
+
+        Self { min, max }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec2 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    pub fn merged(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    pub fn expanded(&self, margin: f32) -> Aabb {
+        Aabb::new(self.min - Vec2::splat(margin), self.max + Vec2::splat(margin))
+    }
+}
+
+/// Convex polygon;
+/// Counter clockwise vertices, edge i runs from vertex i to the next one;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    points: Vec<Vec2>,
+}
+
+impl Polygon {
+    /// Convex hull of the points, inner && collinear points are dropped;
+    pub fn new(points: impl IntoIterator<Item=Vec2>) -> Self {
+        let mut points: Vec<Vec2> = points.into_iter().collect();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points.dedup();
+
+        // Andrew's monotone chain, lower hull then upper hull;
+        let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() + 1);
+        for pass in [points.clone(), points.into_iter().rev().collect()] {
+            let start = hull.len();
+            for point in pass {
+                while hull.len() >= start + 2 {
+                    let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                    if (b - a).perp_dot(point - a) > f32::EPSILON {
+                        break;
+                    }
+                    hull.pop();
+                }
+                hull.push(point);
+            }
+            hull.pop();
+        }
+
+        assert!(hull.len() >= 3, "Polygon needs three points that are not on a line");
+        Self { points: hull }
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Outward normal of edge i;
+    pub fn normal(&self, i: usize) -> Vec2 {
+        let edge = self.points[(i + 1) % self.points.len()] - self.points[i];
+        Vec2::new(edge.y, -edge.x).normalize()
+    }
+}
+
+/// Collision geometry in body space;
+/// Every shape is a convex core of up to many points rounded by a radius;
+/// Capsules are aligned with the local Y axis;
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle { radius: f32 },
+    Polygon(Polygon),
+    Capsule { half_height: f32, radius: f32 },
+}
+
+impl Shape {
+    pub fn circle(radius: f32) -> Self {
+        Self::Circle { radius }
+    }
+
+    pub fn rectangle(half_extents: Vec2) -> Self {
+        let Vec2 { x, y } = half_extents;
+        Self::Polygon(Polygon::new([Vec2::new(-x, -y), Vec2::new(x, -y), Vec2::new(x, y), Vec2::new(-x, y)]))
+    }
+
+    /// Convex hull of the points;
+    pub fn polygon(points: impl IntoIterator<Item=Vec2>) -> Self {
+        Self::Polygon(Polygon::new(points))
+    }
+
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        Self::Capsule { half_height, radius }
+    }
+
+    /// Local core points && the rounding radius;
+    pub(super) fn core(&self) -> (Vec<Vec2>, f32) {
+        match self {
+            Shape::Circle { radius } => (vec![Vec2::ZERO], *radius),
+            Shape::Polygon(polygon) => (polygon.points.clone(), 0.0),
+            Shape::Capsule { half_height, radius } => (vec![Vec2::Y * -*half_height, Vec2::Y * *half_height], *radius),
+        }
+    }
+
+    /// World space bounds;
+    pub fn aabb(&self, iso: &Isometry) -> Aabb {
+        let (points, radius) = self.core();
+        Aabb::from_points(points.into_iter().map(|p| iso.transform_point(p))).expanded(radius)
+    }
+}
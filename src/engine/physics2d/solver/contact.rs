@@ -0,0 +1,167 @@
+use glam::Vec2;
+
+use super::{effective_mass, pair_mut, SolverConfig, Velocity};
+use super::super::body::RigidBody;
+use super::super::{BodyPair, CachedImpulse, Contact};
+
+/// Max drift of a cached point, in body space, to still warm start from it;
+const WARM_START_DISTANCE: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy)]
+struct ConstraintPoint {
+    /// Offsets from each center of mass;
+    r_a: Vec2,
+    r_b: Vec2,
+    local_a: Vec2,
+    depth: f32,
+
+    normal_mass: f32,
+    tangent_mass: f32,
+    /// Target separating speed from restitution;
+    velocity_bias: f32,
+
+    normal_impulse: f32,
+    tangent_impulse: f32,
+    pseudo_impulse: f32,
+}
+
+/// Non-penetration && friction constraints of one manifold;
+pub(super) struct ContactConstraint {
+    pub(super) pair: BodyPair,
+    a: usize,
+    b: usize,
+    normal: Vec2,
+    tangent: Vec2,
+    friction: f32,
+    points: Vec<ConstraintPoint>,
+}
+
+impl ContactConstraint {
+    pub(super) fn new(
+        contact: &Contact,
+        body_a: &RigidBody,
+        body_b: &RigidBody,
+        velocities: &[Velocity],
+        config: &SolverConfig,
+    ) -> Self {
+        let (a, b) = (contact.body_a.0, contact.body_b.0);
+        let (va, vb) = (&velocities[a], &velocities[b]);
+
+        let (collider_a, collider_b) = (body_a.collider(), body_b.collider());
+        let friction = match (collider_a, collider_b) {
+            (Some(ca), Some(cb)) => (ca.friction * cb.friction).sqrt(),
+            _ => 0.0
+        };
+        let restitution = match (collider_a, collider_b) {
+            (Some(ca), Some(cb)) => ca.restitution.max(cb.restitution),
+            _ => 0.0
+        };
+
+        let normal = contact.manifold.normal;
+        let tangent = normal.perp();
+
+        let iso_a = body_a.isometry();
+        let points = contact.manifold.points.iter()
+            .map(|point| {
+                let r_a = point.point - body_a.position;
+                let r_b = point.point - body_b.position;
+                let local_a = iso_a.inverse_transform_point(point.point);
+
+                let approach = (vb.at(r_b) - va.at(r_a)).dot(normal);
+                let velocity_bias = match approach < -config.restitution_threshold {
+                    true => -restitution * approach,
+                    false => 0.0
+                };
+
+                // Reuse the impulse of the closest cached point;
+                let cached = contact.impulses.iter()
+                    .filter(|c| c.local_a.distance_squared(local_a) < WARM_START_DISTANCE * WARM_START_DISTANCE)
+                    .min_by(|x, y| x.local_a.distance_squared(local_a).total_cmp(&y.local_a.distance_squared(local_a)));
+
+                let (normal_impulse, tangent_impulse) = match (config.warm_starting, cached) {
+                    (true, Some(c)) => (c.normal, c.tangent),
+                    _ => (0.0, 0.0)
+                };
+
+                ConstraintPoint {
+                    r_a,
+                    r_b,
+                    local_a,
+                    depth: point.depth,
+
+                    normal_mass: effective_mass(va, r_a, vb, r_b, normal),
+                    tangent_mass: effective_mass(va, r_a, vb, r_b, tangent),
+                    velocity_bias,
+
+                    normal_impulse,
+                    tangent_impulse,
+                    pseudo_impulse: 0.0,
+                }
+            })
+            .collect();
+
+        Self { pair: (contact.body_a, contact.body_b), a, b, normal, tangent, friction, points }
+    }
+
+    /// Apply last step's impulses before iterating;
+    pub(super) fn warm_start(&self, velocities: &mut [Velocity]) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+        for p in &self.points {
+            let impulse = self.normal * p.normal_impulse + self.tangent * p.tangent_impulse;
+            va.apply(-impulse, p.r_a);
+            vb.apply(impulse, p.r_b);
+        }
+    }
+
+    pub(super) fn solve_velocity(&mut self, velocities: &mut [Velocity]) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+
+        // Friction first, bounded by the current normal impulse;
+        for p in &mut self.points {
+            let max = self.friction * p.normal_impulse;
+            let dv = vb.at(p.r_b) - va.at(p.r_a);
+            let lambda = -dv.dot(self.tangent) * p.tangent_mass;
+            let new = (p.tangent_impulse + lambda).clamp(-max, max);
+            let impulse = self.tangent * (new - p.tangent_impulse);
+            p.tangent_impulse = new;
+
+            va.apply(-impulse, p.r_a);
+            vb.apply(impulse, p.r_b);
+        }
+
+        for p in &mut self.points {
+            let vn = (vb.at(p.r_b) - va.at(p.r_a)).dot(self.normal);
+            let lambda = p.normal_mass * (p.velocity_bias - vn);
+            let new = (p.normal_impulse + lambda).max(0.0);
+            let impulse = self.normal * (new - p.normal_impulse);
+            p.normal_impulse = new;
+
+            va.apply(-impulse, p.r_a);
+            vb.apply(impulse, p.r_b);
+        }
+    }
+
+    /// Split impulse: push apart with pseudo velocities only;
+    pub(super) fn solve_position(&mut self, velocities: &mut [Velocity], config: &SolverConfig, dt: f32) {
+        let (va, vb) = pair_mut(velocities, self.a, self.b);
+
+        for p in &mut self.points {
+            let vn = (vb.pseudo_at(p.r_b) - va.pseudo_at(p.r_a)).dot(self.normal);
+            let target = config.correction_factor / dt * (p.depth - config.slop).max(0.0);
+
+            let lambda = p.normal_mass * (target - vn);
+            let new = (p.pseudo_impulse + lambda).max(0.0);
+            let impulse = self.normal * (new - p.pseudo_impulse);
+            p.pseudo_impulse = new;
+
+            va.apply_pseudo(-impulse, p.r_a);
+            vb.apply_pseudo(impulse, p.r_b);
+        }
+    }
+
+    pub(super) fn impulses(&self) -> Vec<CachedImpulse> {
+        self.points.iter()
+            .map(|p| CachedImpulse { local_a: p.local_a, normal: p.normal_impulse, tangent: p.tangent_impulse })
+            .collect()
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use glam::Vec2;
+
+use super::body::RigidBody;
+use super::joint::Joint;
+use super::shape::Isometry;
+use super::{BodyPair, Contact};
+
+use contact::ContactConstraint;
+use joint::JointConstraint;
+
+mod contact;
+mod joint;
+
+/// Iterative constraint solver settings;
+/// Penetration is removed by split impulses, like the 3D world's default;
+#[derive(Debug, Clone, Copy)]
+pub struct SolverConfig {
+    pub(super) velocity_iterations: u32,
+    pub(super) position_iterations: u32,
+    pub(super) warm_starting: bool,
+    /// Approach speed below which contacts don't bounce;
+    pub(super) restitution_threshold: f32,
+    /// Fraction of the penetration removed per step;
+    pub(super) correction_factor: f32,
+    /// Penetration allowed without correction, keeps resting contacts stable;
+    pub(super) slop: f32,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            velocity_iterations: 8,
+            position_iterations: 3,
+            warm_starting: true,
+            restitution_threshold: 1.0,
+            correction_factor: 0.2,
+            slop: 0.005,
+        }
+    }
+}
+
+impl SolverConfig {
+    pub fn velocity_iterations(&self) -> u32 {
+        self.velocity_iterations
+    }
+
+    pub fn position_iterations(&self) -> u32 {
+        self.position_iterations
+    }
+}
+
+/// Solver copy of a body's velocities && inverse mass;
+#[derive(Debug, Clone, Copy)]
+struct Velocity {
+    linear: Vec2,
+    angular: f32,
+    pseudo_linear: Vec2,
+    pseudo_angular: f32,
+    inv_mass: f32,
+    inv_inertia: f32,
+}
+
+impl Velocity {
+    /// Immovable, stands for the world && empty body slots;
+    const FIXED: Self = Self {
+        linear: Vec2::ZERO,
+        angular: 0.0,
+        pseudo_linear: Vec2::ZERO,
+        pseudo_angular: 0.0,
+        inv_mass: 0.0,
+        inv_inertia: 0.0,
+    };
+
+    fn new(body: &RigidBody) -> Self {
+        Self {
+            linear: body.linear_velocity,
+            angular: body.angular_velocity,
+            pseudo_linear: Vec2::ZERO,
+            pseudo_angular: 0.0,
+            inv_mass: body.inv_mass,
+            inv_inertia: body.inv_inertia,
+        }
+    }
+
+    /// Velocity of a point at offset r from the center of mass;
+    fn at(&self, r: Vec2) -> Vec2 {
+        self.linear + r.perp() * self.angular
+    }
+
+    fn pseudo_at(&self, r: Vec2) -> Vec2 {
+        self.pseudo_linear + r.perp() * self.pseudo_angular
+    }
+
+    fn apply(&mut self, impulse: Vec2, r: Vec2) {
+        self.linear += impulse * self.inv_mass;
+        self.angular += self.inv_inertia * r.perp_dot(impulse);
+    }
+
+    fn apply_pseudo(&mut self, impulse: Vec2, r: Vec2) {
+        self.pseudo_linear += impulse * self.inv_mass;
+        self.pseudo_angular += self.inv_inertia * r.perp_dot(impulse);
+    }
+
+    /// Inverse of the mass seen along dir at offset r;
+    fn inv_mass_along(&self, r: Vec2, dir: Vec2) -> f32 {
+        let rn = r.perp_dot(dir);
+        self.inv_mass + self.inv_inertia * rn * rn
+    }
+}
+
+/// Effective mass of a two body constraint along dir;
+fn effective_mass(a: &Velocity, r_a: Vec2, b: &Velocity, r_b: Vec2, dir: Vec2) -> f32 {
+    let k = a.inv_mass_along(r_a, dir) + b.inv_mass_along(r_b, dir);
+    match k > f32::EPSILON {
+        true => 1.0 / k,
+        false => 0.0
+    }
+}
+
+/// Two distinct mutable velocities;
+fn pair_mut(velocities: &mut [Velocity], a: usize, b: usize) -> (&mut Velocity, &mut Velocity) {
+    assert_ne!(a, b);
+    match a < b {
+        true => {
+            let (low, high) = velocities.split_at_mut(b);
+            (&mut low[a], &mut high[0])
+        }
+        false => {
+            let (low, high) = velocities.split_at_mut(a);
+            (&mut high[0], &mut low[b])
+        }
+    }
+}
+
+/// Sequential impulse solver for a single step;
+/// Works on copies of body velocities, written back by `apply`;
+/// Joints && contacts are solved in the same iterations;
+pub(super) struct Solver<'a> {
+    config: &'a SolverConfig,
+    dt: f32,
+    /// One per body slot, then the world;
+    velocities: Vec<Velocity>,
+    contacts: Vec<ContactConstraint>,
+    joints: Vec<JointConstraint>,
+}
+
+impl<'a> Solver<'a> {
+    pub(super) fn new(config: &'a SolverConfig, bodies: &[Option<RigidBody>], dt: f32) -> Self {
+        let velocities = bodies.iter()
+            .map(|body| body.as_ref().map_or(Velocity::FIXED, Velocity::new))
+            .chain([Velocity::FIXED])
+            .collect();
+
+        Self { config, dt, velocities, contacts: Vec::new(), joints: Vec::new() }
+    }
+
+    pub(super) fn add_joint(&mut self, index: usize, joint: &Joint, bodies: &[Option<RigidBody>]) {
+        let Some(body_a) = &bodies[joint.body_a.0] else { return };
+        let (b, dynamic_b) = match joint.body_b {
+            Some(handle) => match &bodies[handle.0] {
+                Some(body_b) => ((handle.0, body_b.isometry()), body_b.is_dynamic()),
+                None => return
+            },
+            None => ((self.velocities.len() - 1, Isometry::IDENTITY), false)
+        };
+
+        if !body_a.is_dynamic() && !dynamic_b {
+            return;
+        }
+
+        let a = (joint.body_a.0, body_a.isometry());
+        self.joints.push(JointConstraint::new(index, joint, a, b, &self.velocities, self.config, self.dt));
+    }
+
+    pub(super) fn add_contact(&mut self, contact: &Contact, bodies: &[Option<RigidBody>]) {
+        if !contact.is_touching() {
+            return;
+        }
+
+        let (Some(body_a), Some(body_b)) = (&bodies[contact.body_a.0], &bodies[contact.body_b.0]) else { return };
+        self.contacts.push(ContactConstraint::new(contact, body_a, body_b, &self.velocities, self.config));
+    }
+
+    pub(super) fn solve(&mut self) {
+        if self.config.warm_starting {
+            for joint in &self.joints {
+                joint.warm_start(&mut self.velocities);
+            }
+            for contact in &self.contacts {
+                contact.warm_start(&mut self.velocities);
+            }
+        }
+
+        for _ in 0..self.config.velocity_iterations {
+            for joint in &mut self.joints {
+                joint.solve_velocity(&mut self.velocities);
+            }
+            for contact in &mut self.contacts {
+                contact.solve_velocity(&mut self.velocities);
+            }
+        }
+
+        for _ in 0..self.config.position_iterations {
+            for contact in &mut self.contacts {
+                contact.solve_position(&mut self.velocities, self.config, self.dt);
+            }
+        }
+    }
+
+    /// Cache accumulated impulses on their contacts for the next step;
+    pub(super) fn store_impulses(&self, pairs: &mut BTreeMap<BodyPair, Contact>) {
+        for constraint in &self.contacts {
+            if let Some(contact) = pairs.get_mut(&constraint.pair) {
+                contact.impulses = constraint.impulses();
+            }
+        }
+    }
+
+    pub(super) fn store_joint_impulses(&self, joints: &mut [Option<Joint>]) {
+        for constraint in &self.joints {
+            if let Some(joint) = &mut joints[constraint.index] {
+                joint.impulses = constraint.impulses();
+            }
+        }
+    }
+
+    /// Write velocities back && move bodies by their pseudo velocities;
+    pub(super) fn apply(&self, bodies: &mut [Option<RigidBody>]) {
+        for (slot, velocity) in bodies.iter_mut().zip(&self.velocities) {
+            let Some(body) = slot else { continue };
+            if !body.is_dynamic() {
+                continue;
+            }
+
+            body.linear_velocity = velocity.linear;
+            body.angular_velocity = velocity.angular;
+            body.displace(velocity.pseudo_linear, velocity.pseudo_angular, self.dt);
+        }
+    }
+}
@@ -3,13 +3,29 @@ use engine::EventHandler;
 
 use crate::engine::context::ContextBuilder;
 
+mod demos;
 mod engine;
 
+/// Starts the application, or the demo named by the first argument;
 fn main() {
-    let (context, event_loop) = ContextBuilder::new("SPE", "Delfi")
-        .build();
+    match std::env::args().nth(1).as_deref() {
+        Some("bridge") => run::<demos::Bridge>(demos::Bridge::context()),
+        Some("character") => run::<demos::Character>(demos::Character::context()),
+        Some("crane") => run::<demos::Crane>(demos::Crane::context()),
+        Some("curtain") => run::<demos::Curtain>(demos::Curtain::context()),
+        Some("flag") => run::<demos::Flag>(demos::Flag::context()),
+        Some("jelly") => run::<demos::Jelly>(demos::Jelly::context()),
+        Some("pendulums") => run::<demos::Pendulums>(demos::Pendulums::context()),
+        Some("pouring") => run::<demos::Pouring>(demos::Pouring::context()),
+        Some("tablecloth") => run::<demos::Tablecloth>(demos::Tablecloth::context()),
+        _ => run::<Application>(ContextBuilder::new("SPE", "Delfi")),
+    }
+}
+
+fn run<Handler: EventHandler>(builder: ContextBuilder) {
+    let (context, event_loop) = builder.build();
 
-    let worker = engine::Worker::<Application>::new(context);
+    let worker = engine::Worker::<Handler>::new(context);
     worker.run(event_loop);
 }
 